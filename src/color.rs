@@ -0,0 +1,144 @@
+//! Colored output. Whether to use color is decided once, from `--color`,
+//! `$NO_COLOR` and whether stdout is a terminal, and then handed to whatever
+//! formats output as a `bool`.
+
+/// The `--color` option.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color if stdout is a terminal and `$NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color output going to a terminal if `is_terminal`. `env` is
+    /// the environment lookup, see `platform::process_env`.
+    pub fn enabled(self, is_terminal: bool, env: impl Fn(&str) -> Option<String>) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            // https://no-color.org: set and not empty disables color.
+            ColorChoice::Auto => is_terminal && env("NO_COLOR").is_none_or(|v| v.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+/// `text` in `color` if `enabled`, as is otherwise.
+pub fn paint(enabled: bool, color: Color, text: &str) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+    let code = match color {
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Cyan => 36,
+    };
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+/// How many characters of `text` show, leaving out escape sequences like
+/// those of `paint`.
+pub fn width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => skip_escape(&mut chars, &mut String::new()),
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+/// `text` cut down to `max` characters as `width` counts them, ending in an
+/// ellipsis when anything was cut. Escape sequences are kept whole, and if
+/// there are any, colors are reset after the ellipsis.
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut escaped = false;
+    let mut shown = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            skip_escape(&mut chars, &mut out);
+            escaped = true;
+        } else if shown + 1 < max {
+            out.push(c);
+            shown += 1;
+        }
+    }
+    if max > 0 {
+        out.push('…');
+    }
+    if escaped {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// Moves `chars`, which are just past an escape character, past the rest of
+/// its sequence, copying that to `out`. Only `ESC [ ... final` sequences are
+/// more than the escape character itself.
+fn skip_escape(chars: &mut std::str::Chars, out: &mut String) {
+    if !chars.as_str().starts_with('[') {
+        return;
+    }
+    out.extend(chars.next());
+    for c in chars.by_ref() {
+        out.push(c);
+        if ('\x40'..='\x7e').contains(&c) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_enabled() {
+        let unset = |_: &str| None;
+        let no_color = |k: &str| (k == "NO_COLOR").then(|| String::from("1"));
+
+        assert!(!ColorChoice::Never.enabled(true, unset));
+        assert!(ColorChoice::Always.enabled(false, no_color));
+        assert!(ColorChoice::Auto.enabled(true, unset));
+        assert!(!ColorChoice::Auto.enabled(false, unset));
+        assert!(!ColorChoice::Auto.enabled(true, no_color));
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint(true, Color::Red, "[fail]"), "\x1b[31m[fail]\x1b[0m");
+        assert_eq!(paint(false, Color::Red, "[fail]"), "[fail]");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Caches", 6), "Caches");
+        assert_eq!(truncate("Caches, revisited", 8), "Caches,…");
+        assert_eq!(truncate("Caches", 1), "…");
+        assert_eq!(truncate("Caches", 0), "");
+
+        let red = paint(true, Color::Red, "Caches, revisited");
+        assert_eq!(width(&red), 17);
+        assert_eq!(truncate(&red, 8), "\x1b[31mCaches,\x1b[0m…\x1b[0m");
+        assert_eq!(truncate(&red, 17), red);
+    }
+}
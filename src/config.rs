@@ -0,0 +1,2026 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use chrono::format::{Item, StrftimeItems};
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::git;
+use crate::lifecycle::Transitions;
+use crate::platform;
+
+/// Why reading or changing the configuration failed. The CLI turns these
+/// into `anyhow` errors; matching on them is for code that wants to react to
+/// a particular failure.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// There is no config file to use, or no place for one.
+    NotFound(String),
+    /// A config file couldn't be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A config file isn't valid TOML or JSON, or doesn't fit `Config`.
+    Parse {
+        path: PathBuf,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// No configuration key has this name, see `KEYS`.
+    UnknownKey(String),
+    /// A value doesn't fit the key it's meant for; says why.
+    InvalidValue(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::NotFound(message) | ConfigError::InvalidValue(message) => {
+                f.write_str(message)
+            }
+            ConfigError::Io { path, .. } => write!(
+                f,
+                "Unexpected error when reading config file from {}",
+                path.display()
+            ),
+            ConfigError::Parse { path, .. } => {
+                write!(f, "Failed to parse config file {}", path.display())
+            }
+            ConfigError::UnknownKey(name) => write!(
+                f,
+                "Unknown configuration key '{}', known keys: {}",
+                name,
+                KEYS.iter().map(|k| k.name).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Fails a setter or validation with `ConfigError::InvalidValue`, formatted
+/// like `bail!`.
+macro_rules! invalid {
+    ($($arg:tt)*) => {
+        return Err(ConfigError::InvalidValue(format!($($arg)*)))
+    };
+}
+
+/// Name of the per-repository config file, living in the root of the
+/// repository's working directory.
+pub const LOCAL_CONFIG_FILE: &str = ".rfcs.toml";
+
+/// Name of the directory of config fragments next to the global config file,
+/// see `read_config_dir`.
+pub const CONFIG_DIR: &str = "config.d";
+
+pub const DEFAULT_CLONE_TIMEOUT_SECS: u64 = 300;
+
+pub const DEFAULT_SLUG_MAX_LENGTH: usize = 60;
+
+/// How dates are written without `date_format` in the config, like
+/// 2024-02-29.
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// What `numbering.separator` may be: characters that are safe in both file
+/// and branch names, and not part of a slug's words.
+pub const SEPARATORS: &[char] = &['-', '_', '+'];
+
+/// The schema version of config files written by this version of `rfcs`.
+/// Bumping it means adding a step to `MIGRATIONS`.
+pub const CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Config {
+    /// The schema version the file was written with, see `migrate`. Files
+    /// from before versioning don't have one, which counts as version 0.
+    pub version: Option<u32>,
+    pub repo: Option<Repo>,
+    pub git: Option<Git>,
+    /// The directory RFCs live in, relative to the root of the repository.
+    /// Without it, the whole repository is searched.
+    pub rfc_dir: Option<PathBuf>,
+    /// The directories RFCs live in when there's more than one, like one per
+    /// service in a monorepo. Takes the place of `rfc_dir`; new RFCs go into
+    /// the first one unless `create --dir` names another.
+    pub rfc_dirs: Option<Vec<PathBuf>>,
+    /// Which status an RFC may move to from which, see `lifecycle`. Can only
+    /// be set in the config file, as a table of status to list of statuses.
+    pub transitions: Option<Transitions>,
+    /// The command `rfcs edit` opens RFCs with, instead of `$VISUAL` or
+    /// `$EDITOR`. May include arguments, like `code --wait`.
+    pub editor: Option<String>,
+    /// The directory templates for new RFCs live in, relative to the root of
+    /// the repository. Defaults to `template::DEFAULT_DIR`.
+    pub templates_dir: Option<PathBuf>,
+    /// Commands to run after `rfcs` did something, like `hooks.post_create`.
+    pub hooks: Option<Hooks>,
+    /// The directories `rfcs promote` files RFCs into by status, like
+    /// `status_dirs.accepted`.
+    pub status_dirs: Option<StatusDirs>,
+    /// How many characters of the title the branch and file names of new
+    /// RFCs keep at most. Defaults to `DEFAULT_SLUG_MAX_LENGTH`.
+    pub slug_max_length: Option<usize>,
+    /// Whether files without a number in their name count as RFCs when their
+    /// front matter has a `number:`. Defaults to false.
+    pub number_in_front_matter: Option<bool>,
+    pub numbering: Option<Numbering>,
+    /// The directory drafts that don't have a number yet live in, relative
+    /// to the root of the repository, see `list --include-unnumbered`.
+    pub drafts_dir: Option<PathBuf>,
+    /// What a relative `templates_dir` and hook programs given as relative
+    /// paths are relative to. Defaults to the repository.
+    pub paths_relative_to: Option<PathBase>,
+    /// Whether commands that ask for confirmation go ahead when stdin isn't
+    /// a terminal and there's nobody to ask. Defaults to not going ahead.
+    pub non_tty: Option<NonTtyPolicy>,
+    /// How `{{date}}` in templates and dates `rfcs touch` writes look, as a
+    /// chrono format string. Defaults to `DEFAULT_DATE_FORMAT`.
+    pub date_format: Option<String>,
+    /// How many directories deep RFCs are looked for below each RFC
+    /// directory, 1 being only the files right in it. Defaults to no limit.
+    pub max_depth: Option<usize>,
+    /// Which extension wins when an RFC is there in several formats, like
+    /// `042-foo.md` and `042-foo.org`, first ones first. The others are left
+    /// out, as if they weren't there. Defaults to showing all of them.
+    pub preferred_extensions: Option<Vec<String>>,
+}
+
+/// What relative paths in the config are relative to, see
+/// `Config::paths_relative_to`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathBase {
+    /// The root of the RFC repository, wherever it's checked out.
+    #[default]
+    Repo,
+    /// The directory of the global config file.
+    Config,
+}
+
+impl PathBase {
+    fn name(self) -> &'static str {
+        match self {
+            PathBase::Repo => "repo",
+            PathBase::Config => "config",
+        }
+    }
+
+    /// The directory this stands for, with the repository at `repo`.
+    pub fn dir(self, repo: &Path) -> Result<PathBuf, ConfigError> {
+        match self {
+            PathBase::Repo => Ok(repo.to_path_buf()),
+            PathBase::Config => Ok(config_path()?
+                .parent()
+                .expect("Config path must have parent")
+                .to_path_buf()),
+        }
+    }
+}
+
+/// What confirmations do without a terminal to ask on, see
+/// `Config::non_tty`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NonTtyPolicy {
+    /// Answer no, so that nothing is deleted or merged unasked.
+    #[default]
+    Deny,
+    /// Answer yes, as if `--yes` was given.
+    Allow,
+}
+
+impl NonTtyPolicy {
+    fn name(self) -> &'static str {
+        match self {
+            NonTtyPolicy::Deny => "deny",
+            NonTtyPolicy::Allow => "allow",
+        }
+    }
+}
+
+/// How the slugs of new RFCs are cased, see `Numbering::case`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugCase {
+    /// As the title has it: `042-Caches-revisited`.
+    #[default]
+    Keep,
+    /// Lowercased: `042-caches-revisited`.
+    Lower,
+    /// Lowercased, with a single dash for anything that isn't a letter or
+    /// digit: `042-caches-v2-revisited` for "Caches (v2), revisited".
+    Kebab,
+}
+
+impl SlugCase {
+    fn name(self) -> &'static str {
+        match self {
+            SlugCase::Keep => "keep",
+            SlugCase::Lower => "lower",
+            SlugCase::Kebab => "kebab",
+        }
+    }
+}
+
+/// Where RFC numbers come from.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Numbering {
+    /// What goes between the number and the slug in the branch and file
+    /// names of new RFCs, one of `SEPARATORS`. Defaults to `-`.
+    pub separator: Option<char>,
+    /// Whether each of `rfc_dirs` is numbered on its own, rather than all of
+    /// them together. Defaults to false.
+    pub per_dir: Option<bool>,
+    /// How the slug after the number is cased. Defaults to keeping the case
+    /// of the title.
+    pub case: Option<SlugCase>,
+    /// The regex RFC numbers are found in file and branch names with, with
+    /// the number in a group named `rfc_number`. Defaults to the first three
+    /// or more digits.
+    pub pattern: Option<String>,
+}
+
+/// Commands run after `rfcs` did something, see `hook`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Hooks {
+    /// Run after `rfcs create` created an RFC.
+    pub post_create: Option<String>,
+}
+
+/// Where `rfcs promote` moves RFCs reaching a status, relative to the RFC
+/// directory they're in, so that the directories show where each RFC is at.
+/// Without one, RFCs stay where they are.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StatusDirs {
+    pub accepted: Option<PathBuf>,
+    pub rejected: Option<PathBuf>,
+}
+
+impl StatusDirs {
+    /// The directory RFCs moving to `status` go into, if they move.
+    pub fn get(&self, status: &str) -> Option<&PathBuf> {
+        match status {
+            "accepted" => self.accepted.as_ref(),
+            "rejected" => self.rejected.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// The local checkout of the RFC repository.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Repo {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Git {
+    pub url: Option<String>,
+    /// Access token for cloning private repositories over HTTPS.
+    pub token: Option<String>,
+    /// The branch new RFC branches are based on. Without it, the branch
+    /// `origin/HEAD` points at, then `main` and then `master` are tried.
+    pub default_branch: Option<String>,
+    /// How long cloning `url` may take before it's given up on. Defaults to
+    /// `DEFAULT_CLONE_TIMEOUT_SECS`.
+    pub clone_timeout_secs: Option<u64>,
+    /// Tags named this followed by a number, e.g. `rfc-042` for `rfc-`, count
+    /// towards the next RFC number. Without it, tags don't count.
+    pub tag_prefix: Option<String>,
+    /// Whether, with neither `repo.path` nor `url` set, the repository
+    /// containing the current directory is used. Defaults to true.
+    pub discover: Option<bool>,
+    /// Whether cloning `url` fetches only the latest commit of the default
+    /// branch, for large repositories. Defaults to false.
+    pub shallow: Option<bool>,
+    /// The directory `url` is cloned into, as its `rfcs` subdirectory, when
+    /// neither `repo` nor an earlier clone is there. Defaults to the
+    /// directory of the global config file.
+    pub clone_dir: Option<PathBuf>,
+    /// Whether `url` is cloned when there's no clone yet. Without it, the
+    /// `git clone` command to run instead is printed. Defaults to true.
+    pub auto_clone: Option<bool>,
+}
+
+impl Config {
+    /// Layers `other` on top of `self`: every value set in `other` wins, every
+    /// value it leaves unset falls through to `self`.
+    pub fn merge(self, other: Config) -> Config {
+        Config {
+            version: other.version.or(self.version),
+            repo: match (self.repo, other.repo) {
+                (Some(base), Some(over)) => Some(Repo {
+                    path: over.path.or(base.path),
+                }),
+                (base, over) => over.or(base),
+            },
+            git: match (self.git, other.git) {
+                (Some(base), Some(over)) => Some(base.merge(over)),
+                (base, over) => over.or(base),
+            },
+            rfc_dir: other.rfc_dir.or(self.rfc_dir),
+            rfc_dirs: other.rfc_dirs.or(self.rfc_dirs),
+            transitions: other.transitions.or(self.transitions),
+            editor: other.editor.or(self.editor),
+            templates_dir: other.templates_dir.or(self.templates_dir),
+            slug_max_length: other.slug_max_length.or(self.slug_max_length),
+            number_in_front_matter: other.number_in_front_matter.or(self.number_in_front_matter),
+            drafts_dir: other.drafts_dir.or(self.drafts_dir),
+            paths_relative_to: other.paths_relative_to.or(self.paths_relative_to),
+            non_tty: other.non_tty.or(self.non_tty),
+            date_format: other.date_format.or(self.date_format),
+            max_depth: other.max_depth.or(self.max_depth),
+            preferred_extensions: other.preferred_extensions.or(self.preferred_extensions),
+            numbering: match (self.numbering, other.numbering) {
+                (Some(base), Some(over)) => Some(Numbering {
+                    separator: over.separator.or(base.separator),
+                    per_dir: over.per_dir.or(base.per_dir),
+                    case: over.case.or(base.case),
+                    pattern: over.pattern.or(base.pattern),
+                }),
+                (base, over) => over.or(base),
+            },
+            hooks: match (self.hooks, other.hooks) {
+                (Some(base), Some(over)) => Some(Hooks {
+                    post_create: over.post_create.or(base.post_create),
+                }),
+                (base, over) => over.or(base),
+            },
+            status_dirs: match (self.status_dirs, other.status_dirs) {
+                (Some(base), Some(over)) => Some(StatusDirs {
+                    accepted: over.accepted.or(base.accepted),
+                    rejected: over.rejected.or(base.rejected),
+                }),
+                (base, over) => over.or(base),
+            },
+        }
+    }
+}
+
+impl Config {
+    /// The `numbering.separator`, failing if the config file has one that
+    /// isn't in `SEPARATORS`.
+    pub fn separator(&self) -> Result<char, ConfigError> {
+        match self.numbering.as_ref().and_then(|n| n.separator) {
+            None => Ok('-'),
+            Some(c) => check_separator(c),
+        }
+    }
+
+    /// The compiled `numbering.pattern`, failing if the config file has one
+    /// that `check_pattern` rejects.
+    pub fn pattern(&self) -> Result<Option<Regex>, ConfigError> {
+        match self.numbering.as_ref().and_then(|n| n.pattern.as_deref()) {
+            None => Ok(None),
+            Some(pattern) => check_pattern(pattern).map(Some),
+        }
+    }
+
+    /// The `date_format`, failing if the config file has one that
+    /// chrono can't write dates in.
+    pub fn date_format(&self) -> Result<String, ConfigError> {
+        match &self.date_format {
+            None => Ok(DEFAULT_DATE_FORMAT.to_string()),
+            Some(format) => check_date_format(format),
+        }
+    }
+
+    /// The directories RFCs live in: `rfc_dirs`, or else just `rfc_dir`, or
+    /// else the root of the repository, as an empty path.
+    pub fn rfc_dirs(&self) -> Vec<PathBuf> {
+        match &self.rfc_dirs {
+            Some(dirs) if !dirs.is_empty() => dirs.clone(),
+            _ => vec![self.rfc_dir.clone().unwrap_or_default()],
+        }
+    }
+
+    /// Whether each of `rfc_dirs` is numbered on its own, see
+    /// `Numbering::per_dir`.
+    pub fn per_dir_numbering(&self) -> bool {
+        self.numbering
+            .as_ref()
+            .and_then(|n| n.per_dir)
+            .unwrap_or(false)
+    }
+
+    pub fn slug_case(&self) -> SlugCase {
+        self.numbering
+            .as_ref()
+            .and_then(|n| n.case)
+            .unwrap_or_default()
+    }
+}
+
+fn check_date_format(format: &str) -> Result<String, ConfigError> {
+    let items = StrftimeItems::new(format);
+    if format.is_empty() || items.clone().any(|item| item == Item::Error) {
+        invalid!(
+            "'{}' is not a date format: see chrono's strftime docs for the specifiers.",
+            format
+        )
+    }
+    Ok(format.to_string())
+}
+
+/// `pattern` compiled, if it's a regex with an `rfc_number` group.
+pub fn check_pattern(pattern: &str) -> Result<Regex, ConfigError> {
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(e) => invalid!("'{}' is not a regex: {}", pattern, e),
+    };
+    if !regex.capture_names().any(|name| name == Some("rfc_number")) {
+        invalid!(
+            "'{}' has no group named rfc_number to take the number from, like (?<rfc_number>\\d+).",
+            pattern
+        )
+    }
+    Ok(regex)
+}
+
+fn check_separator(c: char) -> Result<char, ConfigError> {
+    if !SEPARATORS.contains(&c) {
+        invalid!(
+            "'{}' can't separate the number from the title, use one of {}.",
+            c,
+            SEPARATORS.iter().collect::<String>()
+        )
+    }
+    Ok(c)
+}
+
+impl Git {
+    pub fn clone_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.clone_timeout_secs
+                .unwrap_or(DEFAULT_CLONE_TIMEOUT_SECS),
+        )
+    }
+
+    /// The access token to authenticate with: `$RFCS_TOKEN` if set, otherwise
+    /// `git.token`.
+    pub fn effective_token(&self) -> Option<String> {
+        platform::process_env("RFCS_TOKEN").or_else(|| self.token.clone())
+    }
+
+    fn merge(self, other: Git) -> Git {
+        Git {
+            url: other.url.or(self.url),
+            token: other.token.or(self.token),
+            default_branch: other.default_branch.or(self.default_branch),
+            clone_timeout_secs: other.clone_timeout_secs.or(self.clone_timeout_secs),
+            tag_prefix: other.tag_prefix.or(self.tag_prefix),
+            discover: other.discover.or(self.discover),
+            shallow: other.shallow.or(self.shallow),
+            clone_dir: other.clone_dir.or(self.clone_dir),
+            auto_clone: other.auto_clone.or(self.auto_clone),
+        }
+    }
+}
+
+/// A key that can be set with `rfcs configure`, along with how to read and
+/// write it. Adding a configuration key means adding an entry to `KEYS`.
+pub struct ConfigKey {
+    pub name: &'static str,
+    /// Validates `value` and stores it in the config.
+    pub set: fn(&mut Config, &str) -> Result<(), ConfigError>,
+    pub get: fn(&Config) -> Option<String>,
+    /// Whether the value must be kept out of output, like a token.
+    pub secret: bool,
+}
+
+pub const KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        name: "git.url",
+        set: |c, v| {
+            git_mut(c).url = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.git.as_ref()?.url.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "repo.path",
+        set: |c, v| {
+            if v.is_empty() {
+                invalid!(
+                    "Was not able to convert given value '{}' into a file path, \
+                     please supply a valid path.",
+                    v
+                )
+            }
+            c.repo.get_or_insert_with(Repo::default).path = Some(PathBuf::from(v));
+            Ok(())
+        },
+        get: |c| Some(c.repo.as_ref()?.path.as_ref()?.display().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.token",
+        set: |c, v| {
+            git_mut(c).token = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.git.as_ref()?.token.clone(),
+        secret: true,
+    },
+    ConfigKey {
+        name: "git.default_branch",
+        set: |c, v| {
+            if !git2::Branch::name_is_valid(v).unwrap_or(false) {
+                invalid!("'{}' is not a valid git branch name.", v)
+            }
+            git_mut(c).default_branch = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.git.as_ref()?.default_branch.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.clone_timeout_secs",
+        set: |c, v| {
+            match v.parse::<u64>() {
+                Ok(secs) if secs > 0 => git_mut(c).clone_timeout_secs = Some(secs),
+                _ => invalid!("'{}' is not a positive number of seconds.", v),
+            }
+            Ok(())
+        },
+        get: |c| Some(c.git.as_ref()?.clone_timeout_secs?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.tag_prefix",
+        set: |c, v| {
+            git_mut(c).tag_prefix = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.git.as_ref()?.tag_prefix.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.discover",
+        set: |c, v| {
+            git_mut(c).discover = Some(parse_bool(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.git.as_ref()?.discover?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.shallow",
+        set: |c, v| {
+            git_mut(c).shallow = Some(parse_bool(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.git.as_ref()?.shallow?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.clone_dir",
+        set: |c, v| {
+            if v.is_empty() {
+                invalid!("'{}' is not a directory, please supply a path.", v)
+            }
+            git_mut(c).clone_dir = Some(PathBuf::from(v));
+            Ok(())
+        },
+        get: |c| Some(c.git.as_ref()?.clone_dir.as_ref()?.display().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "git.auto_clone",
+        set: |c, v| {
+            git_mut(c).auto_clone = Some(parse_bool(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.git.as_ref()?.auto_clone?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "rfc_dir",
+        set: |c, v| {
+            c.rfc_dir = Some(dir_in_repo(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.rfc_dir.as_ref()?.display().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "rfc_dirs",
+        set: |c, v| {
+            let dirs = v
+                .split(',')
+                .map(|dir| dir_in_repo(dir.trim()))
+                .collect::<Result<Vec<_>, _>>()?;
+            c.rfc_dirs = Some(dirs);
+            Ok(())
+        },
+        get: |c| {
+            let dirs: Vec<String> = c
+                .rfc_dirs
+                .as_ref()?
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect();
+            Some(dirs.join(","))
+        },
+        secret: false,
+    },
+    ConfigKey {
+        name: "editor",
+        set: |c, v| {
+            if platform::split_command(v).is_empty() {
+                invalid!("'{}' is not a command to run.", v)
+            }
+            c.editor = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.editor.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "templates_dir",
+        set: |c, v| {
+            c.templates_dir = Some(dir_in_repo(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.templates_dir.as_ref()?.display().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "drafts_dir",
+        set: |c, v| {
+            c.drafts_dir = Some(dir_in_repo(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.drafts_dir.as_ref()?.display().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "paths_relative_to",
+        set: |c, v| {
+            c.paths_relative_to = Some(match v {
+                "repo" => PathBase::Repo,
+                "config" => PathBase::Config,
+                _ => invalid!(
+                    "'{}' is not something paths can be relative to, use repo or config.",
+                    v
+                ),
+            });
+            Ok(())
+        },
+        get: |c| Some(c.paths_relative_to?.name().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "non_tty",
+        set: |c, v| {
+            c.non_tty = Some(match v {
+                "deny" => NonTtyPolicy::Deny,
+                "allow" => NonTtyPolicy::Allow,
+                _ => invalid!(
+                    "'{}' is not a policy for when there's no terminal, use deny or allow.",
+                    v
+                ),
+            });
+            Ok(())
+        },
+        get: |c| Some(c.non_tty?.name().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "date_format",
+        set: |c, v| {
+            c.date_format = Some(check_date_format(v)?);
+            Ok(())
+        },
+        get: |c| c.date_format.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "max_depth",
+        set: |c, v| {
+            match v.parse::<usize>() {
+                Ok(depth) if depth > 0 => c.max_depth = Some(depth),
+                _ => invalid!("'{}' is not a positive depth.", v),
+            }
+            Ok(())
+        },
+        get: |c| Some(c.max_depth?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "preferred_extensions",
+        set: |c, v| {
+            let mut extensions = vec![];
+            for extension in v.split(',').map(|e| e.trim().trim_start_matches('.')) {
+                if extension.is_empty() || extension.contains(['.', '/']) {
+                    invalid!("'{}' is not a list of file extensions.", v)
+                }
+                extensions.push(extension.to_lowercase());
+            }
+            c.preferred_extensions = Some(extensions);
+            Ok(())
+        },
+        get: |c| Some(c.preferred_extensions.as_ref()?.join(",")),
+        secret: false,
+    },
+    ConfigKey {
+        name: "slug_max_length",
+        set: |c, v| {
+            match v.parse::<usize>() {
+                Ok(length) if length > 0 => c.slug_max_length = Some(length),
+                _ => invalid!("'{}' is not a positive number of characters.", v),
+            }
+            Ok(())
+        },
+        get: |c| Some(c.slug_max_length?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "number_in_front_matter",
+        set: |c, v| {
+            c.number_in_front_matter = Some(parse_bool(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.number_in_front_matter?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "numbering.separator",
+        set: |c, v| {
+            let mut chars = v.chars();
+            let separator = match (chars.next(), chars.next()) {
+                (Some(c), None) => check_separator(c)?,
+                _ => invalid!("'{}' is not a single character.", v),
+            };
+            c.numbering.get_or_insert_with(Numbering::default).separator = Some(separator);
+            Ok(())
+        },
+        get: |c| Some(c.numbering.as_ref()?.separator?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "numbering.pattern",
+        set: |c, v| {
+            check_pattern(v)?;
+            c.numbering.get_or_insert_with(Numbering::default).pattern = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.numbering.as_ref()?.pattern.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "numbering.per_dir",
+        set: |c, v| {
+            c.numbering.get_or_insert_with(Numbering::default).per_dir = Some(parse_bool(v)?);
+            Ok(())
+        },
+        get: |c| Some(c.numbering.as_ref()?.per_dir?.to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "numbering.case",
+        set: |c, v| {
+            let case = match v {
+                "keep" => SlugCase::Keep,
+                "lower" => SlugCase::Lower,
+                "kebab" => SlugCase::Kebab,
+                _ => invalid!("'{}' is not a case for slugs, use keep, lower or kebab.", v),
+            };
+            c.numbering.get_or_insert_with(Numbering::default).case = Some(case);
+            Ok(())
+        },
+        get: |c| Some(c.numbering.as_ref()?.case?.name().to_string()),
+        secret: false,
+    },
+    ConfigKey {
+        name: "hooks.post_create",
+        set: |c, v| {
+            if platform::split_command(v).is_empty() {
+                invalid!("'{}' is not a command to run.", v)
+            }
+            c.hooks.get_or_insert_with(Hooks::default).post_create = Some(v.to_string());
+            Ok(())
+        },
+        get: |c| c.hooks.as_ref()?.post_create.clone(),
+        secret: false,
+    },
+    ConfigKey {
+        name: "status_dirs.accepted",
+        set: |c, v| {
+            c.status_dirs
+                .get_or_insert_with(StatusDirs::default)
+                .accepted = Some(dir_in_repo(v)?);
+            Ok(())
+        },
+        get: |c| {
+            Some(
+                c.status_dirs
+                    .as_ref()?
+                    .accepted
+                    .as_ref()?
+                    .display()
+                    .to_string(),
+            )
+        },
+        secret: false,
+    },
+    ConfigKey {
+        name: "status_dirs.rejected",
+        set: |c, v| {
+            c.status_dirs
+                .get_or_insert_with(StatusDirs::default)
+                .rejected = Some(dir_in_repo(v)?);
+            Ok(())
+        },
+        get: |c| {
+            Some(
+                c.status_dirs
+                    .as_ref()?
+                    .rejected
+                    .as_ref()?
+                    .display()
+                    .to_string(),
+            )
+        },
+        secret: false,
+    },
+];
+
+/// Validates `v` as a directory relative to the repository root, not
+/// escaping it.
+fn dir_in_repo(v: &str) -> Result<PathBuf, ConfigError> {
+    let dir = PathBuf::from(v);
+    let escapes = dir
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)));
+    if v.is_empty() || escapes {
+        invalid!(
+            "'{}' is not a directory inside the repository, please supply \
+             a relative path like docs/rfcs.",
+            v
+        )
+    }
+    Ok(dir)
+}
+
+/// The value of a boolean key: `true`, `yes` or `1`, or `false`, `no` or `0`,
+/// in any case.
+fn parse_bool(v: &str) -> Result<bool, ConfigError> {
+    match v.trim().to_lowercase().as_str() {
+        "true" | "yes" | "1" => Ok(true),
+        "false" | "no" | "0" => Ok(false),
+        _ => invalid!(
+            "'{}' is not a boolean, use true or false (or yes/no, 1/0).",
+            v
+        ),
+    }
+}
+
+fn git_mut(config: &mut Config) -> &mut Git {
+    config.git.get_or_insert_with(Git::default)
+}
+
+/// Keys that were renamed, by their old name. Config files using an old name
+/// keep working: it's read as the new one, with a warning, and the global and
+/// repo-local config files are rewritten with the new name.
+const RENAMED_KEYS: &[(&str, &str)] = &[("git.repo", "repo.path")];
+
+/// Moves the values of renamed keys in `table` to their new names, returning
+/// the old and new name of each. Where both are set, the new name wins.
+fn rename_keys(table: &mut toml::Table) -> Vec<(&'static str, &'static str)> {
+    let mut renamed = vec![];
+    for &(old, new) in RENAMED_KEYS {
+        let Some(value) = take_key(table, old) else {
+            continue;
+        };
+        insert_key(table, new, value);
+        renamed.push((old, new));
+    }
+    renamed
+}
+
+/// Sets the dotted key `name` in `table` to `value`, unless it's set already
+/// or one of the tables it goes into is something else.
+fn insert_key(table: &mut toml::Table, name: &str, value: toml::Value) {
+    match name.split_once('.') {
+        None => {
+            table.entry(name).or_insert(value);
+        }
+        Some((first, rest)) => {
+            let inner = table
+                .entry(first)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if let toml::Value::Table(inner) = inner {
+                insert_key(inner, rest, value);
+            }
+        }
+    }
+}
+
+/// Removes the value of the dotted key `name` from `table`, dropping tables
+/// that are empty after that.
+fn take_key(table: &mut toml::Table, name: &str) -> Option<toml::Value> {
+    match name.split_once('.') {
+        None => table.remove(name),
+        Some((first, rest)) => {
+            let inner = table.get_mut(first)?.as_table_mut()?;
+            let value = take_key(inner, rest);
+            if inner.is_empty() {
+                table.remove(first);
+            }
+            value
+        }
+    }
+}
+
+/// The keys whose value differs between `old` and `new`, as `key: old → new`
+/// lines in `KEYS` order. Secrets are redacted, unset values are `<unset>`.
+pub fn changes(old: &Config, new: &Config) -> Vec<String> {
+    let shown = |key: &ConfigKey, value: Option<String>| match value {
+        Some(_) if key.secret => String::from("<redacted>"),
+        Some(value) => value,
+        None => String::from("<unset>"),
+    };
+    KEYS.iter()
+        .filter_map(|key| {
+            let (before, after) = ((key.get)(old), (key.get)(new));
+            (before != after).then(|| {
+                format!(
+                    "{}: {} → {}",
+                    key.name,
+                    shown(key, before),
+                    shown(key, after)
+                )
+            })
+        })
+        .collect()
+}
+
+/// Looks up a configuration key by name, failing with the list of known keys
+/// if there is no such key. Old names of renamed keys are accepted, see
+/// `deprecation` for the warning to give.
+pub fn find_key(name: &str) -> Result<&'static ConfigKey, ConfigError> {
+    let name = match RENAMED_KEYS.iter().find(|(old, _)| *old == name) {
+        Some((_, new)) => *new,
+        None => name,
+    };
+    match KEYS.iter().find(|key| key.name == name) {
+        Some(key) => Ok(key),
+        None => Err(ConfigError::UnknownKey(name.to_string())),
+    }
+}
+
+/// The warning for using `name`, if it's the old name of a renamed key.
+pub fn deprecation(name: &str) -> Option<String> {
+    RENAMED_KEYS
+        .iter()
+        .find(|(old, _)| *old == name)
+        .map(|(old, new)| format!("{} is deprecated, it's called {} now.", old, new))
+}
+
+/// Which config file an operation applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The per-user config file, see `config_path`.
+    Global,
+    /// The `.rfcs.toml` in the root of the repository containing the current
+    /// directory.
+    Local,
+}
+
+/// The serialization format of a config file, going by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    pub fn of(path: &Path) -> Format {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Format::Json,
+            _ => Format::Toml,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Toml => "TOML",
+            Format::Json => "JSON",
+        }
+    }
+
+    /// The format `content` looks like it's in, going by its first line
+    /// that isn't blank: a JSON object starts with `{`, while TOML starts
+    /// with a comment, a `[table]` or a `key = value`. `None` if it's hard
+    /// to say.
+    fn sniff(content: &str) -> Option<Format> {
+        let line = content.lines().map(str::trim).find(|l| !l.is_empty())?;
+        if line.starts_with('{') {
+            return Some(Format::Json);
+        }
+        let is_key = |key: &str| {
+            let key = key.trim().trim_matches('"');
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        };
+        match line.split_once('=') {
+            _ if line.starts_with('#') || line.starts_with('[') => Some(Format::Toml),
+            Some((key, _)) if is_key(key) => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    pub fn serialize(self, config: &Config) -> Result<String> {
+        match self {
+            Format::Toml => Ok(toml::to_string(config)?),
+            Format::Json => {
+                Ok(serde_json::to_string_pretty(&toml::Value::try_from(config)?)? + "\n")
+            }
+        }
+    }
+
+    /// Parses `content` without interpreting it as a `Config` yet, so that
+    /// it can be migrated first.
+    fn parse(self, content: &str) -> Result<toml::Value> {
+        match self {
+            Format::Toml => Ok(toml::from_str(content)?),
+            Format::Json => {
+                let mut value: serde_json::Value = serde_json::from_str(content)?;
+                drop_nulls(&mut value);
+                Ok(toml::Value::try_from(value)?)
+            }
+        }
+    }
+}
+
+/// Removes the nulls from `value`, which `toml::Value` has no equivalent of.
+/// Members that are null are unset, as `Option`s that are `None` are left
+/// out when writing.
+fn drop_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(members) => {
+            members.retain(|_, member| !member.is_null());
+            members.values_mut().for_each(drop_nulls);
+        }
+        serde_json::Value::Array(items) => {
+            items.retain(|item| !item.is_null());
+            items.iter_mut().for_each(drop_nulls);
+        }
+        _ => {}
+    }
+}
+
+/// The steps upgrading a config file from one schema version to the next:
+/// `MIGRATIONS[n]` turns version `n` into version `n + 1`.
+const MIGRATIONS: &[fn(&mut toml::Table)] = &[
+    // Version 1 only introduced the `version` field itself.
+    |_| {},
+];
+
+/// How `migrate` left a config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Migration {
+    Current,
+    Migrated,
+    /// Written by a newer `rfcs`, and left alone.
+    Newer(u32),
+}
+
+/// Upgrades a parsed config file to `CONFIG_VERSION`, one step at a time.
+fn migrate(value: &mut toml::Value) -> Result<Migration> {
+    let Some(table) = value.as_table_mut() else {
+        bail!("A config file must be a table of settings")
+    };
+    let version = match table.get("version") {
+        None => 0,
+        Some(toml::Value::Integer(v)) => u32::try_from(*v)?,
+        Some(other) => bail!("version must be a number, not {}", other),
+    };
+    if version > CONFIG_VERSION {
+        return Ok(Migration::Newer(version));
+    }
+    if version == CONFIG_VERSION {
+        return Ok(Migration::Current);
+    }
+
+    for step in &MIGRATIONS[version as usize..] {
+        step(table);
+    }
+    table.insert(
+        String::from("version"),
+        toml::Value::Integer(CONFIG_VERSION.into()),
+    );
+    Ok(Migration::Migrated)
+}
+
+/// The config file given with `--config`, used in place of the global one.
+/// Set once the arguments are parsed.
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Uses the config file at `path` in place of the global one, for the rest
+/// of the run.
+pub fn override_config_path(path: PathBuf) {
+    CONFIG_OVERRIDE
+        .set(path)
+        .expect("The config path is only overridden once");
+}
+
+/// The global config file, or the one given with `--config`. Fails if the
+/// environment doesn't say where the user's config lives, like a CI job
+/// without `$HOME`.
+pub fn config_path() -> Result<PathBuf, ConfigError> {
+    if let Some(path) = CONFIG_OVERRIDE.get() {
+        return Ok(path.clone());
+    }
+    config_path_in(platform::process_env)
+}
+
+fn config_path_in(env: impl Fn(&str) -> Option<String>) -> Result<PathBuf, ConfigError> {
+    let Some(mut path) = platform::config_home(env) else {
+        return Err(ConfigError::NotFound(format!(
+            "Neither $HOME nor %APPDATA% is set, so there is no global config \
+             file. Set one of them, or configure rfcs through RFCS_GIT_REPO or \
+             RFCS_GIT_URL and a {} instead.",
+            LOCAL_CONFIG_FILE
+        )));
+    };
+    path.push("rfcs");
+    path.push("config.toml");
+
+    Ok(prefer_existing_json(path))
+}
+
+/// The directory of global config fragments, see `read_config_dir`. There is
+/// none with `--config`, which names the one config file to use.
+pub fn config_dir_path() -> Option<PathBuf> {
+    if CONFIG_OVERRIDE.get().is_some() {
+        return None;
+    }
+    let path = config_path_in(platform::process_env).ok()?;
+    Some(path.with_file_name(CONFIG_DIR))
+}
+
+/// The location of the repo-local config file, if the current directory is
+/// inside a git repository with a working directory, or `$GIT_DIR` and
+/// `$GIT_WORK_TREE` point at one.
+pub fn local_config_path() -> Option<PathBuf> {
+    let repo = match git::env_repo() {
+        Ok(Some(repo)) => repo,
+        _ => git2::Repository::discover(".").ok()?,
+    };
+    repo.workdir()
+        .map(|dir| prefer_existing_json(dir.join(LOCAL_CONFIG_FILE)))
+}
+
+/// Config files are TOML by default, but if there is only a JSON file of the
+/// same name, that one is used instead.
+fn prefer_existing_json(toml_path: PathBuf) -> PathBuf {
+    let json_path = toml_path.with_extension("json");
+    if !toml_path.exists() && json_path.exists() {
+        json_path
+    } else {
+        toml_path
+    }
+}
+
+pub fn scope_path(scope: Scope) -> Result<PathBuf, ConfigError> {
+    match scope {
+        Scope::Global => config_path(),
+        Scope::Local => match local_config_path() {
+            Some(path) => Ok(path),
+            None => Err(ConfigError::NotFound(format!(
+                "Not inside a git repository with a working directory, \
+                 can't use the repo-local config file {}.",
+                LOCAL_CONFIG_FILE
+            ))),
+        },
+    }
+}
+
+pub fn default_config() -> Config {
+    Config {
+        version: Some(CONFIG_VERSION),
+        repo: None,
+        git: None,
+        rfc_dir: None,
+        rfc_dirs: None,
+        transitions: None,
+        editor: None,
+        templates_dir: None,
+        hooks: None,
+        status_dirs: None,
+        slug_max_length: None,
+        number_in_front_matter: None,
+        drafts_dir: None,
+        paths_relative_to: None,
+        non_tty: None,
+        date_format: None,
+        max_depth: None,
+        preferred_extensions: None,
+        numbering: None,
+    }
+}
+
+/// Where a configuration value came from, in increasing order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Global,
+    Local,
+    Env,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Source::Global => "global config",
+            Source::Local => "repo-local config",
+            Source::Env => "environment",
+        })
+    }
+}
+
+/// Every configuration layer, kept apart so that the origin of a value can
+/// still be told after merging.
+#[derive(Debug, Clone, Default)]
+pub struct Layers {
+    pub global: Config,
+    pub local: Config,
+    pub env: Config,
+    /// A warning for each renamed key the config files used, for the caller
+    /// to give.
+    pub deprecations: Vec<String>,
+}
+
+impl Layers {
+    pub fn effective(&self) -> Config {
+        self.global
+            .clone()
+            .merge(self.local.clone())
+            .merge(self.env.clone())
+    }
+
+    /// The highest-precedence layer for which `is_set` holds.
+    pub fn source_of(&self, is_set: impl Fn(&Config) -> bool) -> Option<Source> {
+        [
+            (Source::Env, &self.env),
+            (Source::Local, &self.local),
+            (Source::Global, &self.global),
+        ]
+        .into_iter()
+        .find(|(_, layer)| is_set(layer))
+        .map(|(source, _)| source)
+    }
+}
+
+/// Loads the effective configuration: the global config file, with the
+/// repo-local config file and then the environment layered on top.
+pub fn load_config() -> Result<Config> {
+    Ok(load_layers()?.effective())
+}
+
+/// Like `load_config`, but with the layers kept apart. The global layer is
+/// the global config file with its fragments merged over it.
+pub fn load_layers() -> Result<Layers> {
+    let (mut global, mut deprecations) = load_global_config()?;
+    if let Some(dir) = config_dir_path() {
+        let (fragments, fragment_deprecations) = read_fragments(&dir)?;
+        global = global.merge(fragments);
+        deprecations.extend(fragment_deprecations);
+    }
+    let local = match local_config_path() {
+        Some(path) => {
+            let (local, local_deprecations) = load_local_config(&path)?;
+            deprecations.extend(local_deprecations);
+            local
+        }
+        None => Config::default(),
+    };
+    Ok(Layers {
+        global,
+        local,
+        env: env_config(platform::process_env),
+        deprecations,
+    })
+}
+
+/// The config expressed by `RFCS_GIT_URL` and `RFCS_GIT_REPO`.
+pub fn env_config(env: impl Fn(&str) -> Option<String>) -> Config {
+    let url = env("RFCS_GIT_URL");
+    let path = env("RFCS_GIT_REPO").map(PathBuf::from);
+    Config {
+        repo: path.is_some().then_some(Repo { path }),
+        git: url.is_some().then_some(Git {
+            url,
+            ..Git::default()
+        }),
+        ..Config::default()
+    }
+}
+
+/// Loads just the config file for `scope`, without layering.
+pub fn load_scope(scope: Scope) -> Result<Config> {
+    match scope {
+        Scope::Global => Ok(load_global_config()?.0),
+        Scope::Local => Ok(read_config_file(&scope_path(scope)?)?.unwrap_or_default()),
+    }
+}
+
+/// Loads the global config file, writing the default config there if there
+/// is none yet, and writing back migrated files. With `RFCS_NO_CONFIG_WRITE`
+/// set, for read-only or sandboxed environments, neither happens. Without a
+/// place for the global config file at all, it's empty. Returns the
+/// deprecation warnings for the file too.
+fn load_global_config() -> Result<(Config, Vec<String>)> {
+    let Ok(path) = config_path() else {
+        return Ok((Config::default(), vec![]));
+    };
+    let write = platform::process_env("RFCS_NO_CONFIG_WRITE").is_none();
+    match read_migrated_config_file(&path)? {
+        Some(read) => {
+            if write && read.migration == Migration::Migrated {
+                write_config_to(&path, &read.config)?;
+            }
+            Ok((read.config, read.deprecations))
+        }
+        None => {
+            let config = default_config();
+            if write {
+                write_config(config.clone())?;
+            }
+            Ok((config, vec![]))
+        }
+    }
+}
+
+/// Loads the repo-local config file at `path`, writing it back if it uses
+/// renamed keys, unless `RFCS_NO_CONFIG_WRITE` is set. Other migrations stay
+/// in memory, a repo-local file is shared with older `rfcs` too. Returns the
+/// deprecation warnings for the file too.
+fn load_local_config(path: &Path) -> Result<(Config, Vec<String>)> {
+    let Some(read) = read_migrated_config_file(path)? else {
+        return Ok((Config::default(), vec![]));
+    };
+    let write = platform::process_env("RFCS_NO_CONFIG_WRITE").is_none();
+    if write && !read.deprecations.is_empty() && read.migration == Migration::Migrated {
+        write_config_to(path, &read.config)?;
+    }
+    Ok((read.config, read.deprecations))
+}
+
+/// Reads and parses the config file at `path`, returning `None` if there is
+/// no such file. Files with an older schema are migrated in memory only.
+pub fn read_config_file(path: &Path) -> Result<Option<Config>, ConfigError> {
+    Ok(read_migrated_config_file(path)?.map(|read| read.config))
+}
+
+/// A config file as `read_migrated_config_file` read it.
+struct ReadConfig {
+    config: Config,
+    migration: Migration,
+    /// A warning for each renamed key the file used, see `RENAMED_KEYS`.
+    deprecations: Vec<String>,
+}
+
+fn read_migrated_config_file(path: &Path) -> Result<Option<ReadConfig>, ConfigError> {
+    let parse = |content: &str| -> Result<ReadConfig> {
+        let format = Format::of(path);
+        let mut value = match (format.parse(content), Format::sniff(content)) {
+            (Ok(value), _) => value,
+            (Err(_), Some(actual)) if actual != format => bail!(
+                "It's named like a {} file, but looks like {}. Rename it to end in \
+                 .{}, or write it as {}.",
+                format.name(),
+                actual.name(),
+                actual.name().to_lowercase(),
+                format.name()
+            ),
+            (Err(e), _) => return Err(e),
+        };
+        let mut migration = migrate(&mut value)?;
+        let table = value.as_table_mut().expect("migrate only accepts tables");
+        let mut deprecations = vec![];
+        for (old, new) in rename_keys(table) {
+            deprecations.push(format!(
+                "{} in {} is deprecated, it's called {} now.",
+                old,
+                path.display(),
+                new
+            ));
+            if migration == Migration::Current {
+                migration = Migration::Migrated;
+            }
+        }
+        if let Migration::Newer(version) = migration {
+            eprintln!(
+                "Warning: {} is config version {}, but this rfcs only knows up to \
+                 version {}. Settings it doesn't know are ignored.",
+                path.display(),
+                version,
+                CONFIG_VERSION
+            );
+        }
+        Ok(ReadConfig {
+            config: value.try_into()?,
+            migration,
+            deprecations,
+        })
+    };
+    match fs::read_to_string(path) {
+        Ok(content) => match parse(&content) {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(e) => Err(ConfigError::Parse {
+                path: path.to_path_buf(),
+                source: e.into(),
+            }),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(ConfigError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        }),
+    }
+}
+
+/// Reads the `*.toml` fragments in `dir` and merges them in the order of
+/// their names, so that later ones override earlier ones, like
+/// `10-git.toml` and then `20-work.toml`. Without `dir`, that's an empty
+/// config. Fragments are only ever read; writes go to the config file.
+pub fn read_config_dir(dir: &Path) -> Result<Config, ConfigError> {
+    Ok(read_fragments(dir)?.0)
+}
+
+/// `read_config_dir`, with the deprecation warnings for the fragments.
+fn read_fragments(dir: &Path) -> Result<(Config, Vec<String>), ConfigError> {
+    let io_error = |source| ConfigError::Io {
+        path: dir.to_path_buf(),
+        source,
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok((Config::default(), vec![]))
+        }
+        Err(e) => return Err(io_error(e)),
+    };
+    let mut fragments = vec![];
+    for entry in entries {
+        let path = entry.map_err(io_error)?.path();
+        if path.extension().is_some_and(|e| e == "toml") && path.is_file() {
+            fragments.push(path);
+        }
+    }
+    fragments.sort();
+
+    let mut config = Config::default();
+    let mut deprecations = vec![];
+    for fragment in fragments {
+        if let Some(read) = read_migrated_config_file(&fragment)? {
+            config = config.merge(read.config);
+            deprecations.extend(read.deprecations);
+        }
+    }
+    Ok((config, deprecations))
+}
+
+pub fn write_config(config: Config) -> Result<()> {
+    write_config_to(&config_path()?, &config)
+}
+
+/// Writes the config to a temporary file next to `path` first, then renames it
+/// over `path`, so that a crash mid-write never leaves a truncated config
+/// behind. The permissions of an existing config file are carried over.
+pub fn write_config_to(path: &Path, config: &Config) -> Result<()> {
+    let parent = path.parent().expect("Config path must have parent");
+    ensure_dir(parent)?;
+
+    let mut tmp_name = path
+        .file_name()
+        .expect("Config path must have a file name")
+        .to_os_string();
+    // Named for this process, so that concurrent writers don't write into
+    // each other's temporary file.
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = parent.join(tmp_name);
+
+    let config = Config {
+        version: Some(CONFIG_VERSION),
+        ..config.clone()
+    };
+    let content = Format::of(path).serialize(&config)?;
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        if let Ok(existing) = fs::metadata(path) {
+            fs::set_permissions(&tmp, existing.permissions())?;
+        }
+        fs::rename(&tmp, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("Failed to write config file to {}", path.display()))
+}
+
+/// Creates `dir` and its parents, if they don't exist yet. Another process
+/// creating them at the same time is fine, but something other than a
+/// directory in their place is an error naming it.
+fn ensure_dir(dir: &Path) -> Result<()> {
+    if let Some(blocker) = dir.ancestors().find(|a| a.exists()) {
+        if !blocker.is_dir() {
+            bail!(
+                "Can't create the config directory {}, because {} is not a \
+                 directory. Move it out of the way and try again.",
+                dir.display(),
+                blocker.display()
+            )
+        }
+    }
+    match fs::create_dir_all(dir) {
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && dir.is_dir() => Ok(()),
+        result => result
+            .with_context(|| format!("Failed to create the config directory {}", dir.display())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_write_config_is_complete_and_cleans_up() {
+        let dir = test_util::temp_dir("config");
+        let path = dir.join("rfcs").join("config.toml");
+        let config = Config {
+            repo: Some(Repo {
+                path: Some(PathBuf::from("/some/repo")),
+            }),
+            git: Some(Git {
+                url: Some(String::from("git@example.com:org/rfcs.git")),
+                ..Git::default()
+            }),
+            ..Config::default()
+        };
+
+        write_config_to(&path, &config).unwrap();
+        write_config_to(&path, &config).unwrap();
+
+        let written: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            written.repo.unwrap().path,
+            Some(PathBuf::from("/some/repo"))
+        );
+        let leftovers: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, vec![std::ffi::OsString::from("config.toml")]);
+    }
+
+    #[test]
+    fn test_write_config_where_a_file_blocks_the_directory() {
+        let dir = test_util::temp_dir("config");
+        fs::write(dir.join("rfcs"), "not a directory").unwrap();
+        let path = dir.join("rfcs").join("nested").join("config.toml");
+
+        let err = write_config_to(&path, &default_config()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Can't create the config directory {}, because {} is not a \
+                 directory. Move it out of the way and try again.",
+                path.parent().unwrap().display(),
+                dir.join("rfcs").display()
+            )
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("rfcs")).unwrap(),
+            "not a directory"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_config_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = test_util::temp_dir("config");
+        let path = dir.join("config.toml");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_config_to(&path, &default_config()).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_round_trip_through_both_formats() {
+        let dir = test_util::temp_dir("config");
+        let config = Config {
+            git: Some(Git {
+                default_branch: Some(String::from("trunk")),
+                url: Some(String::from("https://example.com/rfcs.git")),
+                ..Git::default()
+            }),
+            ..Config::default()
+        };
+
+        for name in ["config.toml", "config.json"] {
+            let path = dir.join(name);
+            write_config_to(&path, &config).unwrap();
+            let read = read_config_file(&path).unwrap().unwrap().git.unwrap();
+            assert_eq!(read.default_branch.as_deref(), Some("trunk"));
+            assert_eq!(read.url.as_deref(), Some("https://example.com/rfcs.git"));
+            assert_eq!(read.token, None);
+        }
+        let json = fs::read_to_string(dir.join("config.json")).unwrap();
+        assert!(
+            json.starts_with("{\n  \"version\": 1,\n  \"git\": {"),
+            "{}",
+            json
+        );
+    }
+
+    #[test]
+    fn test_json_nulls_are_unset() {
+        let value = Format::Json
+            .parse(r#"{"version": 1, "editor": null, "git": {"url": "x", "token": null}}"#)
+            .unwrap();
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.editor, None);
+        let git = config.git.unwrap();
+        assert_eq!(git.url.as_deref(), Some("x"));
+        assert_eq!(git.token, None);
+        assert!(Format::Json.parse("{\"git\": }").is_err());
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(Format::sniff("\n  {\"version\": 1}"), Some(Format::Json));
+        assert_eq!(Format::sniff("version = 1\n"), Some(Format::Toml));
+        assert_eq!(Format::sniff("[git]\nurl = \"x\"\n"), Some(Format::Toml));
+        assert_eq!(Format::sniff("# rfcs\n{}"), Some(Format::Toml));
+        assert_eq!(Format::sniff("git.url = \"x\"\n"), Some(Format::Toml));
+        assert_eq!(Format::sniff(""), None);
+        assert_eq!(Format::sniff("what is this"), None);
+    }
+
+    #[test]
+    fn test_mismatched_format_says_so() {
+        use std::error::Error;
+
+        let dir = test_util::temp_dir("config");
+        let path = dir.join("config.json");
+        fs::write(&path, "version = 1\n[git]\nurl = \"x\"\n").unwrap();
+        let err = read_config_file(&path).unwrap_err();
+        assert!(matches!(&err, ConfigError::Parse { path: p, .. } if *p == path));
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "It's named like a JSON file, but looks like TOML. Rename it to end in \
+             .toml, or write it as JSON."
+        );
+
+        let path = dir.join("config.toml");
+        fs::write(&path, "{\"version\": 1}\n").unwrap();
+        assert_eq!(
+            read_config_file(&path)
+                .unwrap_err()
+                .source()
+                .unwrap()
+                .to_string(),
+            "It's named like a TOML file, but looks like JSON. Rename it to end in \
+             .json, or write it as TOML."
+        );
+
+        fs::write(&path, "version = true\n").unwrap();
+        assert_eq!(
+            read_config_file(&path)
+                .unwrap_err()
+                .source()
+                .unwrap()
+                .to_string(),
+            "version must be a number, not true"
+        );
+    }
+
+    #[test]
+    fn test_json_file_is_used_when_toml_is_absent() {
+        let dir = test_util::temp_dir("config");
+        let toml_path = dir.join("config.toml");
+        assert_eq!(prefer_existing_json(toml_path.clone()), toml_path);
+        fs::write(dir.join("config.json"), "{}").unwrap();
+        assert_eq!(prefer_existing_json(toml_path), dir.join("config.json"));
+        fs::write(dir.join("config.toml"), "").unwrap();
+        assert_eq!(
+            prefer_existing_json(dir.join("config.toml")),
+            dir.join("config.toml")
+        );
+    }
+
+    #[test]
+    fn test_read_config_dir_merges_fragments_in_order() {
+        let dir = test_util::temp_dir("config");
+        assert!(read_config_dir(&dir.join(CONFIG_DIR))
+            .unwrap()
+            .git
+            .is_none());
+        let fragments = dir.join(CONFIG_DIR);
+        fs::create_dir_all(&fragments).unwrap();
+        fs::write(
+            fragments.join("10-git.toml"),
+            "[repo]\npath = \"/first/repo\"\n[git]\nurl = \"https://example.com/rfcs.git\"\n",
+        )
+        .unwrap();
+        fs::write(
+            fragments.join("20-work.toml"),
+            "rfc_dir = \"rfcs\"\n[repo]\npath = \"/second/repo\"\n",
+        )
+        .unwrap();
+        fs::write(fragments.join("30-notes.txt"), "not = \"config\"\n").unwrap();
+
+        let config = read_config_dir(&fragments).unwrap();
+        assert_eq!(
+            config.repo.unwrap().path,
+            Some(PathBuf::from("/second/repo"))
+        );
+        let git = config.git.unwrap();
+        assert_eq!(git.url.as_deref(), Some("https://example.com/rfcs.git"));
+        assert_eq!(config.rfc_dir, Some(PathBuf::from("rfcs")));
+
+        fs::write(fragments.join("40-broken.toml"), "[git\n").unwrap();
+        assert!(matches!(
+            read_config_dir(&fragments),
+            Err(ConfigError::Parse { path, .. }) if path.ends_with("40-broken.toml")
+        ));
+    }
+
+    #[test]
+    fn test_env_overrides_file_config() {
+        let env = |k: &str| (k == "RFCS_GIT_REPO").then(|| String::from("/env/repo"));
+        let layers = Layers {
+            global: Config {
+                repo: Some(Repo {
+                    path: Some(PathBuf::from("/global/repo")),
+                }),
+                git: Some(Git {
+                    url: Some(String::from("https://example.com/global.git")),
+                    ..Git::default()
+                }),
+                ..Config::default()
+            },
+            local: Config::default(),
+            env: env_config(env),
+            deprecations: vec![],
+        };
+
+        let effective = layers.effective();
+        assert_eq!(
+            effective.repo.unwrap().path,
+            Some(PathBuf::from("/env/repo"))
+        );
+        let url = effective.git.unwrap().url;
+        assert_eq!(url.as_deref(), Some("https://example.com/global.git"));
+        let repo_set = |c: &Config| c.repo.as_ref().is_some_and(|r| r.path.is_some());
+        let url_set = |c: &Config| c.git.as_ref().is_some_and(|g| g.url.is_some());
+        assert_eq!(layers.source_of(repo_set), Some(Source::Env));
+        assert_eq!(layers.source_of(url_set), Some(Source::Global));
+    }
+
+    #[test]
+    fn test_merge_prefers_layered_values() {
+        let global = Config {
+            repo: Some(Repo {
+                path: Some(PathBuf::from("/global/repo")),
+            }),
+            git: Some(Git {
+                url: Some(String::from("https://example.com/global.git")),
+                ..Git::default()
+            }),
+            ..Config::default()
+        };
+        let local = Config {
+            repo: Some(Repo { path: None }),
+            git: Some(Git {
+                url: Some(String::from("https://example.com/local.git")),
+                token: Some(String::from("hunter2")),
+                ..Git::default()
+            }),
+            ..Config::default()
+        };
+
+        let merged = global.merge(local);
+        assert_eq!(
+            merged.repo.unwrap().path,
+            Some(PathBuf::from("/global/repo"))
+        );
+        let merged = merged.git.unwrap();
+        assert_eq!(merged.url.as_deref(), Some("https://example.com/local.git"));
+        assert_eq!(merged.token.as_deref(), Some("hunter2"));
+    }
+
+    #[test]
+    fn test_unknown_key_lists_every_registered_key() {
+        let err = find_key("git.nope").err().unwrap().to_string();
+        assert!(err.contains("'git.nope'"));
+        for key in KEYS {
+            assert!(err.contains(key.name), "{} missing from: {}", key.name, err);
+        }
+    }
+
+    #[test]
+    fn test_error_variants() {
+        use std::error::Error;
+
+        assert!(matches!(
+            find_key("git.nope"),
+            Err(ConfigError::UnknownKey(name)) if name == "git.nope"
+        ));
+        assert!(matches!(
+            (find_key("git.shallow").unwrap().set)(&mut Config::default(), "on"),
+            Err(ConfigError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            config_path_in(|_| None),
+            Err(ConfigError::NotFound(_))
+        ));
+
+        let dir = test_util::temp_dir("config");
+        let path = dir.join("config.toml");
+        fs::write(&path, "version = true\n").unwrap();
+        let err = read_config_file(&path).unwrap_err();
+        assert!(matches!(&err, ConfigError::Parse { path: p, .. } if *p == path));
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "version must be a number, not true"
+        );
+        assert!(matches!(
+            read_config_file(&dir),
+            Err(ConfigError::Io { .. })
+        ));
+        assert!(read_config_file(&dir.join("missing.toml"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_migrates_unversioned_config() {
+        let mut value: toml::Value = toml::from_str("[repo]\npath = \"/some/repo\"\n").unwrap();
+        assert_eq!(migrate(&mut value).unwrap(), Migration::Migrated);
+        assert_eq!(value["version"].as_integer(), Some(CONFIG_VERSION.into()));
+        let config: Config = value.clone().try_into().unwrap();
+        assert_eq!(config.repo.unwrap().path, Some(PathBuf::from("/some/repo")));
+
+        assert_eq!(migrate(&mut value).unwrap(), Migration::Current);
+    }
+
+    #[test]
+    fn test_renames_deprecated_keys() {
+        let mut table: toml::Table = toml::from_str("[git]\nrepo = \"/some/repo\"\n").unwrap();
+        assert_eq!(rename_keys(&mut table), vec![("git.repo", "repo.path")]);
+        assert_eq!(
+            toml::to_string(&table).unwrap(),
+            "[repo]\npath = \"/some/repo\"\n"
+        );
+        let config: Config = toml::Value::Table(table.clone()).try_into().unwrap();
+        assert_eq!(config.repo.unwrap().path, Some(PathBuf::from("/some/repo")));
+        assert!(config.git.is_none());
+        assert!(rename_keys(&mut table).is_empty());
+
+        let mut table: toml::Table =
+            toml::from_str("[git]\nrepo = \"/old\"\nurl = \"x\"\n[repo]\npath = \"/new\"\n")
+                .unwrap();
+        rename_keys(&mut table);
+        assert_eq!(
+            toml::to_string(&table).unwrap(),
+            "[git]\nurl = \"x\"\n\n[repo]\npath = \"/new\"\n"
+        );
+    }
+
+    #[test]
+    fn test_take_key_drops_emptied_tables() {
+        let mut table: toml::Table = toml::from_str("[a.b]\nc = 1\n").unwrap();
+        assert_eq!(take_key(&mut table, "a.b.c"), Some(toml::Value::Integer(1)));
+        assert!(table.is_empty());
+        assert_eq!(take_key(&mut table, "a.b.c"), None);
+    }
+
+    #[test]
+    fn test_newer_config_is_left_alone() {
+        let mut value: toml::Value = toml::from_str("version = 99\nfuture = true\n").unwrap();
+        let before = value.clone();
+        assert_eq!(migrate(&mut value).unwrap(), Migration::Newer(99));
+        assert_eq!(value, before);
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.version, Some(99));
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        for v in ["true", "TRUE", "yes", "Yes", "1"] {
+            assert!(parse_bool(v).unwrap(), "{}", v);
+        }
+        for v in ["false", "False", "no", "NO", "0"] {
+            assert!(!parse_bool(v).unwrap(), "{}", v);
+        }
+        let err = parse_bool("maybe").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'maybe' is not a boolean, use true or false (or yes/no, 1/0)."
+        );
+        assert!((find_key("git.shallow").unwrap().set)(&mut Config::default(), "on").is_err());
+    }
+
+    #[test]
+    fn test_changes() {
+        let old = default_config();
+        let mut new = old.clone();
+        (find_key("git.url").unwrap().set)(&mut new, "https://example.com/rfcs.git").unwrap();
+        (find_key("git.token").unwrap().set)(&mut new, "hunter2").unwrap();
+        assert_eq!(
+            changes(&old, &new),
+            [
+                "git.url: <unset> → https://example.com/rfcs.git",
+                "git.token: <unset> → <redacted>",
+            ]
+        );
+        assert!(changes(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_keys_round_trip() {
+        let mut config = Config::default();
+        for (name, value) in [
+            ("git.url", "https://example.com/rfcs.git"),
+            ("repo.path", "/some/repo"),
+            ("git.token", "hunter2"),
+            ("git.default_branch", "trunk"),
+            ("git.clone_timeout_secs", "60"),
+            ("git.tag_prefix", "rfc-"),
+            ("git.discover", "false"),
+            ("git.shallow", "true"),
+            ("git.clone_dir", "/some/clones"),
+            ("git.auto_clone", "false"),
+            ("rfc_dir", "docs/rfcs"),
+            ("rfc_dirs", "services/a/rfcs,services/b/rfcs"),
+            ("drafts_dir", "docs/drafts"),
+            ("paths_relative_to", "config"),
+            ("non_tty", "allow"),
+            ("date_format", "%d.%m.%Y"),
+            ("max_depth", "2"),
+            ("preferred_extensions", "md,org"),
+            ("number_in_front_matter", "true"),
+            ("numbering.separator", "_"),
+            ("numbering.per_dir", "true"),
+            ("numbering.case", "kebab"),
+            ("numbering.pattern", r"^RFC-(?<rfc_number>\d+)"),
+            ("status_dirs.accepted", "accepted"),
+            ("status_dirs.rejected", "archive/rejected"),
+        ] {
+            let key = find_key(name).unwrap();
+            (key.set)(&mut config, value).unwrap();
+            assert_eq!((key.get)(&config).as_deref(), Some(value));
+        }
+        assert!((find_key("git.default_branch").unwrap().set)(&mut config, "no..dots").is_err());
+        assert!((find_key("git.clone_timeout_secs").unwrap().set)(&mut config, "0").is_err());
+        assert!((find_key("git.clone_dir").unwrap().set)(&mut config, "").is_err());
+        assert!((find_key("max_depth").unwrap().set)(&mut config, "0").is_err());
+        assert!((find_key("date_format").unwrap().set)(&mut config, "%Y-%Q").is_err());
+        assert!((find_key("date_format").unwrap().set)(&mut config, "%Y%").is_err());
+        assert!((find_key("date_format").unwrap().set)(&mut config, "").is_err());
+        assert!((find_key("preferred_extensions").unwrap().set)(&mut config, "md,,org").is_err());
+        assert!((find_key("rfc_dir").unwrap().set)(&mut config, "../elsewhere").is_err());
+        assert!((find_key("rfc_dir").unwrap().set)(&mut config, "/absolute").is_err());
+        assert!((find_key("status_dirs.accepted").unwrap().set)(&mut config, "../up").is_err());
+        assert!((find_key("numbering.separator").unwrap().set)(&mut config, "/").is_err());
+        assert!((find_key("numbering.separator").unwrap().set)(&mut config, "--").is_err());
+        assert!((find_key("numbering.case").unwrap().set)(&mut config, "upper").is_err());
+        assert!((find_key("numbering.pattern").unwrap().set)(&mut config, r"(\d+").is_err());
+        assert!((find_key("numbering.pattern").unwrap().set)(&mut config, r"(\d+)").is_err());
+    }
+}
@@ -0,0 +1,643 @@
+//! `rfcs doctor`: a checklist of the things that commonly go wrong when
+//! setting `rfcs` up. Checking modifies nothing; some problems come with a
+//! `Fix` that `doctor --fix` applies.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as Cmd;
+
+use anyhow::{Context, Result};
+
+use crate::color::Color;
+use crate::config::{self, Config, Layers};
+use crate::{
+    git, metadata, number_collisions, platform, rfc_dir_of, rfc_files, rfc_root, rfc_title, Scan,
+};
+
+/// The front matter fields an RFC with front matter should have, with what
+/// `doctor --fix` puts in when one is missing. `None` goes by `rfc_title`.
+const REQUIRED_FIELDS: [(&str, Option<&str>); 2] = [("title", None), ("status", Some("draft"))];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn color(self) -> Color {
+        match self {
+            Status::Ok => Color::Green,
+            Status::Warn => Color::Yellow,
+            Status::Fail => Color::Red,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::Ok => "[ok]  ",
+            Status::Warn => "[warn]",
+            Status::Fail => "[fail]",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub status: Status,
+    pub message: String,
+    /// How to repair what this check found, if that's safe to do unasked.
+    pub fix: Option<Fix>,
+}
+
+impl Check {
+    fn new(status: Status, message: impl Into<String>) -> Check {
+        Check {
+            status,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn fixable(status: Status, message: impl Into<String>, fix: Fix) -> Check {
+        Check {
+            fix: Some(fix),
+            ..Check::new(status, message)
+        }
+    }
+}
+
+/// A change to a single RFC in the repository at `repo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub repo: PathBuf,
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// Sets `fields` in the front matter of `file`.
+    AddFields {
+        file: PathBuf,
+        fields: Vec<(String, String)>,
+    },
+}
+
+impl Fix {
+    /// Makes the change, and with `commit`, commits it on its own. Returns
+    /// what was done.
+    pub fn apply(&self, commit: bool) -> Result<String> {
+        let relative = |file: &Path| git::relative_to_repo(&self.repo, file);
+        let (files, message) = match &self.change {
+            Change::Rename { from, to } => {
+                fs::rename(from, to).with_context(|| {
+                    format!("Failed to rename {} to {}", from.display(), to.display())
+                })?;
+                let (from, to) = (relative(from)?, relative(to)?);
+                let message = format!("Rename {} to {}", from.display(), to.display());
+                (vec![from, to], message)
+            }
+            Change::AddFields { file, fields } => {
+                let mut content = fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                for (key, value) in fields {
+                    content = metadata::set_field(&content, key, value);
+                }
+                fs::write(file, content)
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+                let file = relative(file)?;
+                let keys: Vec<&str> = fields.iter().map(|(key, _)| key.as_str()).collect();
+                let message = format!(
+                    "Add {} to the front matter of {}",
+                    keys.join(", "),
+                    file.display()
+                );
+                (vec![file], message)
+            }
+        };
+        if commit {
+            let files: Vec<&Path> = files.iter().map(PathBuf::as_path).collect();
+            git::commit_files(&self.repo, &files, &message)?;
+        }
+        Ok(message)
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.message)
+    }
+}
+
+/// Runs every check, in order. `ping` additionally asks the configured remote
+/// whether it's there, which needs the network, giving up at `deadline`.
+/// `follow_symlinks` is as for `rfc_files`.
+pub fn run(ping: bool, follow_symlinks: bool, deadline: platform::Deadline) -> Vec<Check> {
+    let mut checks = vec![];
+    let config = check_config_files(&mut checks);
+    let git = config.git.clone().unwrap_or_default();
+    let path = config.repo.as_ref().and_then(|r| r.path.as_ref());
+
+    check_git_binary(&mut checks, path.is_none() && git.url.is_some());
+    let Some(repo) = check_repo(&mut checks, path, &git) else {
+        return checks;
+    };
+    match git::default_branch_name(&repo, git.default_branch.as_deref()) {
+        Ok(name) => checks.push(Check::new(
+            Status::Ok,
+            format!("RFC branches are based on '{}'", name),
+        )),
+        Err(e) => checks.push(Check::new(Status::Fail, format!("{:#}", e))),
+    }
+    if ping {
+        if let Some(url) = &git.url {
+            checks.push(check_remote(url, deadline));
+        }
+    }
+    let dirs = config.rfc_dirs();
+    let key = if config.rfc_dirs.is_some() {
+        "rfc_dirs"
+    } else {
+        "rfc_dir"
+    };
+    for dir in &dirs {
+        let rfcs = rfc_root(&repo, Some(dir));
+        if !rfcs.is_dir() {
+            checks.push(Check::new(
+                Status::Fail,
+                format!("{} {} doesn't exist", key, rfcs.display()),
+            ));
+            return checks;
+        }
+    }
+    let pattern = match config.pattern() {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            checks.push(Check::new(Status::Fail, e.to_string()));
+            return checks;
+        }
+    };
+    let scan = Scan {
+        follow_symlinks,
+        number_in_front_matter: config.number_in_front_matter.unwrap_or(false),
+        max_depth: config.max_depth,
+        preferred_extensions: config.preferred_extensions.clone(),
+        pattern,
+    };
+    let files = match rfc_files(&repo, &dirs, &scan) {
+        Ok(files) => files,
+        Err(e) => {
+            checks.push(Check::new(Status::Fail, format!("{:#}", e)));
+            return checks;
+        }
+    };
+    if config.per_dir_numbering() {
+        for dir in &dirs {
+            let in_dir: Vec<PathBuf> = files
+                .iter()
+                .filter(|f| rfc_dir_of(&repo, &dirs, f) == Some(dir))
+                .cloned()
+                .collect();
+            checks.extend(check_numbering(&in_dir, &scan));
+        }
+    } else {
+        checks.extend(check_numbering(&files, &scan));
+    }
+    checks.extend(check_case_collisions(&files));
+    checks.extend(check_file_names(&repo, &files));
+    checks.extend(check_front_matter(&repo, &files));
+
+    checks
+}
+
+/// Reads every config layer without writing any of them, and returns the
+/// effective config of those that could be read.
+fn check_config_files(checks: &mut Vec<Check>) -> Config {
+    let mut read = |path: &Path, missing: Option<Check>| match config::read_config_file(path) {
+        Ok(Some(config)) => {
+            checks.push(Check::new(
+                Status::Ok,
+                format!("Read config file {}", path.display()),
+            ));
+            config
+        }
+        Ok(None) => {
+            checks.extend(missing);
+            Config::default()
+        }
+        Err(e) => {
+            checks.push(Check::new(Status::Fail, format!("{:#}", e)));
+            Config::default()
+        }
+    };
+
+    let (global, no_global) = match config::config_path() {
+        Ok(global_path) => (
+            read(
+                &global_path,
+                Some(Check::new(
+                    Status::Warn,
+                    format!(
+                        "No config file at {} yet, the defaults will be written there",
+                        global_path.display()
+                    ),
+                )),
+            ),
+            None,
+        ),
+        Err(e) => (
+            Config::default(),
+            Some(Check::new(Status::Warn, format!("{:#}", e))),
+        ),
+    };
+    let local = match config::local_config_path() {
+        Some(path) => read(&path, None),
+        None => Config::default(),
+    };
+    checks.extend(no_global);
+    let global = match config::config_dir_path().filter(|dir| dir.is_dir()) {
+        Some(dir) => match config::read_config_dir(&dir) {
+            Ok(fragments) => {
+                checks.push(Check::new(
+                    Status::Ok,
+                    format!("Read config fragments in {}", dir.display()),
+                ));
+                global.merge(fragments)
+            }
+            Err(e) => {
+                checks.push(Check::new(Status::Fail, format!("{:#}", e)));
+                global
+            }
+        },
+        None => global,
+    };
+
+    Layers {
+        global,
+        local,
+        env: config::env_config(platform::process_env),
+        deprecations: vec![],
+    }
+    .effective()
+}
+
+fn check_git_binary(checks: &mut Vec<Check>, needed: bool) {
+    match Cmd::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => checks.push(Check::new(
+            Status::Ok,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )),
+        _ => checks.push(Check::new(
+            if needed { Status::Fail } else { Status::Warn },
+            "Can't run `git`, which is needed to clone git.url",
+        )),
+    }
+}
+
+/// The local repository, if there is a usable one. `path` is `repo.path`.
+fn check_repo(
+    checks: &mut Vec<Check>,
+    path: Option<&PathBuf>,
+    git: &config::Git,
+) -> Option<PathBuf> {
+    let repo = match (path, &git.url) {
+        (Some(repo), _) => repo.clone(),
+        (None, Some(url)) => {
+            let clone = match config::config_path() {
+                Ok(path) => path
+                    .parent()
+                    .expect("Config path must have parent")
+                    .join("rfcs"),
+                Err(e) => {
+                    checks.push(Check::new(Status::Fail, format!("{:#}", e)));
+                    return None;
+                }
+            };
+            if !clone.exists() {
+                checks.push(Check::new(
+                    Status::Warn,
+                    format!(
+                        "repo.path is not set, {} will be cloned to {} on first use",
+                        git::redact_url(url),
+                        clone.display()
+                    ),
+                ));
+                return None;
+            }
+            clone
+        }
+        (None, None) => match git::discover(Path::new(".")) {
+            Some(repo) if git.discover.unwrap_or(true) => {
+                checks.push(Check::new(
+                    Status::Ok,
+                    "Neither repo.path nor git.url is configured, using the repository \
+                     containing the current directory",
+                ));
+                repo
+            }
+            _ => {
+                checks.push(Check::new(
+                    Status::Fail,
+                    "Neither repo.path nor git.url is configured",
+                ));
+                return None;
+            }
+        },
+    };
+
+    if !repo.exists() {
+        checks.push(Check::new(
+            Status::Fail,
+            format!("repo.path {} doesn't exist", repo.display()),
+        ));
+        return None;
+    }
+    if git2::Repository::open(&repo).is_err() {
+        checks.push(Check::new(
+            Status::Fail,
+            format!("repo.path {} is not a git repository", repo.display()),
+        ));
+        return None;
+    }
+    if git::is_bare(&repo) {
+        checks.push(Check::new(
+            Status::Fail,
+            format!(
+                "repo.path {} is a bare repository, without files to work with",
+                repo.display()
+            ),
+        ));
+        return None;
+    }
+    checks.push(Check::new(
+        Status::Ok,
+        format!("Found git repository {}", repo.display()),
+    ));
+    Some(repo)
+}
+
+fn check_remote(url: &str, deadline: platform::Deadline) -> Check {
+    let mut command = Cmd::new("git");
+    command
+        .args(["ls-remote", "--quiet", "--heads", url])
+        .env("GIT_TERMINAL_PROMPT", "0");
+    match platform::output_with_timeout(&mut command, deadline.remaining()) {
+        Ok(output) if output.status.success() => {
+            Check::new(Status::Ok, format!("Reached {}", git::redact_url(url)))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::TimedOut => Check::new(
+            Status::Fail,
+            format!(
+                "Gave up reaching {} when --timeout ran out",
+                git::redact_url(url)
+            ),
+        ),
+        _ => Check::new(
+            Status::Fail,
+            format!("Can't reach {}", git::redact_url(url)),
+        ),
+    }
+}
+
+/// Warns about numbers used by more than one RFC, and about gaps.
+fn check_numbering(files: &[PathBuf], scan: &Scan) -> Vec<Check> {
+    let mut by_number: BTreeMap<usize, Vec<&PathBuf>> = BTreeMap::new();
+    for file in files {
+        if let Some(n) = scan.file_number(file) {
+            by_number.entry(n).or_default().push(file);
+        }
+    }
+
+    let mut checks = vec![];
+    for (n, files) in number_collisions(files, scan) {
+        let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        checks.push(Check::new(
+            Status::Warn,
+            format!("RFC {:03} is used by {}", n, names.join(", ")),
+        ));
+    }
+
+    let Some(&highest) = by_number.keys().next_back() else {
+        checks.push(Check::new(Status::Ok, "No RFCs yet"));
+        return checks;
+    };
+    let missing: Vec<String> = (1..highest)
+        .filter(|n| !by_number.contains_key(n))
+        .map(|n| format!("{:03}", n))
+        .collect();
+    if !missing.is_empty() {
+        checks.push(Check::new(
+            Status::Warn,
+            format!("No RFCs numbered {}", missing.join(", ")),
+        ));
+    }
+    if checks.is_empty() {
+        checks.push(Check::new(
+            Status::Ok,
+            format!("{} RFCs, numbered 001 to {:03}", by_number.len(), highest),
+        ));
+    }
+    checks
+}
+
+/// Warns about RFC files whose paths differ only in case, which can't both
+/// exist on case-insensitive file systems like those of macOS and Windows.
+fn check_case_collisions(files: &[PathBuf]) -> Vec<Check> {
+    let mut by_lowercase: BTreeMap<String, Vec<&PathBuf>> = BTreeMap::new();
+    for file in files {
+        by_lowercase
+            .entry(file.to_string_lossy().to_lowercase())
+            .or_default()
+            .push(file);
+    }
+    by_lowercase
+        .values()
+        .filter(|files| files.len() > 1)
+        .map(|files| {
+            let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+            Check::new(
+                Status::Warn,
+                format!(
+                    "{} differ only in case, and collide on case-insensitive file systems",
+                    names.join(", ")
+                ),
+            )
+        })
+        .collect()
+}
+
+/// `name` with its number padded to three digits, like `create` names RFCs,
+/// and its extension in lowercase. Only numbers at the start of the name
+/// are re-padded.
+fn canonical_name(name: &str) -> String {
+    let digits = name.len() - name.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let mut canonical = match name[..digits].parse::<usize>() {
+        Ok(number) if digits > 0 => format!("{:03}{}", number, &name[digits..]),
+        _ => name.to_string(),
+    };
+    if let Some(dot) = canonical.rfind('.').filter(|&dot| dot > 0) {
+        canonical = format!("{}{}", &canonical[..dot], canonical[dot..].to_lowercase());
+    }
+    canonical
+}
+
+/// Warns about RFC files whose number isn't padded to three digits, or whose
+/// extension isn't lowercase. They can be renamed unless something already
+/// goes by the new name.
+fn check_file_names(repo: &Path, files: &[PathBuf]) -> Vec<Check> {
+    let mut taken: HashSet<PathBuf> = files.iter().cloned().collect();
+    let mut checks = vec![];
+    for file in files {
+        let Some(name) = file.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let canonical = canonical_name(name);
+        if canonical == name {
+            continue;
+        }
+        let to = file.with_file_name(&canonical);
+        let shown = shown(repo, file);
+        // On case-insensitive file systems, the file itself exists under the
+        // new name.
+        let same_file = canonical.eq_ignore_ascii_case(name);
+        if taken.contains(&to) || (!same_file && to.exists()) {
+            checks.push(Check::new(
+                Status::Warn,
+                format!("{} should be named {}, which is taken", shown, canonical),
+            ));
+            continue;
+        }
+        taken.insert(to.clone());
+        checks.push(Check::fixable(
+            Status::Warn,
+            format!("{} should be named {}", shown, canonical),
+            Fix {
+                repo: repo.to_path_buf(),
+                change: Change::Rename {
+                    from: file.clone(),
+                    to,
+                },
+            },
+        ));
+    }
+    checks
+}
+
+/// Warns about RFCs with front matter that lack any of `REQUIRED_FIELDS`.
+/// Files without front matter are left alone.
+fn check_front_matter(repo: &Path, files: &[PathBuf]) -> Vec<Check> {
+    let mut checks = vec![];
+    for file in files {
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+        if !metadata::has_front_matter(&content) {
+            continue;
+        }
+        let front_matter = metadata::parse(&content);
+        let missing: Vec<(String, String)> = REQUIRED_FIELDS
+            .iter()
+            .filter(|(key, _)| front_matter.get(key).is_none())
+            .map(|(key, placeholder)| {
+                let value = match placeholder {
+                    Some(value) => value.to_string(),
+                    None => rfc_title(file, &front_matter, &content),
+                };
+                (key.to_string(), value)
+            })
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        let keys: Vec<&str> = missing.iter().map(|(key, _)| key.as_str()).collect();
+        checks.push(Check::fixable(
+            Status::Warn,
+            format!(
+                "{} has no {} in its front matter",
+                shown(repo, file),
+                keys.join(" or ")
+            ),
+            Fix {
+                repo: repo.to_path_buf(),
+                change: Change::AddFields {
+                    file: file.clone(),
+                    fields: missing,
+                },
+            },
+        ));
+    }
+    checks
+}
+
+/// `file` relative to `repo`, for messages.
+fn shown(repo: &Path, file: &Path) -> String {
+    file.strip_prefix(repo)
+        .unwrap_or(file)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_numbering_consistent() {
+        let files = [PathBuf::from("001-a.md"), PathBuf::from("002-b.md")];
+        assert_eq!(
+            check_numbering(&files, &Scan::default()),
+            [Check::new(Status::Ok, "2 RFCs, numbered 001 to 002")]
+        );
+    }
+
+    #[test]
+    fn test_numbering_duplicates_and_gaps() {
+        let files = [
+            PathBuf::from("001-a.md"),
+            PathBuf::from("001-b.md"),
+            PathBuf::from("004-c.md"),
+        ];
+        assert_eq!(
+            check_numbering(&files, &Scan::default()),
+            [
+                Check::new(Status::Warn, "RFC 001 is used by 001-a.md, 001-b.md"),
+                Check::new(Status::Warn, "No RFCs numbered 002, 003"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_canonical_name() {
+        assert_eq!(canonical_name("0042-Caches.md"), "042-Caches.md");
+        assert_eq!(canonical_name("42-Caches.MD"), "042-Caches.md");
+        assert_eq!(canonical_name("1234-big.md"), "1234-big.md");
+        assert_eq!(canonical_name("042-Caches.v2.Md"), "042-Caches.v2.md");
+        assert_eq!(canonical_name("RFC-042.md"), "RFC-042.md");
+    }
+
+    #[test]
+    fn test_case_collisions() {
+        let files = [
+            PathBuf::from("001-Caches.md"),
+            PathBuf::from("001-caches.md"),
+            PathBuf::from("002-other.md"),
+        ];
+        assert_eq!(
+            check_case_collisions(&files),
+            [Check::new(
+                Status::Warn,
+                "001-Caches.md, 001-caches.md differ only in case, and collide on \
+                 case-insensitive file systems"
+            )]
+        );
+    }
+}
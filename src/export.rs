@@ -0,0 +1,90 @@
+//! `rfcs export`: RFCs bundled into a single markdown document, for
+//! publishing.
+
+/// An RFC as it goes into the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub number: usize,
+    pub title: String,
+    /// The RFC without its front matter.
+    pub body: String,
+}
+
+/// Starts a new page when the document is printed or turned into a PDF.
+const PAGE_BREAK: &str = "<div style=\"page-break-before: always;\"></div>";
+
+/// The document: a table of contents linking to each RFC, then the RFCs in
+/// the order given, each on a page of its own.
+pub fn document(entries: &[Entry]) -> String {
+    let mut out = String::from("# RFCs\n\n");
+    for entry in entries {
+        out.push_str(&format!(
+            "- [RFC {:03}: {}](#{})\n",
+            entry.number,
+            entry.title,
+            anchor(entry.number)
+        ));
+    }
+    for entry in entries {
+        out.push_str(&format!(
+            "\n{}\n\n<a id=\"{}\"></a>\n\n{}",
+            PAGE_BREAK,
+            anchor(entry.number),
+            entry.body.trim_start()
+        ));
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn anchor(number: usize) -> String {
+    format!("rfc-{:03}", number)
+}
+
+/// The title of an RFC without one in its front matter: its first heading,
+/// if it has one.
+pub fn heading(body: &str) -> Option<&str> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_document() {
+        let entries = [
+            Entry {
+                number: 1,
+                title: String::from("Caches"),
+                body: String::from("# Caches\nAre bad."),
+            },
+            Entry {
+                number: 2,
+                title: String::from("Queues"),
+                body: String::from("\n# Queues\nAre good.\n"),
+            },
+        ];
+        assert_eq!(
+            document(&entries),
+            format!(
+                "# RFCs\n\n\
+                 - [RFC 001: Caches](#rfc-001)\n\
+                 - [RFC 002: Queues](#rfc-002)\n\
+                 \n{0}\n\n<a id=\"rfc-001\"></a>\n\n# Caches\nAre bad.\n\
+                 \n{0}\n\n<a id=\"rfc-002\"></a>\n\n# Queues\nAre good.\n",
+                PAGE_BREAK
+            )
+        );
+    }
+
+    #[test]
+    fn test_heading() {
+        assert_eq!(heading("Intro\n# Caches \n# Other\n"), Some("Caches"));
+        assert_eq!(heading("## Not a title\n"), None);
+    }
+}
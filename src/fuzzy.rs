@@ -0,0 +1,55 @@
+//! Matching a remembered fragment of a title against RFCs, for finding one
+//! without knowing its number.
+
+/// How well `query` matches `text`, higher is better, or `None` if it doesn't
+/// match at all. Both are split into words, ignoring case and punctuation.
+/// Every word of the query must occur in `text`: a whole word counts the
+/// most, the start of a word less, and anywhere inside a word the least.
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let query = words(query);
+    let text = words(text);
+    if query.is_empty() {
+        return None;
+    }
+    query
+        .iter()
+        .map(|q| {
+            text.iter()
+                .map(|t| {
+                    if t == q {
+                        3
+                    } else if t.starts_with(q.as_str()) {
+                        2
+                    } else if t.contains(q.as_str()) {
+                        1
+                    } else {
+                        0
+                    }
+                })
+                .max()
+                .filter(|&s| s > 0)
+        })
+        .sum()
+}
+
+fn words(s: &str) -> Vec<String> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score("cache", "002-Caches-revisited"), Some(2));
+        assert_eq!(score("caches", "002-Caches-revisited"), Some(3));
+        assert_eq!(score("visit", "002-Caches-revisited"), Some(1));
+        assert_eq!(score("Caches, REVISITED", "002-Caches-revisited"), Some(6));
+        assert_eq!(score("caches queues", "002-Caches-revisited"), None);
+        assert_eq!(score("--", "002-Caches-revisited"), None);
+    }
+}
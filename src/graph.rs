@@ -0,0 +1,205 @@
+//! `rfcs graph`: how RFCs relate to each other, going by the `supersedes`,
+//! `amends` and `superseded_by` fields of their front matter, as a Graphviz
+//! or mermaid graph.
+
+use std::collections::BTreeSet;
+
+use crate::metadata::FrontMatter;
+
+/// An RFC as it goes into the graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub number: usize,
+    pub title: String,
+}
+
+/// One RFC superseding or amending another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: Relation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relation {
+    Supersedes,
+    Amends,
+}
+
+impl Relation {
+    fn label(self) -> &'static str {
+        match self {
+            Relation::Supersedes => "supersedes",
+            Relation::Amends => "amends",
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Graphviz, for `dot -Tsvg`.
+    Dot,
+    /// A mermaid flowchart, which GitHub and GitLab render in markdown.
+    Mermaid,
+}
+
+/// The edges going out of RFC `number` with `front_matter`. `superseded_by`
+/// is turned around, so that it's the same edge as the other RFC's
+/// `supersedes`. Numbers that don't parse are skipped.
+pub fn edges(number: usize, front_matter: &FrontMatter) -> Vec<Edge> {
+    let numbers = |key: &str| -> Vec<usize> {
+        front_matter
+            .list(key)
+            .iter()
+            .filter_map(|n| n.parse().ok())
+            .collect()
+    };
+    let mut edges = vec![];
+    for to in numbers("supersedes") {
+        edges.push(Edge {
+            from: number,
+            to,
+            kind: Relation::Supersedes,
+        });
+    }
+    for to in numbers("amends") {
+        edges.push(Edge {
+            from: number,
+            to,
+            kind: Relation::Amends,
+        });
+    }
+    for from in numbers("superseded_by") {
+        edges.push(Edge {
+            from,
+            to: number,
+            kind: Relation::Supersedes,
+        });
+    }
+    edges
+}
+
+/// The graph of `nodes` and `edges` in `format`. An edge recorded on both
+/// ends is drawn once.
+pub fn render(nodes: &[Node], edges: &[Edge], format: Format) -> String {
+    let edges: BTreeSet<&Edge> = edges.iter().collect();
+    let mut out = String::new();
+    match format {
+        Format::Dot => {
+            out.push_str("digraph rfcs {\n");
+            for node in nodes {
+                out.push_str(&format!(
+                    "    {} [label=\"{:03}: {}\"];\n",
+                    id(node.number),
+                    node.number,
+                    node.title.replace('\\', "\\\\").replace('"', "\\\"")
+                ));
+            }
+            for edge in edges {
+                out.push_str(&format!(
+                    "    {} -> {} [label=\"{}\"];\n",
+                    id(edge.from),
+                    id(edge.to),
+                    edge.kind.label()
+                ));
+            }
+            out.push_str("}\n");
+        }
+        Format::Mermaid => {
+            out.push_str("graph LR\n");
+            for node in nodes {
+                out.push_str(&format!(
+                    "    {}[\"{:03}: {}\"]\n",
+                    id(node.number),
+                    node.number,
+                    node.title.replace('"', "#quot;")
+                ));
+            }
+            for edge in edges {
+                out.push_str(&format!(
+                    "    {} -->|{}| {}\n",
+                    id(edge.from),
+                    edge.kind.label(),
+                    id(edge.to)
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn id(number: usize) -> String {
+    format!("rfc{:03}", number)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metadata;
+
+    #[test]
+    fn test_edges() {
+        let front_matter =
+            metadata::parse("---\nsupersedes: [001, 2]\namends: 004\nsuperseded_by: 009\n---\n");
+        assert_eq!(
+            edges(5, &front_matter),
+            [
+                Edge {
+                    from: 5,
+                    to: 1,
+                    kind: Relation::Supersedes
+                },
+                Edge {
+                    from: 5,
+                    to: 2,
+                    kind: Relation::Supersedes
+                },
+                Edge {
+                    from: 5,
+                    to: 4,
+                    kind: Relation::Amends
+                },
+                Edge {
+                    from: 9,
+                    to: 5,
+                    kind: Relation::Supersedes
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let nodes = [
+            Node {
+                number: 1,
+                title: String::from("Caches"),
+            },
+            Node {
+                number: 2,
+                title: String::from("Caches, \"revisited\""),
+            },
+        ];
+        let edge = Edge {
+            from: 2,
+            to: 1,
+            kind: Relation::Supersedes,
+        };
+        assert_eq!(
+            render(&nodes, &[edge, edge], Format::Dot),
+            "digraph rfcs {\n\
+             \x20   rfc001 [label=\"001: Caches\"];\n\
+             \x20   rfc002 [label=\"002: Caches, \\\"revisited\\\"\"];\n\
+             \x20   rfc002 -> rfc001 [label=\"supersedes\"];\n\
+             }\n"
+        );
+        assert_eq!(
+            render(&nodes, &[edge], Format::Mermaid),
+            "graph LR\n\
+             \x20   rfc001[\"001: Caches\"]\n\
+             \x20   rfc002[\"002: Caches, #quot;revisited#quot;\"]\n\
+             \x20   rfc002 -->|supersedes| rfc001\n"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Hooks: commands from the `hooks` config table that `rfcs` runs after it did
+//! something, like `hooks.post_create`. A hook is a command line split into
+//! words like `platform::split_command`, it's not run through a shell.
+//! `{name}` placeholders in its words are replaced with the values describing
+//! what was done. A program given as a relative path, like
+//! `scripts/notify.sh`, is relative to `paths_relative_to`; one given by name
+//! is looked up in `$PATH`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::bail;
+use anyhow::Result;
+
+use crate::platform;
+use crate::trace;
+
+/// Runs the hook `name` with `command`, in `cwd`, with a relative program
+/// path resolved against `base`. With `capture`, its output is only shown if
+/// it fails. What the hook ran after has happened regardless of whether it
+/// fails, so callers usually only warn about that.
+pub fn run(
+    name: &str,
+    command: &str,
+    values: &[(&str, String)],
+    cwd: &Path,
+    base: &Path,
+    capture: bool,
+) -> Result<()> {
+    let words: Vec<String> = platform::split_command(command)
+        .iter()
+        .map(|word| substitute(word, values))
+        .collect();
+    let Some((program, args)) = words.split_first() else {
+        return Ok(());
+    };
+
+    let mut child = Command::new(program_path(program, base));
+    child.args(args).current_dir(cwd);
+    trace::command(&child);
+    let result = match capture {
+        true => child
+            .stdin(Stdio::null())
+            .output()
+            .map(|output| (output.status, output.stdout, output.stderr)),
+        false => child.status().map(|status| (status, vec![], vec![])),
+    };
+    match result {
+        Ok((status, _, _)) if status.success() => Ok(()),
+        Ok((status, stdout, stderr)) => {
+            eprint!("{}", String::from_utf8_lossy(&stdout));
+            eprint!("{}", String::from_utf8_lossy(&stderr));
+            bail!("the {} hook `{}` failed with {}.", name, command, status)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+            "can't run the {} hook `{}`: '{}' not found. Install it, or fix \
+             hooks.{} with `rfcs configure`.",
+            name,
+            command,
+            program,
+            name
+        ),
+        Err(e) => bail!("can't run the {} hook `{}`: {}", name, command, e),
+    }
+}
+
+/// Where `program` is: under `base` if it's a relative path, or as it is if
+/// it's absolute or just a name for `$PATH` lookup.
+fn program_path(program: &str, base: &Path) -> PathBuf {
+    let path = Path::new(program);
+    match path.is_relative() && path.components().count() > 1 {
+        true => base.join(path),
+        false => path.to_path_buf(),
+    }
+}
+
+/// `word` with every `{key}` of `values` replaced by its value.
+fn substitute(word: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(word.to_string(), |word, (key, value)| {
+        word.replace(&format!("{{{}}}", key), value)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_program_path() {
+        let base = Path::new("/src/rfcs");
+        assert_eq!(
+            program_path("scripts/notify.sh", base),
+            Path::new("/src/rfcs/scripts/notify.sh")
+        );
+        assert_eq!(
+            program_path("./notify.sh", base),
+            Path::new("/src/rfcs/./notify.sh")
+        );
+        assert_eq!(program_path("notify", base), Path::new("notify"));
+        #[cfg(unix)]
+        assert_eq!(
+            program_path("/usr/bin/notify", base),
+            Path::new("/usr/bin/notify")
+        );
+    }
+
+    #[test]
+    fn test_substitute() {
+        let values = [
+            ("number", String::from("007")),
+            ("branch", String::from("007-Bond")),
+        ];
+        assert_eq!(substitute("--rfc={number}", &values), "--rfc=007");
+        assert_eq!(substitute("{branch}/{number}", &values), "007-Bond/007");
+        assert_eq!(substitute("{path}", &values), "{path}");
+    }
+}
@@ -0,0 +1,78 @@
+//! The statuses an RFC goes through, and which status may follow which.
+
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+use anyhow::Result;
+
+/// The status of an RFC whose front matter doesn't have one.
+pub const INITIAL_STATUS: &str = "draft";
+
+/// For every status, the statuses it may change to.
+pub type Transitions = BTreeMap<String, Vec<String>>;
+
+/// The lifecycle used unless the config has a `transitions` table.
+pub fn default_transitions() -> Transitions {
+    [
+        ("draft", &["in-review", "withdrawn"][..]),
+        ("in-review", &["draft", "accepted", "rejected", "withdrawn"]),
+        ("accepted", &["superseded"]),
+        ("rejected", &[]),
+        ("withdrawn", &["draft"]),
+        ("superseded", &[]),
+    ]
+    .into_iter()
+    .map(|(from, to)| (from.to_string(), to.iter().map(|s| s.to_string()).collect()))
+    .collect()
+}
+
+/// Fails, listing the allowed alternatives, unless `transitions` allows going
+/// from status `from` to `to`.
+pub fn check_transition(transitions: &Transitions, from: &str, to: &str) -> Result<()> {
+    let Some(allowed) = transitions.get(from) else {
+        bail!(
+            "'{}' is not a known status, known statuses: {}",
+            from,
+            transitions.keys().cloned().collect::<Vec<_>>().join(", ")
+        )
+    };
+    if allowed.iter().any(|s| s == to) {
+        return Ok(());
+    }
+    match allowed.as_slice() {
+        [] => bail!("An RFC that is {} can't change status anymore.", from),
+        _ => bail!(
+            "An RFC that is {} can't become {}, only {}.",
+            from,
+            to,
+            allowed.join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_transitions() {
+        let transitions = default_transitions();
+        assert!(check_transition(&transitions, "draft", "in-review").is_ok());
+        assert!(check_transition(&transitions, "in-review", "accepted").is_ok());
+
+        let err = check_transition(&transitions, "accepted", "draft").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "An RFC that is accepted can't become draft, only superseded."
+        );
+        assert!(check_transition(&transitions, "rejected", "draft").is_err());
+        assert!(check_transition(&transitions, "unheard-of", "draft").is_err());
+    }
+
+    #[test]
+    fn test_custom_transitions() {
+        let transitions: Transitions = toml::from_str("draft = [\"final\"]\nfinal = []\n").unwrap();
+        assert!(check_transition(&transitions, "draft", "final").is_ok());
+        assert!(check_transition(&transitions, "draft", "in-review").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! A lock file, so that two `rfcs` processes working on the same repository
+//! don't both hand out the same RFC number. The lock is the file existing:
+//! whoever manages to create it holds the lock, and it's removed again when
+//! the `Lock` is dropped. The file holds the holder's process id, so that a
+//! lock left behind by a run that crashed can be told apart and broken.
+
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+
+use crate::platform;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+pub struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    /// Creates the lock file at `path`, waiting up to `timeout` for another
+    /// process holding it to let go.
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<Lock> {
+        let started = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(path)
+            {
+                Ok(mut file) => {
+                    let lock = Lock {
+                        path: path.to_path_buf(),
+                    };
+                    write!(file, "{}", std::process::id())
+                        .with_context(|| format!("Failed to write lock {}", path.display()))?;
+                    return Ok(lock);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if let Some(pid) = holder(path).filter(|&pid| !platform::process_running(pid)) {
+                        // Whoever held it is gone without letting go.
+                        break_stale(path, pid);
+                        continue;
+                    }
+                    if started.elapsed() >= timeout {
+                        bail!(
+                            "Another rfcs process is holding {}. If there is none, \
+                             a previous run was interrupted and the file can be \
+                             deleted.",
+                            path.display()
+                        )
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to create lock {}", path.display()))
+                }
+            }
+        }
+    }
+}
+
+/// The process id in the lock file at `path`, unless it can't be read yet.
+fn holder(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Breaks the lock at `path` left behind by the gone process `pid`. It's
+/// moved aside first, which only one process manages, so that two breaking it
+/// at once can't have the second remove the lock the first took since. If
+/// what was moved isn't `pid`'s after all, it's put back with a hard link,
+/// which never replaces a lock taken in the meantime.
+fn break_stale(path: &Path, pid: u32) {
+    let mut aside = path.as_os_str().to_os_string();
+    aside.push(format!(".{}.stale", std::process::id()));
+    let aside = PathBuf::from(aside);
+    if fs::rename(path, &aside).is_err() {
+        // Broken by someone else already.
+        return;
+    }
+    if holder(&aside) != Some(pid) {
+        let _ = fs::hard_link(&aside, path);
+    }
+    let _ = fs::remove_file(&aside);
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_lock_excludes_and_releases() {
+        let path = test_util::temp_dir("lock").join("test.lock");
+        let lock = Lock::acquire(&path, Duration::ZERO).unwrap();
+        assert!(Lock::acquire(&path, Duration::from_millis(100)).is_err());
+
+        drop(lock);
+        assert!(!path.exists());
+        assert!(Lock::acquire(&path, Duration::ZERO).is_ok());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_lock_of_a_gone_process_is_broken() {
+        let path = test_util::temp_dir("lock").join("test.lock");
+        // Higher than any process id Linux or macOS hand out.
+        fs::write(&path, "4294967295").unwrap();
+        let lock = Lock::acquire(&path, Duration::ZERO).unwrap();
+        assert_eq!(holder(&path), Some(std::process::id()));
+
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_breaking_a_lock_taken_since_puts_it_back() {
+        let dir = test_util::temp_dir("lock");
+        let path = dir.join("test.lock");
+        fs::write(&path, std::process::id().to_string()).unwrap();
+
+        break_stale(&path, 4294967295);
+        assert_eq!(holder(&path), Some(std::process::id()));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::write(&path, "4294967295").unwrap();
+        break_stale(&path, 4294967295);
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    }
+}
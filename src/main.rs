@@ -1,232 +1,4781 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::io::IsTerminal;
+use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 use anyhow::bail;
+use anyhow::Context;
 use anyhow::Result;
+use chrono::{NaiveDate, Utc};
 use clap::Parser;
 use clap::Subcommand;
 use regex::Regex;
-use serde::Deserialize;
-use serde::Serialize;
 use std::process::Command as Cmd;
 
+use config::{config_path, Config, NonTtyPolicy, Repo, Scope, SlugCase};
+
+mod color;
+mod config;
+mod doctor;
+mod export;
+mod fuzzy;
 mod git;
+mod graph;
+mod hook;
+mod lifecycle;
+mod lock;
+mod metadata;
+mod platform;
+mod prompt;
+mod reserve;
+mod runner;
+mod template;
+#[cfg(test)]
+mod test_util;
+mod trace;
+mod watch;
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// List the RFCs in the repository.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs list\n",
+        "  rfcs list --from 10 --to 20 --format porcelain\n",
+        "  rfcs list --added-on 042-caches",
+    ))]
+    List(ListArgs),
+    /// Print where the configuration file is, and each key with the file it
+    /// comes from.
+    #[command(after_help = "Examples:\n  rfcs dump-info")]
+    DumpInfo,
+    /// Print just the effective value of a configuration key, failing if it
+    /// isn't set.
+    #[command(after_help = "Examples:\n  rfcs config-get repo.path")]
+    ConfigGet { key: String },
+    /// Print just the path of the global config file, or of the one given
+    /// with `--config`.
+    #[command(after_help = "Examples:\n  rfcs config-path\n  $EDITOR \"$(rfcs config-path)\"")]
+    ConfigPath,
+    /// Set a configuration key. Keys are dotted paths into the config file,
+    /// like `git.url`.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs configure git.url git@github.com:org/rfcs.git\n",
+        "  rfcs configure repo.path ~/src/rfcs\n",
+        "  rfcs configure --local rfc_dir docs/rfcs\n",
+        "  rfcs configure --dry-run git.default_branch trunk\n",
+        "  rfcs configure --edit",
+    ))]
+    Configure {
+        /// Write to the `.rfcs.toml` of the repository you're in, instead of
+        /// the global config file.
+        #[arg(long, conflicts_with = "global")]
+        local: bool,
+        /// Write to the global config file. This is the default.
+        #[arg(long)]
+        global: bool,
+        /// Open the config file in the editor instead, creating it first if
+        /// there is none, and check it once the editor exits.
+        #[arg(long, conflicts_with_all = ["key", "value"])]
+        edit: bool,
+        /// Print what would change, without writing the config file.
+        #[arg(long, conflicts_with = "edit")]
+        dry_run: bool,
+        #[arg(required_unless_present = "edit")]
+        key: Option<String>,
+        #[arg(required_unless_present = "edit")]
+        value: Option<String>,
+    },
+    /// Create an RFC with the next number, on a branch of its own, and commit
+    /// it there.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs create 'Caches are bad, actually'\n",
+        "  rfcs create --from-template design 'A new API'\n",
+        "  rfcs create --number 100 --no-checkout 'A reserved number'\n",
+        "  rfcs create --label api --label infra 'Versioned endpoints'\n",
+        "  rfcs create --subdir api 'Versioned endpoints'\n",
+        "  rfcs create --file-only 'Caches'\n",
+        "  rfcs create --interactive",
+    ))]
+    Create(CreateArgs),
+    /// Adopt an existing document as the next RFC: move it into the
+    /// repository, named after its title like `create` names RFCs, and commit
+    /// it.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs import ~/docs/design.md\n",
+        "  rfcs import --no-commit notes/caching.md",
+    ))]
+    Import {
+        /// The document: a markdown, text, reStructuredText, AsciiDoc or org
+        /// file.
+        path: PathBuf,
+        /// Move the document, but leave committing it to you.
+        #[arg(long)]
+        no_commit: bool,
+        #[command(flatten)]
+        numbering: NumberingArgs,
+    },
+    /// Print the branch name `create` would give a new RFC with this title,
+    /// without creating anything.
+    #[command(after_help = "Examples:\n  rfcs branch-name 'Caches are bad, actually'")]
+    BranchName {
+        title: String,
+        #[command(flatten)]
+        numbering: NumberingArgs,
+    },
+    /// Check the configuration and repository for common problems.
+    #[command(
+        after_help = "Examples:\n  rfcs doctor\n  rfcs doctor --ping\n  rfcs doctor --fix --commit"
+    )]
+    Doctor {
+        /// Also check that git.url can be reached over the network.
+        #[arg(long)]
+        ping: bool,
+        /// Repair what can safely be repaired: re-pad RFC numbers, lowercase
+        /// extensions, and fill in missing front matter fields. Anything else
+        /// is only reported.
+        #[arg(long)]
+        fix: bool,
+        /// Commit each fix on its own.
+        #[arg(long, requires = "fix")]
+        commit: bool,
+    },
+    /// Print the version of `rfcs`. With `--verbose`, also the git2 and
+    /// libgit2 it was built with, for bug reports.
+    #[command(after_help = "Examples:\n  rfcs version\n  rfcs version --verbose")]
+    Version,
+    /// Print the number the next RFC would get.
+    #[command(after_help = "Examples:\n  rfcs next\n  rfcs next --include-untracked=false")]
+    Next(NumberingArgs),
+    /// Check that a git URL can be reached, before configuring it as
+    /// `git.url`, and list its branches and tags like `git ls-remote`,
+    /// without cloning it.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs probe https://github.com/org/rfcs.git\n",
+        "  rfcs --timeout 10 probe git@github.com:org/rfcs.git",
+    ))]
+    Probe { url: String },
+    /// Set an RFC number aside, so that new RFCs are numbered past it until
+    /// it's used with `create --number` or released, and print it.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs reserve\n",
+        "  rfcs reserve 100\n",
+        "  rfcs reserve --release 100",
+    ))]
+    Reserve {
+        /// The number to reserve or release. Without it, the number the next
+        /// RFC would get is reserved.
+        number: Option<usize>,
+        /// Release the number instead, so that it's free again. Numbers an
+        /// RFC uses by now can't be released.
+        #[arg(long, requires = "number")]
+        release: bool,
+        #[command(flatten)]
+        numbering: NumberingArgs,
+    },
+    /// Print an RFC.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs show 42\n",
+        "  rfcs show caches\n",
+        "  rfcs show --metadata --json 42\n",
+        "  rfcs show --range 10..14",
+    ))]
+    Show(ShowArgs),
+    /// Set the RFC's `updated` date in its front matter to today, and commit
+    /// that.
+    #[command(after_help = "Examples:\n  rfcs touch 42")]
+    Touch {
+        /// The RFC's number, or the path to its file.
+        target: Target,
+    },
+    /// Give an RFC a new title, renaming its file and branch to match, and
+    /// commit that. With `--title-from-file`, the title in the RFC is taken
+    /// as it is, for when it was changed by hand.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs rename 42 'Caches, revisited'\n",
+        "  rfcs rename --title-from-file 42",
+    ))]
+    Rename {
+        /// The RFC's number, or the path to its file.
+        target: Target,
+        /// The new title. It's also set as the `title` of the front matter,
+        /// if there is one.
+        #[arg(required_unless_present = "title_from_file")]
+        title: Option<String>,
+        /// Go by the title the RFC already has: the `title` of its front
+        /// matter, or else its first heading.
+        #[arg(long, conflicts_with = "title")]
+        title_from_file: bool,
+    },
+    /// Move an RFC to another status, and commit that. Only the transitions
+    /// in the lifecycle are allowed, e.g. draft to in-review.
+    #[command(
+        after_help = "Examples:\n  rfcs promote 42 in-review\n  rfcs promote 10-12 15 accepted"
+    )]
+    Promote {
+        /// The RFCs: numbers, paths, ranges like `10-12`, or `all`.
+        #[arg(required = true, num_args = 1..)]
+        targets: Vec<TargetSet>,
+        /// The status to move to.
+        status: String,
+    },
+    /// List the commits that changed an RFC, following renames.
+    #[command(after_help = "Examples:\n  rfcs log 42")]
+    Log {
+        /// The RFC's number, or the path to its file.
+        target: Target,
+    },
+    /// Print how an RFC changed since a commit, branch or tag, by default the
+    /// default branch, as a unified diff. Uncommitted changes are included.
+    #[command(after_help = "Examples:\n  rfcs diff 42\n  rfcs diff --from v2.0 caches")]
+    Diff {
+        /// The RFC's number, or the path to its file.
+        target: Target,
+        /// Compare with the RFC at this commit, branch or tag instead.
+        #[arg(long, value_name = "REF")]
+        from: Option<String>,
+    },
+    /// Merge an RFC's branch into the default branch, mark it accepted, and
+    /// delete the branch.
+    #[command(after_help = "Examples:\n  rfcs merge 42\n  rfcs merge --keep-status 42")]
+    Merge {
+        number: usize,
+        /// Don't set `status: accepted` in the RFC's front matter.
+        #[arg(long)]
+        keep_status: bool,
+    },
+    /// Delete the RFC branches that are fully merged into the default branch.
+    #[command(after_help = "Examples:\n  rfcs prune\n  rfcs prune --yes")]
+    Prune,
+    /// Pair RFC branches with RFC files by number, and list those without
+    /// a partner: branches that never got an RFC file, which have likely
+    /// stalled, and files without a branch, like RFCs merged long ago.
+    #[command(after_help = "Examples:\n  rfcs reconcile")]
+    Reconcile,
+    /// Fetch the branches others pushed from `origin`, and drop the
+    /// remote-tracking branches of those deleted there.
+    #[command(after_help = "Examples:\n  rfcs sync\n  rfcs sync --all")]
+    Sync {
+        /// Fetch from every remote, not just `origin`.
+        #[arg(long)]
+        all: bool,
+    },
+    /// Bundle RFCs into one markdown document with a table of contents,
+    /// ordered by number.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs export --output rfcs.md\n",
+        "  rfcs export --status accepted --from 10",
+    ))]
+    Export(ExportArgs),
+    /// List the templates `create --from-template` can start an RFC from.
+    #[command(after_help = "Examples:\n  rfcs templates\n  rfcs templates --validate")]
+    Templates {
+        /// Check that each template is UTF-8 and uses only placeholders that
+        /// are filled in, and fail if one isn't.
+        #[arg(long)]
+        validate: bool,
+    },
+    /// Open an RFC in your editor.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs edit 42\n",
+        "  rfcs edit --editor 'code --wait' caches\n",
+        "  rfcs edit --create-if-missing 'Caches, revisited'",
+    ))]
+    Edit {
+        /// The RFC's number, or the path to its file.
+        target: Target,
+        /// The editor to use, instead of the `editor` config key, `$VISUAL`
+        /// or `$EDITOR`. May include arguments, like `code --wait`.
+        #[arg(long, value_name = "CMD")]
+        editor: Option<String>,
+        /// If no RFC's file or title matches, create an RFC with the target
+        /// as its title, like `rfcs create` would, and edit that.
+        #[arg(long)]
+        create_if_missing: bool,
+    },
+    /// Print how RFCs supersede and amend each other as a graph, going by
+    /// their front matter.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs graph | dot -Tsvg > rfcs.svg\n",
+        "  rfcs graph --format mermaid",
+    ))]
+    Graph {
+        #[arg(long, value_enum, ignore_case = true, default_value_t = graph::Format::Dot)]
+        format: graph::Format,
+    },
+    /// Open an RFC's pull request or file on the git host in your browser.
+    /// Without `--file` or `--pr`, that's the pull request while the RFC
+    /// has a branch, and the file on the default branch after.
+    #[command(after_help = concat!(
+        "Examples:\n",
+        "  rfcs open 42\n",
+        "  rfcs open --file 42\n",
+        "  rfcs open --pr --print 42",
+    ))]
+    Open(OpenArgs),
+}
+
+/// Manage, review, read and edit the RFCs in a git repository.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Args {
+    #[command(flatten)]
+    globals: Globals,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct ListArgs {
+    /// Print only the number of matching RFCs.
+    #[arg(long, conflicts_with = "format")]
+    count: bool,
+    /// Only list RFCs numbered at least this.
+    #[arg(long, value_name = "N")]
+    from: Option<usize>,
+    /// Only list RFCs numbered at most this.
+    #[arg(long, value_name = "N")]
+    to: Option<usize>,
+    /// Terminate each path with a NUL byte instead of a newline, like
+    /// `find -print0`, for use with `xargs -0`.
+    #[arg(short = '0', long, conflicts_with_all = ["count", "format"])]
+    null: bool,
+    /// How to print each RFC. Case doesn't matter.
+    #[arg(long, value_enum, ignore_case = true, default_value_t = ListFormat::Human)]
+    format: ListFormat,
+    /// Indent `--format json` output, for reading it rather than piping it.
+    #[arg(long)]
+    pretty: bool,
+    /// Show the titles of `--format table` in full, however wide they are.
+    #[arg(long, conflicts_with = "max_title_width")]
+    no_truncate: bool,
+    /// Cut the titles of `--format table` short at this many characters,
+    /// instead of at what fits the terminal.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_title_width: Option<u64>,
+    /// Add what git knows about each RFC to `--format json` and `ndjson`:
+    /// when it was first and last committed, who committed it last, and in
+    /// how many commits. Takes a walk through the whole history.
+    #[arg(long)]
+    with_git: bool,
+    /// Print `--format json` output as an object with the RFCs under `rfcs`
+    /// and the `version` of its schema, instead of a bare array, so that
+    /// tools can tell when the schema changes.
+    #[arg(long)]
+    envelope: bool,
+    /// The columns of `--format csv` and the members of `--format json` and
+    /// `ndjson`, in order. Defaults to number, title, status and path.
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "FIELDS")]
+    fields: Vec<Field>,
+    /// Print the RFCs as an indented tree, grouped by directory or status.
+    #[arg(long, conflicts_with_all = ["count", "null", "format"])]
+    tree: bool,
+    /// Only list RFCs with this label in their `labels` or `tags` front
+    /// matter, ignoring case. Given more than once, RFCs need all of them.
+    #[arg(long, value_name = "LABEL")]
+    label: Vec<String>,
+    /// With more than one `--label`, list RFCs with any of them instead.
+    #[arg(long, requires = "label")]
+    any_label: bool,
+    /// Only list RFCs with this `status` in their front matter, ignoring
+    /// case. Given more than once, RFCs with any of them.
+    #[arg(long, value_name = "STATUS")]
+    status: Vec<String>,
+    /// Only list accepted RFCs, like `--status accepted`.
+    #[arg(long, conflicts_with_all = ["status", "drafts_only"])]
+    accepted_only: bool,
+    /// Only list draft RFCs, like `--status draft`.
+    #[arg(long, conflicts_with = "status")]
+    drafts_only: bool,
+    /// Only list your RFCs: those with you as an `author` in their front
+    /// matter, or without authors there, those you committed first. Who you
+    /// are comes from git's user.name and user.email.
+    #[arg(long, alias = "mine")]
+    authored_by_me: bool,
+    /// Fail if no RFC is left to list after the filters, for CI checks
+    /// that expect one, like an RFC added on a branch.
+    #[arg(long)]
+    fail_on_empty: bool,
+    /// The order to list the RFCs in. The most recently modified or created
+    /// come first.
+    #[arg(long, value_enum, conflicts_with_all = ["count", "tree", "count_by"], default_value_t = SortBy::Number)]
+    sort: SortBy,
+    /// List at most this many RFCs, after sorting and filtering, for paging
+    /// through them with `--offset`.
+    #[arg(long, value_name = "N", conflicts_with_all = ["count", "count_by", "numbers_only", "duplicates", "with_branch"])]
+    limit: Option<usize>,
+    /// Skip this many RFCs, after sorting and filtering, before listing the
+    /// rest.
+    #[arg(long, value_name = "N", conflicts_with_all = ["count", "count_by", "numbers_only", "duplicates", "with_branch"])]
+    offset: Option<usize>,
+    /// What `--tree` groups by.
+    #[arg(long, value_enum, requires = "tree", default_value_t = GroupBy::Directory)]
+    group_by: GroupBy,
+    /// Instead of listing the RFCs, print how many there are of each status,
+    /// author or hundred numbers, most first.
+    #[arg(long, value_enum, value_name = "FIELD", conflicts_with_all = ["count", "null", "tree", "format"])]
+    count_by: Option<CountBy>,
+    /// End the listing with how many RFCs there are of each status, like
+    /// `12 RFCs: 7 accepted, 3 draft, 2 rejected`. Only the human format
+    /// gets one.
+    #[arg(long, alias = "status-counts-footer", conflicts_with_all = ["count", "null", "tree", "count_by"])]
+    summary: bool,
+    /// List what's committed on the default branch instead of the files in
+    /// the working directory. Works with bare repositories, too.
+    #[arg(long)]
+    committed: bool,
+    /// Only list RFCs with changes that aren't committed yet.
+    #[arg(long, conflicts_with = "committed")]
+    modified: bool,
+    /// Only list the RFCs this branch added or changed since it split off
+    /// from the default branch, as they are on that branch.
+    #[arg(long, value_name = "BRANCH", conflicts_with_all = ["committed", "modified"])]
+    added_on: Option<String>,
+    /// Only list the RFCs that the commits in a range, like `v1.0..v2.0`,
+    /// added or changed, as they are at its end. For release notes.
+    #[arg(long, value_name = "RANGE", conflicts_with_all = ["committed", "modified", "added_on"])]
+    changed_in: Option<String>,
+    /// Only list the RFCs added since a tag, going by how HEAD differs from
+    /// it, as they are at HEAD. For changelogs.
+    #[arg(long, value_name = "TAG", conflicts_with_all = ["committed", "modified", "added_on", "changed_in"])]
+    since_tag: Option<String>,
+    /// Also list the drafts in `drafts_dir` that have no number yet, with
+    /// their number as pending. They come after the numbered RFCs.
+    #[arg(long, conflicts_with_all = ["committed", "added_on", "changed_in", "since_tag"])]
+    include_unnumbered: bool,
+    /// Print paths relative to the repository root. This is the default,
+    /// except for `--format porcelain`, which prints them as found under
+    /// `repo.path`.
+    #[arg(long, conflicts_with_all = ["absolute", "count", "tree"])]
+    relative: bool,
+    /// Print absolute paths.
+    #[arg(long, conflicts_with_all = ["count", "tree"])]
+    absolute: bool,
+    /// Print paths relative to this directory, like `../rfcs/001-caches.md`
+    /// for links in a README in `docs`. Paths that can't be, like on another
+    /// Windows drive, are printed absolute.
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["relative", "absolute", "count", "tree"])]
+    base_path: Option<PathBuf>,
+    /// Say for each RFC whether there's also a local branch with its number,
+    /// and list the RFC branches without a file after them.
+    #[arg(long, conflicts_with_all = ["count", "null", "tree", "count_by", "format"])]
+    with_branch: bool,
+    /// Print only the numbers used by more than one RFC, files and local
+    /// branches alike, each with what uses it. Prints nothing if there are
+    /// none.
+    #[arg(long, conflicts_with_all = ["count", "null", "tree", "count_by", "format", "with_branch"])]
+    duplicates: bool,
+    /// Only list RFCs with a file on disk: not the branches without one that
+    /// `--with-branch` lists, nor, with `--committed`, `--added-on` or
+    /// `--changed-in`, RFCs that aren't in the working directory.
+    #[arg(long, conflicts_with = "duplicates")]
+    exists_only: bool,
+    /// Keep the listing on screen, and print it afresh whenever the
+    /// repository changes, until interrupted. Without a terminal to print
+    /// to, the RFCs are listed once.
+    #[arg(long, conflicts_with = "null")]
+    watch: bool,
+    /// Print only the RFC numbers, lowest first and each once, for shell
+    /// loops. They're zero-padded like in file names, or not with
+    /// `--numbers-only=raw`.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STYLE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "padded",
+        conflicts_with_all = ["count", "null", "tree", "count_by", "summary", "format", "with_branch", "duplicates"],
+    )]
+    numbers_only: Option<NumberStyle>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberStyle {
+    /// Like `042`.
+    Padded,
+    /// Like `42`.
+    Raw,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupBy {
+    /// The directories the RFCs are in, nested.
+    Directory,
+    /// The status from the front matter.
+    Status,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    Number,
+    /// The last commit that changed the RFC, or for uncommitted changes, the
+    /// file's modification time.
+    Modified,
+    /// The first commit that added the RFC, or for uncommitted RFCs, the
+    /// file's modification time.
+    Created,
+    /// The title from the front matter, or the file name without one.
+    Title,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum CountBy {
+    /// The status from the front matter.
+    Status,
+    /// The author from the front matter.
+    Author,
+    /// The hundred an RFC's number is in, like `0xx` for 001 to 099.
+    NumberPrefix,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ListFormat {
+    /// The path of each RFC.
+    Human,
+    /// One line per RFC, ordered by number unless `--sort` says otherwise:
+    /// the number, the status from the front matter (empty if there is none)
+    /// and the path, separated by tabs. This format is stable, scripts can
+    /// rely on it.
+    Porcelain,
+    /// CSV with a header row, for spreadsheets: the number, the title and
+    /// status from the front matter, and the path, or what `--fields` says.
+    Csv,
+    /// A JSON array with an object per RFC, in `--sort` order: the number,
+    /// the title and status from the front matter if it has them, and the
+    /// path, or what `--fields` says. On a single line, unless `--pretty`.
+    Json,
+    /// The objects of `--format json`, one per line instead of in an array,
+    /// for log pipelines.
+    Ndjson,
+    /// Aligned columns with a header: the number, title, status and path, or
+    /// what `--fields` says. Titles are cut short to fit the terminal.
+    Table,
+}
+
+/// What `list --fields` can show of an RFC. All but the number and path come
+/// from the front matter.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Number,
+    Title,
+    Status,
+    Author,
+    Path,
+    /// The one of `rfc_dirs` the RFC is in, `.` for the whole repository.
+    Root,
+}
+
+impl Field {
+    const DEFAULT: [Field; 4] = [Field::Number, Field::Title, Field::Status, Field::Path];
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Number => "number",
+            Field::Title => "title",
+            Field::Status => "status",
+            Field::Author => "author",
+            Field::Path => "path",
+            Field::Root => "root",
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct ExportArgs {
+    /// Only export these RFCs: numbers, paths, ranges like `10-12`, or `all`.
+    targets: Vec<TargetSet>,
+    /// Write the document to this file instead of stdout.
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Only export RFCs with this status, ignoring case. Can be given more
+    /// than once.
+    #[arg(long)]
+    status: Vec<String>,
+    /// Only export RFCs numbered at least this.
+    #[arg(long, value_name = "N")]
+    from: Option<usize>,
+    /// Only export RFCs numbered at most this.
+    #[arg(long, value_name = "N")]
+    to: Option<usize>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct OpenArgs {
+    number: usize,
+    /// Open the RFC's file on the default branch.
+    #[arg(long, conflicts_with = "pr")]
+    file: bool,
+    /// Open the pull request for the RFC's branch.
+    #[arg(long)]
+    pr: bool,
+    /// Print the URL instead of opening it.
+    #[arg(long)]
+    print: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct ShowArgs {
+    /// The RFCs' numbers or paths, ranges like `10-12`, or `all`. A path that
+    /// isn't a file is taken as a fragment of an RFC's title.
+    #[arg(required_unless_present = "range", num_args = 1..)]
+    targets: Vec<TargetSet>,
+    /// Print the RFCs numbered within a range like `10..14` one after the
+    /// other, each under a line with its number and title. Numbers without
+    /// an RFC are noted and skipped.
+    #[arg(long, value_name = "A..B", value_parser = parse_range, conflicts_with_all = ["targets", "metadata", "at", "raw_bytes"])]
+    range: Option<(usize, usize)>,
+    /// Print only the RFC's number and front matter, as `key: value` lines.
+    #[arg(long)]
+    metadata: bool,
+    /// Print the metadata as a JSON object instead.
+    #[arg(long, requires = "metadata")]
+    json: bool,
+    /// Show the RFC as it was at this commit, branch or tag instead of as it
+    /// is in the working directory. Takes a single RFC.
+    #[arg(long, value_name = "REF")]
+    at: Option<String>,
+    /// Print the file's exact bytes, for RFCs that aren't valid UTF-8 or
+    /// have binary content embedded. Otherwise, invalid UTF-8 is replaced.
+    #[arg(long, conflicts_with = "metadata")]
+    raw_bytes: bool,
+}
+
+/// A `show --range`, like `10..14` or `10-14`.
+fn parse_range(range: &str) -> Result<(usize, usize)> {
+    let Some((from, to)) = range.split_once("..").or_else(|| range.split_once('-')) else {
+        bail!("'{}' isn't a range, give one like 10..14.", range)
+    };
+    let (from, to): (usize, usize) = (from.trim().parse()?, to.trim().parse()?);
+    if from > to {
+        bail!("{} is an empty range", range)
+    }
+    Ok((from, to))
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct CreateArgs {
+    #[arg(required_unless_present = "interactive")]
+    title: Option<String>,
+    /// Ask for the title, status, author and labels that aren't given as
+    /// arguments. Needs a terminal.
+    #[arg(short, long)]
+    interactive: bool,
+    /// The status the RFC starts out with, instead of `draft`.
+    #[arg(long, value_name = "STATUS")]
+    status: Option<String>,
+    /// Who wrote the RFC, recorded as `author` in its front matter.
+    #[arg(long, value_name = "NAME")]
+    author: Option<String>,
+    #[command(flatten)]
+    numbering: NumberingArgs,
+    /// Create the RFC branch, but stay on the current branch.
+    #[arg(long)]
+    no_checkout: bool,
+    /// Base the RFC branch on this branch instead of the default branch.
+    #[arg(long, value_name = "BRANCH")]
+    base: Option<String>,
+    /// Print what was created as `key value` lines instead of messages.
+    #[arg(long)]
+    porcelain: bool,
+    /// Start the RFC from this template instead of the default one, see
+    /// `rfcs templates`.
+    #[arg(long, value_name = "NAME")]
+    from_template: Option<String>,
+    /// Start the RFC from a copy of this RFC's text instead of a template,
+    /// under its own front matter, and record it as `derived_from`.
+    #[arg(long, value_name = "N", conflicts_with = "from_template")]
+    copy_from: Option<usize>,
+    /// Use this number instead of the next free one, e.g. one reserved
+    /// ahead of time. No file or branch may use it yet.
+    #[arg(long, value_name = "N")]
+    number: Option<usize>,
+    /// Only show the output of the `hooks.post_create` command if it fails.
+    /// Implied by `--porcelain`.
+    #[arg(long)]
+    capture_hook_output: bool,
+    /// If there already is an RFC branch named like the title, switch to it
+    /// instead of failing.
+    #[arg(long, conflicts_with = "force_new")]
+    reuse: bool,
+    /// Create a new RFC even if there already is an RFC branch named like the
+    /// title. The new one's name gets a suffix, like `-2`.
+    #[arg(long)]
+    force_new: bool,
+    /// Open the new RFC in your editor once it's created, see `rfcs edit`.
+    #[arg(long, conflicts_with = "no_checkout")]
+    edit: bool,
+    /// Switch to the new RFC branch and stage the RFC there, but don't
+    /// commit it, so that it can be reviewed first.
+    #[arg(long, conflicts_with = "no_checkout")]
+    no_commit: bool,
+    /// Categorize the RFC, e.g. by area like `api` or `infra`. Repeat it for
+    /// more labels; they go into the `labels` front matter field.
+    #[arg(long, alias = "tag", value_name = "LABEL", value_parser = parse_label)]
+    label: Vec<String>,
+    /// The number of an RFC the new one replaces, recorded as `supersedes`
+    /// in its front matter.
+    #[arg(long, value_name = "N")]
+    supersedes: Option<usize>,
+    /// The number of an RFC the new one changes, recorded as `amends` in its
+    /// front matter.
+    #[arg(long, value_name = "N")]
+    amends: Option<usize>,
+    /// Also set `superseded_by` in the front matter of the `--supersedes`
+    /// RFC, in a second commit on the new branch.
+    #[arg(long, requires = "supersedes")]
+    mark_superseded: bool,
+    /// Put the RFC into this subdirectory of the RFC directory, e.g. to sort
+    /// RFCs by topic. It's created if need be; numbers stay shared.
+    #[arg(long, value_name = "SUBDIR", value_parser = parse_subdir)]
+    subdir: Option<PathBuf>,
+    /// Only write the RFC's file, numbered by the RFC files alone, without
+    /// creating a branch or committing, or running the `post_create` hook.
+    /// For repositories that don't keep a branch per RFC.
+    #[arg(long, conflicts_with_all = ["no_checkout", "base", "reuse", "force_new", "no_commit", "mark_superseded"])]
+    file_only: bool,
+}
+
+/// A `--label`, which has to fit into the `labels: [a, b]` front matter list.
+fn parse_label(label: &str) -> Result<String> {
+    let label = label.trim();
+    if label.is_empty() || label.contains([',', '[', ']', '\n', '\r']) {
+        bail!(
+            "'{}' can't be a label, labels can't be empty or contain , [ or ].",
+            label
+        )
+    }
+    Ok(label.to_string())
+}
+
+/// A `--subdir`, which has to stay inside the RFC directory.
+fn parse_subdir(dir: &str) -> Result<PathBuf> {
+    let subdir = PathBuf::from(dir);
+    let escapes = subdir
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)));
+    if dir.is_empty() || escapes {
+        bail!(
+            "'{}' isn't a directory inside the RFC directory, give a relative \
+             path like api.",
+            dir
+        )
+    }
+    Ok(subdir)
+}
+
+/// Options for how the next RFC number is worked out.
+#[derive(clap::Args, Debug, Clone)]
+struct NumberingArgs {
+    /// Whether RFC files that aren't tracked by git yet count towards the
+    /// next number.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set, value_name = "BOOL")]
+    include_untracked: bool,
+    /// Don't count RFC branches already merged into the default branch
+    /// towards the next number. Their RFCs are on the default branch anyway,
+    /// unless they were renumbered.
+    #[arg(long)]
+    exclude_merged: bool,
+    /// The one of `rfc_dirs` the RFC is numbered for, and goes into.
+    /// Defaults to the first.
+    #[arg(long, value_name = "DIR")]
+    dir: Option<PathBuf>,
+}
+
+fn lowercase(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// An RFC as given on the command line: either its number, or a path to its
+/// file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Number(usize),
+    Path(PathBuf),
+}
+
+impl FromStr for Target {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(number) if s.chars().all(|c| c.is_ascii_digit()) => Ok(Target::Number(number)),
+            _ => Ok(Target::Path(PathBuf::from(s))),
+        }
+    }
+}
+
+/// Any number of RFCs as given on the command line: one `Target`, the RFCs
+/// numbered within a range like `10-12`, or `all` of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TargetSet {
+    One(Target),
+    Range(usize, usize),
+    All,
+}
+
+impl FromStr for TargetSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(TargetSet::All);
+        }
+        let range = s.split_once('-').filter(|(from, to)| {
+            [from, to]
+                .iter()
+                .all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        });
+        match range {
+            Some((from, to)) => {
+                let (from, to): (usize, usize) = (
+                    from.parse().map_err(|e| format!("{}", e))?,
+                    to.parse().map_err(|e| format!("{}", e))?,
+                );
+                if from > to {
+                    return Err(format!("{} is an empty range", s));
+                }
+                Ok(TargetSet::Range(from, to))
+            }
+            None => Ok(TargetSet::One(
+                s.parse().expect("Parsing a Target can't fail"),
+            )),
+        }
+    }
+}
+
+impl TargetSet {
+    /// The RFCs among `files` in `sets`, each once, in the order given;
+    /// ranges and `all` in order of number. Every single target must exist,
+    /// and every range must have at least one RFC. With `search`, single
+    /// targets are resolved like `Target::resolve_or_search`.
+    fn expand(
+        sets: &[TargetSet],
+        repo: &Path,
+        files: &[PathBuf],
+        scan: &Scan,
+        search: bool,
+    ) -> Result<Vec<PathBuf>> {
+        let mut by_number: Vec<&PathBuf> = files.iter().collect();
+        by_number.sort_by_cached_key(|f| (scan.file_number(f), *f));
+        let mut expanded: Vec<PathBuf> = vec![];
+        for set in sets {
+            let found = match set {
+                TargetSet::One(target) if search => {
+                    vec![target.resolve_or_search(repo, files, scan)?]
+                }
+                TargetSet::One(target) => vec![target.resolve(repo, files, scan)?],
+                TargetSet::Range(from, to) => {
+                    let found: Vec<PathBuf> = by_number
+                        .iter()
+                        .filter(|f| number_in_range(f, scan, Some(*from), Some(*to)))
+                        .map(|f| f.to_path_buf())
+                        .collect();
+                    if found.is_empty() {
+                        bail!("There is no RFC numbered {:03} to {:03}.", from, to)
+                    }
+                    found
+                }
+                TargetSet::All => by_number.iter().map(|f| f.to_path_buf()).collect(),
+            };
+            for file in found {
+                if !expanded.contains(&file) {
+                    expanded.push(file);
+                }
+            }
+        }
+        Ok(expanded)
+    }
+}
+
+impl Target {
+    /// Finds the file this target refers to among `files`, the RFCs in the
+    /// repository at `repo`. Paths are taken as they are if they exist, and
+    /// relative to the repository otherwise. If the repository ignores case,
+    /// paths match RFCs regardless of case, and resolve to the RFC's own
+    /// spelling.
+    fn resolve(&self, repo: &Path, files: &[PathBuf], scan: &Scan) -> Result<PathBuf> {
+        if let Target::Path(path) = self {
+            if git::ignore_case(repo) {
+                let wanted = [lowercase(path), lowercase(&repo.join(path))];
+                if let Some(file) = files.iter().find(|f| wanted.contains(&lowercase(f))) {
+                    return Ok(file.clone());
+                }
+            }
+        }
+        match self {
+            Target::Number(number) => {
+                let matches: Vec<&PathBuf> = files
+                    .iter()
+                    .filter(|f| scan.file_number(f) == Some(*number))
+                    .collect();
+                match matches.as_slice() {
+                    [file] => Ok(file.to_path_buf()),
+                    [] => bail!("There is no RFC numbered {:03}.", number),
+                    _ => bail!(
+                        "More than one file is numbered {:03}: {}",
+                        number,
+                        matches
+                            .iter()
+                            .map(|f| f.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+            }
+            Target::Path(path) if path.is_file() => Ok(path.clone()),
+            Target::Path(path) if repo.join(path).is_file() => Ok(repo.join(path)),
+            Target::Path(path) => bail!(
+                "There is no file at {}, nor at {}.",
+                path.display(),
+                repo.join(path).display()
+            ),
+        }
+    }
+
+    /// Like `resolve`, but a path that isn't a file is also taken as a
+    /// fragment of an RFC's title, matched against the file names and the
+    /// titles in the front matter of `files`, see `fuzzy::score`. The best
+    /// match wins, several equally good ones are listed.
+    fn resolve_or_search(&self, repo: &Path, files: &[PathBuf], scan: &Scan) -> Result<PathBuf> {
+        let not_found = match self.resolve(repo, files, scan) {
+            Ok(file) => return Ok(file),
+            Err(e) => e,
+        };
+        let Target::Path(query) = self else {
+            return Err(not_found);
+        };
+        let query = query.to_string_lossy();
+
+        match title_matches(&query, files).as_slice() {
+            [] => bail!("{} No RFC's title matches '{}' either.", not_found, query),
+            [file] => Ok(file.to_path_buf()),
+            matches => bail!(
+                "'{}' matches more than one RFC: {}",
+                query,
+                matches
+                    .iter()
+                    .map(|f| f.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// The `files` whose name or front matter title match `query` best, going by
+/// `fuzzy::score`, in order. Empty if none match at all.
+fn title_matches<'f>(query: &str, files: &'f [PathBuf]) -> Vec<&'f PathBuf> {
+    let mut matches: Vec<(u32, &PathBuf)> = files
+        .iter()
+        .filter_map(|file| {
+            let name = file.file_stem().unwrap_or_default().to_string_lossy();
+            let title = file_front_matter(file)
+                .ok()
+                .and_then(|fm| fm.get("title").map(String::from))
+                .unwrap_or_default();
+            let score = fuzzy::score(query, &format!("{} {}", name, title))?;
+            Some((score, file))
+        })
+        .collect();
+    let best = matches.iter().map(|(score, _)| *score).max();
+    matches.retain(|(score, _)| Some(*score) == best);
+    matches.sort();
+    matches.into_iter().map(|(_, file)| file).collect()
+}
+
+/// Flags that apply to every subcommand.
+#[derive(clap::Args, Debug, Clone)]
+struct Globals {
+    /// Don't print informational messages, only errors.
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    /// Read and write this config file instead of the global one.
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Answer yes to every confirmation, for running non-interactively.
+    #[arg(short, long, global = true)]
+    yes: bool,
+    /// Go ahead without asking when stdin isn't a terminal, as non_tty =
+    /// "allow" in the config does. Still asks on a terminal.
+    #[arg(long, global = true)]
+    assume_yes_on_nontty: bool,
+    /// What confirmations do without a terminal, from the config and
+    /// `--assume-yes-on-nontty`.
+    #[arg(skip)]
+    non_tty: NonTtyPolicy,
+    /// Log the commands run to stderr, for troubleshooting. Given twice,
+    /// also every git operation, with the branches, refs and paths involved.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Have libgit2 log what it does internally to stderr, at this level or
+    /// worse, for debugging clones, fetches and authentication. Credentials
+    /// are redacted.
+    #[arg(long, global = true, value_enum, value_name = "LEVEL")]
+    trace_git: Option<trace::GitTraceLevel>,
+    /// Fail instead of cloning git.url when there is no local checkout yet.
+    #[arg(long, global = true)]
+    no_clone: bool,
+    /// Clone git.url into this directory instead of git.clone_dir, when there
+    /// is no local checkout yet.
+    #[arg(long, global = true, value_name = "DIR")]
+    clone_dir: Option<PathBuf>,
+    /// Give up on network operations once this many seconds have passed in
+    /// total, for a hard cap in CI. Cloning still stops at
+    /// git.clone_timeout_secs if that comes first.
+    #[arg(long, global = true, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+    /// When `timeout` runs out, counted from the start of the run.
+    #[arg(skip)]
+    deadline: platform::Deadline,
+    /// Look for RFCs behind symbolic links too. Without it, symbolic links
+    /// are skipped, whether they point at files or directories.
+    #[arg(long, global = true)]
+    follow_symlinks: bool,
+    /// How RFC files are found, from these flags and the config. Set once
+    /// the config is loaded.
+    #[arg(skip)]
+    scan: Scan,
+    /// Find RFC numbers in file and branch names with this regex, which
+    /// needs a group named `rfc_number`. Overrides numbering.pattern.
+    #[arg(long, global = true, value_name = "REGEX")]
+    pattern: Option<String>,
+    /// Only look for RFCs this many directories deep below each RFC
+    /// directory, 1 being only the files right in it. Overrides max_depth.
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_depth: Option<u64>,
+    /// When to color output. `auto` colors when printing to a terminal,
+    /// unless `$NO_COLOR` is set.
+    #[arg(long, global = true, value_enum, default_value_t = color::ColorChoice::Auto)]
+    color: color::ColorChoice,
+    /// Treat warnings as errors, for CI: fail instead of carrying on.
+    #[arg(long, global = true)]
+    strict: bool,
+    /// How to print the error when a command fails.
+    #[arg(long, global = true, value_enum, ignore_case = true, default_value_t = ErrorFormat::Text)]
+    error_format: ErrorFormat,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorFormat {
+    /// `Error: ` and the message, followed by its causes.
+    Text,
+    /// A JSON object on a single line, for tools wrapping `rfcs`: the exit
+    /// `code`, the `message`, and the `causes` behind it, outermost first.
+    Json,
+}
+
+impl Globals {
+    /// Prints an informational line to stdout, unless `--quiet` is set.
+    fn info(&self, message: impl std::fmt::Display) {
+        if !self.quiet {
+            println!("{}", message);
+        }
+    }
+
+    /// Prints a warning to stderr, or with `--strict`, fails with it.
+    fn warn(&self, message: impl std::fmt::Display) -> Result<()> {
+        if self.strict {
+            bail!("{}", message)
+        }
+        eprintln!("Warning: {}", message);
+        Ok(())
+    }
+
+    /// Asks the user to confirm `prompt` on stdin. `--yes` confirms without
+    /// asking; when stdin isn't a terminal there's nobody to ask, so the
+    /// answer is no, unless the non-TTY policy is `allow`.
+    fn confirm(&self, prompt: impl std::fmt::Display) -> Result<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+        if !std::io::stdin().is_terminal() {
+            return Ok(match self.non_tty {
+                NonTtyPolicy::Allow => {
+                    self.info(format_args!("{} Yes, stdin isn't a terminal.", prompt));
+                    true
+                }
+                NonTtyPolicy::Deny => false,
+            });
+        }
+
+        eprint!("{} [y/N] ", prompt);
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        std::io::stdin()
+            .read_line(&mut answer)
+            .context("Failed to read the answer from stdin")?;
+        Ok(is_yes(&answer))
+    }
+
+    /// How RFC files are found, as the flags and config say.
+    fn scan(&self) -> &Scan {
+        &self.scan
+    }
+
+    /// Whether output to stdout is colored, see `color::ColorChoice`.
+    fn color(&self) -> bool {
+        self.color
+            .enabled(std::io::stdout().is_terminal(), platform::process_env)
+    }
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn main() -> std::process::ExitCode {
+    let args = Args::parse();
+    let error_format = args.globals.error_format;
+    match run(args) {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", format_error(&e, error_format));
+            std::process::ExitCode::from(ERROR_EXIT_CODE)
+        }
+    }
+}
+
+/// What `rfcs` exits with when a command fails.
+const ERROR_EXIT_CODE: u8 = 1;
+
+/// `e` the way `--error-format` asks for. The text format is what returning
+/// the error from `main` would print, without a backtrace.
+fn format_error(e: &anyhow::Error, format: ErrorFormat) -> String {
+    match format {
+        ErrorFormat::Text => {
+            let mut text = format!("Error: {}", e);
+            let causes: Vec<String> = e.chain().skip(1).map(|c| c.to_string()).collect();
+            match causes.as_slice() {
+                [] => {}
+                [cause] => text.push_str(&format!("\n\nCaused by:\n    {}", cause)),
+                causes => {
+                    text.push_str("\n\nCaused by:");
+                    for (i, cause) in causes.iter().enumerate() {
+                        text.push_str(&format!("\n    {}: {}", i, cause));
+                    }
+                }
+            }
+            text
+        }
+        ErrorFormat::Json => {
+            let causes: Vec<String> = e.chain().skip(1).map(|c| c.to_string()).collect();
+            serde_json::json!({
+                "code": ERROR_EXIT_CODE,
+                "message": e.to_string(),
+                "causes": causes,
+            })
+            .to_string()
+        }
+    }
+}
+
+fn run(mut args: Args) -> Result<()> {
+    trace::init(args.globals.verbose);
+    if let Some(level) = args.globals.trace_git {
+        trace::set_git_tracing(Some(level));
+    }
+    if let Some(secs) = args.globals.timeout {
+        args.globals.deadline = platform::Deadline::after(std::time::Duration::from_secs(secs));
+    }
+    if let Some(file) = &args.globals.config {
+        let cwd = std::env::current_dir().context("Can't tell the current directory")?;
+        config::override_config_path(cwd.join(file));
+    }
+    // Runs before loading the config, because a broken config is one of the
+    // things it's meant to diagnose.
+    if let Command::Doctor { ping, fix, commit } = args.command {
+        return cmd_doctor(&args.globals, ping, fix, commit);
+    }
+    // Likewise, a bug report shouldn't need a working config.
+    if let Command::Version = args.command {
+        print!("{}", version_info(args.globals.verbose > 0));
+        return Ok(());
+    }
+    // Likewise, so that a config file that doesn't parse can be found.
+    if let Command::ConfigPath = args.command {
+        println!("{}", config_path()?.display());
+        return Ok(());
+    }
+    // Likewise, so that a config file that doesn't parse can be fixed.
+    if let Command::Configure {
+        edit: true, local, ..
+    } = args.command
+    {
+        let scope = if local { Scope::Local } else { Scope::Global };
+        return cmd_config_edit(&args.globals, scope);
+    }
+    let layers = config::load_layers()?;
+    for deprecation in &layers.deprecations {
+        args.globals.warn(deprecation)?;
+    }
+    let config = layers.effective();
+    let mut globals = args.globals;
+    globals.scan = Scan {
+        follow_symlinks: globals.follow_symlinks,
+        number_in_front_matter: config.number_in_front_matter.unwrap_or(false),
+        max_depth: globals
+            .max_depth
+            .map(|depth| depth as usize)
+            .or(config.max_depth),
+        preferred_extensions: config.preferred_extensions.clone(),
+        pattern: match &globals.pattern {
+            Some(pattern) => Some(config::check_pattern(pattern)?),
+            None => config.pattern()?,
+        },
+    };
+    globals.non_tty = match globals.assume_yes_on_nontty {
+        true => NonTtyPolicy::Allow,
+        false => config.non_tty.unwrap_or_default(),
+    };
+    match args.command {
+        Command::List(list_args) => cmd_list(&globals, config, &list_args),
+        Command::DumpInfo => cmd_dump_info(),
+        Command::ConfigGet { key } => cmd_config_get(&globals, &key),
+        Command::Configure {
+            local,
+            dry_run,
+            key: Some(key),
+            value: Some(value),
+            ..
+        } => {
+            let scope = if local { Scope::Local } else { Scope::Global };
+            cmd_config(&globals, scope, key, value, dry_run)
+        }
+        Command::Configure { .. } => unreachable!("clap requires key and value without --edit"),
+        Command::Create(create_args) => cmd_create(&globals, config, &create_args),
+        Command::Import {
+            path,
+            no_commit,
+            numbering,
+        } => cmd_import(&globals, config, &path, no_commit, &numbering),
+        Command::BranchName { title, numbering } => {
+            cmd_branch_name(&globals, config, &title, &numbering)
+        }
+        Command::Doctor { .. } | Command::Version | Command::ConfigPath => {
+            unreachable!("handled before loading the config")
+        }
+        Command::Next(numbering) => cmd_next(&globals, config, &numbering),
+        Command::Probe { url } => cmd_probe(&globals, config, &url),
+        Command::Reserve {
+            number,
+            release,
+            numbering,
+        } => cmd_reserve(&globals, config, number, release, &numbering),
+        Command::Show(show_args) => cmd_show(&globals, config, &show_args),
+        Command::Touch { target } => cmd_touch(&globals, config, &target),
+        Command::Rename { target, title, .. } => {
+            cmd_rename(&globals, config, &target, title.as_deref())
+        }
+        Command::Promote { targets, status } => cmd_promote(&globals, config, &targets, &status),
+        Command::Log { target } => cmd_log(&globals, config, &target),
+        Command::Diff { target, from } => cmd_diff(&globals, config, &target, from.as_deref()),
+        Command::Merge {
+            number,
+            keep_status,
+        } => cmd_merge(&globals, config, number, keep_status),
+        Command::Edit {
+            target,
+            editor,
+            create_if_missing,
+        } => cmd_edit(&globals, config, &target, editor, create_if_missing),
+        Command::Templates { validate } => cmd_templates(&globals, config, validate),
+        Command::Open(open_args) => cmd_open(&globals, config, &open_args),
+        Command::Graph { format } => cmd_graph(&globals, config, format),
+        Command::Export(export_args) => cmd_export(&globals, config, &export_args),
+        Command::Prune => cmd_prune(&globals, config),
+        Command::Reconcile => cmd_reconcile(&globals, config),
+        Command::Sync { all } => cmd_sync(&globals, config, all),
+    }
+}
+
+impl ListArgs {
+    /// The statuses to list RFCs with, by `--status` or a shorthand for it.
+    /// Empty for any status.
+    fn statuses(&self) -> Vec<String> {
+        match (self.accepted_only, self.drafts_only) {
+            (true, _) => vec![String::from("accepted")],
+            (_, true) => vec![String::from("draft")],
+            _ => self.status.clone(),
+        }
+    }
+}
+
+fn cmd_list(globals: &Globals, config: Config, args: &ListArgs) -> Result<()> {
+    let scan = globals.scan();
+    if args.watch && std::io::stdout().is_terminal() {
+        let path = local_repo(globals, &config)?;
+        let mut watcher = watch::Polling::new(&path, &git::git_dir(&path)?);
+        let once = ListArgs {
+            watch: false,
+            ..args.clone()
+        };
+        return watch::run(&mut watcher, || {
+            // Clears the screen, and moves the cursor to the top left.
+            print!("\x1b[2J\x1b[H");
+            cmd_list(globals, config.clone(), &once)
+        });
+    }
+    if args.pretty && args.format != ListFormat::Json {
+        bail!("--pretty only applies to --format json.");
+    }
+    if args.envelope && args.format != ListFormat::Json {
+        bail!("--envelope only applies to --format json.");
+    }
+    if args.with_git && !matches!(args.format, ListFormat::Json | ListFormat::Ndjson) {
+        bail!("--with-git only applies to --format json and ndjson.");
+    }
+    if (args.no_truncate || args.max_title_width.is_some()) && args.format != ListFormat::Table {
+        bail!("--no-truncate and --max-title-width only apply to --format table.");
+    }
+    if !args.fields.is_empty()
+        && !matches!(
+            args.format,
+            ListFormat::Csv | ListFormat::Json | ListFormat::Ndjson | ListFormat::Table
+        )
+    {
+        bail!("--fields only applies to --format csv, json, ndjson and table.");
+    }
+    if args.include_unnumbered && config.drafts_dir.is_none() {
+        bail!(
+            "--include-unnumbered lists the drafts in drafts_dir, which isn't set. \
+             Set it with `rfcs configure drafts_dir drafts`."
+        );
+    }
+    let fields = match args.fields.as_slice() {
+        [] => &Field::DEFAULT[..],
+        fields => fields,
+    };
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let rfc_dirs = config.rfc_dirs();
+    let per_dir = config.per_dir_numbering();
+    // The content of each file, when it doesn't come from the file system.
+    let mut committed: HashMap<PathBuf, String> = HashMap::new();
+    let is_rfc = |f: &Path| file_is_text_document(f) && scan.has_rfc_id(f);
+    let (path, files) = if args.committed
+        || args.added_on.is_some()
+        || args.changed_in.is_some()
+        || args.since_tag.is_some()
+    {
+        let path = local_repo(globals, &config)?;
+        let in_tree = match (&args.added_on, &args.changed_in, &args.since_tag) {
+            (Some(branch), _, _) => {
+                let changed = git::changed_on_branch(&path, branch, default_branch.as_deref())?;
+                let mut in_tree = git::files_on_branch(&path, branch, &rfc_dirs, is_rfc)?;
+                in_tree.retain(|(relative, _)| changed.contains(relative));
+                in_tree
+            }
+            (None, Some(range), _) => git::files_changed_in(&path, range, &rfc_dirs, is_rfc)?,
+            (None, None, Some(tag)) => git::files_added_since(&path, tag, &rfc_dirs, is_rfc)?,
+            (None, None, None) => {
+                git::committed_files(&path, default_branch.as_deref(), &rfc_dirs, is_rfc)?
+            }
+        };
+        let mut files = vec![];
+        for (relative, content) in in_tree {
+            let file = path.join(relative);
+            committed.insert(file.clone(), String::from_utf8_lossy(&content).to_string());
+            files.push(file);
+        }
+        (path, files)
+    } else {
+        let path = ensure_local_repo(globals, &config)?;
+        let files = rfc_files(&path, &rfc_dirs, scan)?;
+        (path, files)
+    };
+    let mut files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|f| number_in_range(f, scan, args.from, args.to))
+        .collect();
+    // Without a number, drafts are never within --from or --to.
+    if let Some(drafts_dir) = config
+        .drafts_dir
+        .as_ref()
+        .filter(|_| args.include_unnumbered)
+    {
+        if args.from.is_none() && args.to.is_none() {
+            files.extend(unnumbered_drafts(&path.join(drafts_dir), scan)?);
+        }
+    }
+    if args.exists_only {
+        files.retain(|f| f.is_file());
+    }
+    if args.modified {
+        let modified = git::modified_files(&path)?;
+        files.retain(|f| {
+            git::relative_to_repo(&path, f).is_ok_and(|relative| modified.contains(&relative))
+        });
+    }
+    let front_matter_of = |file: &Path| match committed.get(file) {
+        Some(content) => Ok(metadata::parse(content)),
+        None => file_front_matter(file),
+    };
+    if !args.label.is_empty() {
+        let mut labelled = vec![];
+        for file in files {
+            if has_labels(&front_matter_of(&file)?, &args.label, args.any_label) {
+                labelled.push(file);
+            }
+        }
+        files = labelled;
+    }
+    let statuses = args.statuses();
+    if !statuses.is_empty() {
+        let mut with_status = vec![];
+        for file in files {
+            let front_matter = front_matter_of(&file)?;
+            let status = front_matter.get("status").unwrap_or_default();
+            if statuses.iter().any(|s| s.eq_ignore_ascii_case(status)) {
+                with_status.push(file);
+            }
+        }
+        files = with_status;
+    }
+    if args.authored_by_me {
+        let (name, email) =
+            git::identity(&path).context("--authored-by-me goes by who you commit as")?;
+        let first_authors = git::first_authors(&path)?;
+        let mut mine = vec![];
+        for file in files {
+            let first_author = git::relative_to_repo(&path, &file)
+                .ok()
+                .and_then(|relative| first_authors.get(&relative));
+            if is_by(&front_matter_of(&file)?, first_author, &name, &email) {
+                mine.push(file);
+            }
+        }
+        files = mine;
+    }
+    if args.fail_on_empty && files.is_empty() {
+        bail!("No RFCs match, and --fail-on-empty was given.");
+    }
+
+    let absolute_repo = std::path::absolute(&path)?;
+    // Resolving symlinks in both, so that they don't hide what they share.
+    let canonical = |dir: &Path| dir.canonicalize().or_else(|_| std::path::absolute(dir));
+    let base_path = match &args.base_path {
+        Some(base) => Some((canonical(base)?, canonical(&path)?)),
+        None => None,
+    };
+    let shown = |file: &Path| {
+        let relative = file.strip_prefix(&path).unwrap_or(file);
+        if let Some((base, repo)) = &base_path {
+            let file = repo.join(relative);
+            return path_from(base, &file).unwrap_or(file);
+        }
+        match (args.relative, args.absolute, args.format) {
+            (true, _, _) => relative.to_path_buf(),
+            (_, true, _) => absolute_repo.join(relative),
+            // Porcelain paths stayed as found when the other formats became
+            // repo-relative, for the scripts relying on them.
+            (_, _, ListFormat::Porcelain) => file.to_path_buf(),
+            _ => relative.to_path_buf(),
+        }
+    };
+
+    if args.duplicates {
+        let mut branches = rfc_branches(&path, scan)?;
+        branches.sort();
+        files.sort();
+        print!("{}", duplicates_list(&files, scan, &branches, &shown));
+        return Ok(());
+    }
+
+    let root_of = |file: &Path| {
+        let dir = rfc_dir_of(&path, &rfc_dirs, file)?;
+        Some(match dir.as_os_str().is_empty() {
+            true => String::from("."),
+            false => dir.display().to_string(),
+        })
+    };
+    // Numbered per directory, the same number in two of them is no collision.
+    let groups: Vec<Vec<PathBuf>> = match per_dir {
+        true => rfc_dirs
+            .iter()
+            .map(|dir| {
+                files
+                    .iter()
+                    .filter(|f| rfc_dir_of(&path, &rfc_dirs, f) == Some(dir))
+                    .cloned()
+                    .collect()
+            })
+            .collect(),
+        false => vec![files.clone()],
+    };
+    let collisions: Vec<String> = groups
+        .iter()
+        .flat_map(|group| number_collisions(group, scan))
+        .map(|(number, files)| {
+            let names: Vec<String> = files
+                .iter()
+                .map(|f| shown(f).display().to_string())
+                .collect();
+            format!(
+                "RFC {:03} is used by more than one file: {}",
+                number,
+                names.join(", ")
+            )
+        })
+        .collect();
+    // All of them at once, rather than failing on the first with --strict.
+    if globals.strict && !collisions.is_empty() {
+        bail!("{}", collisions.join("\n"))
+    }
+    for collision in &collisions {
+        globals.warn(collision)?;
+    }
+
+    if args.count {
+        println!("{}", files.len());
+        return Ok(());
+    }
+    if let Some(style) = args.numbers_only {
+        let numbers: BTreeSet<usize> = files.iter().filter_map(|f| scan.file_number(f)).collect();
+        for number in numbers {
+            match style {
+                NumberStyle::Padded => println!("{:03}", number),
+                NumberStyle::Raw => println!("{}", number),
+            }
+        }
+        return Ok(());
+    }
+    sort_files(&path, &mut files, scan, args.sort, &front_matter_of)?;
+    // Paged once sorted, so that the pages follow on from each other.
+    files.drain(..args.offset.unwrap_or(0).min(files.len()));
+    if let Some(limit) = args.limit {
+        files.truncate(limit);
+    }
+
+    if let Some(count_by) = args.count_by {
+        print!("{}", counts_by(&files, scan, count_by, &front_matter_of)?);
+        return Ok(());
+    }
+
+    if args.null {
+        let mut out = std::io::stdout().lock();
+        for f in &files {
+            out.write_all(shown(f).as_os_str().as_encoded_bytes())?;
+            out.write_all(b"\0")?;
+        }
+        out.flush()?;
+        return Ok(());
+    }
+
+    if args.tree {
+        // Several RFC directories are shown as where they are in the repository.
+        let root = match rfc_dirs.as_slice() {
+            [dir] => rfc_root(&path, Some(dir)),
+            _ => path.clone(),
+        };
+        print!(
+            "{}",
+            tree_list(&root, &files, args.group_by, &front_matter_of)?
+        );
+        return Ok(());
+    }
+
+    if args.with_branch {
+        let branches = rfc_branches(&path, scan)?;
+        print!(
+            "{}",
+            with_branch_list(&files, scan, &branches, &shown, !args.exists_only)
+        );
+        return Ok(());
+    }
+
+    let git_stats = match args.with_git {
+        true => git::file_stats(&path)?,
+        false => HashMap::new(),
+    };
+    let git_stats_of = |file: &Path| {
+        let relative = git::relative_to_repo(&path, file).ok()?;
+        git_stats.get(&relative).cloned()
+    };
+
+    match args.format {
+        ListFormat::Human => {
+            files.iter().for_each(|f| match scan.file_number(f) {
+                Some(_) => println!("{}", shown(f).display()),
+                None => println!("{} (number pending)", shown(f).display()),
+            });
+            if args.summary {
+                println!("{}", summary_line(&files, scan, &front_matter_of)?);
+            }
+        }
+        ListFormat::Porcelain => print!(
+            "{}",
+            porcelain_list(&files, scan, &front_matter_of, &shown)?
+        ),
+        ListFormat::Csv => print!(
+            "{}",
+            csv_list(&files, scan, &front_matter_of, &shown, &root_of, fields)?
+        ),
+        ListFormat::Json => {
+            let mut list = json_list(
+                &files,
+                scan,
+                &front_matter_of,
+                &shown,
+                &root_of,
+                &git_stats_of,
+                fields,
+            )?;
+            if args.envelope {
+                list = serde_json::json!({
+                    "version": JSON_SCHEMA_VERSION,
+                    "rfcs": list,
+                });
+            }
+            match args.pretty {
+                true => println!("{}", serde_json::to_string_pretty(&list)?),
+                false => println!("{}", serde_json::to_string(&list)?),
+            }
+        }
+        ListFormat::Ndjson => ndjson_list(
+            &files,
+            &|file| list_row(file, scan, &front_matter_of, &shown, &root_of),
+            &git_stats_of,
+            fields,
+            &mut std::io::stdout().lock(),
+        )?,
+        ListFormat::Table => {
+            let title_width = match (args.no_truncate, args.max_title_width) {
+                (true, _) => TitleWidth::Full,
+                (false, Some(max)) => TitleWidth::Max(max as usize),
+                (false, None) => TitleWidth::Fit(
+                    platform::terminal_width(
+                        std::io::stdout().is_terminal(),
+                        platform::process_env,
+                    )
+                    .unwrap_or(DEFAULT_TERMINAL_WIDTH),
+                ),
+            };
+            print!(
+                "{}",
+                table_list(
+                    &files,
+                    scan,
+                    &front_matter_of,
+                    &shown,
+                    &root_of,
+                    fields,
+                    title_width
+                )?
+            )
+        }
+    }
+
+    Ok(())
+}
+
+/// `target` relative to the directory `base`, going up with `..` where it
+/// has to. Both are absolute. `None` if they don't share a root, like paths
+/// on two Windows drives.
+fn path_from(base: &Path, target: &Path) -> Option<PathBuf> {
+    let mut base = base.components().peekable();
+    let mut target = target.components().peekable();
+    if base.peek() != target.peek() {
+        return None;
+    }
+    while base.peek().is_some() && base.peek() == target.peek() {
+        base.next();
+        target.next();
+    }
+    let mut path: PathBuf = base.map(|_| std::path::Component::ParentDir).collect();
+    path.extend(target);
+    Some(path)
+}
+
+/// Whether the `labels` or `tags` of `front_matter` include all of `wanted`,
+/// or with `any`, one of them. Case doesn't matter.
+fn has_labels(front_matter: &metadata::FrontMatter, wanted: &[String], any: bool) -> bool {
+    let labels: Vec<String> = ["labels", "tags"]
+        .iter()
+        .flat_map(|key| front_matter.list(key))
+        .map(str::to_lowercase)
+        .collect();
+    let has = |label: &String| labels.contains(&label.to_lowercase());
+    match any {
+        true => wanted.iter().any(has),
+        false => wanted.iter().all(has),
+    }
+}
+
+/// Whether the RFC with `front_matter` is by the person named `name` with
+/// `email`: one of its `author`s is them, by name or email, or, without any
+/// `author`, `first_author` (name and email) is.
+fn is_by(
+    front_matter: &metadata::FrontMatter,
+    first_author: Option<&(String, String)>,
+    name: &str,
+    email: &str,
+) -> bool {
+    let authors = front_matter.list("author");
+    if authors.is_empty() {
+        return first_author.is_some_and(|(first_name, first_email)| {
+            first_name == name || (!email.is_empty() && first_email.eq_ignore_ascii_case(email))
+        });
+    }
+    authors.iter().any(|author| {
+        author.eq_ignore_ascii_case(name)
+            || (!email.is_empty() && author.to_lowercase().contains(&email.to_lowercase()))
+    })
+}
+
+/// Looks up the front matter of an RFC, see `file_front_matter`.
+type FrontMatterOf<'a> = dyn Fn(&Path) -> Result<metadata::FrontMatter> + 'a;
+
+/// The `--tree` listing of `files`, which are under `root`. Entries are
+/// sorted by name within each group.
+fn tree_list(
+    root: &Path,
+    files: &[PathBuf],
+    group_by: GroupBy,
+    front_matter_of: &FrontMatterOf,
+) -> Result<String> {
+    // Each entry is the groups it's nested in, outermost first, and its name.
+    let mut entries: Vec<(Vec<String>, String)> = vec![];
+    for file in files {
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        entries.push(match group_by {
+            GroupBy::Directory => (
+                relative
+                    .parent()
+                    .into_iter()
+                    .flat_map(Path::components)
+                    .map(|c| format!("{}/", c.as_os_str().to_string_lossy()))
+                    .collect(),
+                relative
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            GroupBy::Status => (
+                vec![front_matter_of(file)?
+                    .get("status")
+                    .unwrap_or("(no status)")
+                    .to_string()],
+                relative.display().to_string(),
+            ),
+        });
+    }
+    entries.sort();
+
+    let mut out = String::new();
+    let mut previous: &[String] = &[];
+    for (groups, name) in &entries {
+        let shared = previous
+            .iter()
+            .zip(groups)
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (depth, group) in groups.iter().enumerate().skip(shared) {
+            out.push_str(&format!("{}{}\n", "  ".repeat(depth), group));
+        }
+        out.push_str(&format!("{}{}\n", "  ".repeat(groups.len()), name));
+        previous = groups;
+    }
+
+    Ok(out)
+}
+
+/// The front matter of `file`.
+fn file_front_matter(file: &Path) -> Result<metadata::FrontMatter> {
+    fs::File::open(file)
+        .map(std::io::BufReader::new)
+        .and_then(metadata::read)
+        .with_context(|| format!("Failed to read {}", file.display()))
+}
+
+/// Orders `files`, which are in the repository at `path`, for `list --sort`.
+/// Ties are broken by number, then path; drafts without a number go last.
+fn sort_files(
+    path: &Path,
+    files: &mut [PathBuf],
+    scan: &Scan,
+    sort: SortBy,
+    front_matter_of: &FrontMatterOf,
+) -> Result<()> {
+    files.sort_by_cached_key(|f| {
+        let number = scan.file_number(f);
+        (number.is_none(), number, f.clone())
+    });
+    match sort {
+        SortBy::Number => {}
+        SortBy::Modified | SortBy::Created => {
+            let times = git::commit_times(path)?;
+            let modified = git::modified_files(path).unwrap_or_default();
+            let time = |file: &Path| -> i64 {
+                let relative = git::relative_to_repo(path, file).unwrap_or_default();
+                match (times.get(&relative), sort) {
+                    (Some((first, _)), SortBy::Created) => *first,
+                    (Some((_, last)), _) if !modified.contains(&relative) => *last,
+                    _ => fs::metadata(file)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map_or(0, |d| d.as_secs() as i64),
+                }
+            };
+            files.sort_by_cached_key(|f| std::cmp::Reverse(time(f)));
+        }
+        SortBy::Title => {
+            let mut titles = HashMap::new();
+            for file in files.iter() {
+                let title = match front_matter_of(file)?.get("title") {
+                    Some(title) => title.to_string(),
+                    None => file
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                };
+                titles.insert(file.clone(), title.to_lowercase());
+            }
+            files.sort_by_key(|f| titles[f].clone());
+        }
+    }
+    Ok(())
+}
+
+/// The `--with-branch` listing: each of `files` followed by a tab and
+/// whether one of `branches` has its number, then, with `branch_only`, the
+/// RFC branches no file has the number of, ordered by number.
+fn with_branch_list(
+    files: &[PathBuf],
+    scan: &Scan,
+    branches: &[String],
+    shown: &dyn Fn(&Path) -> PathBuf,
+    branch_only: bool,
+) -> String {
+    let branch_numbers: HashSet<usize> =
+        branches.iter().filter_map(|b| scan.rfc_number(b)).collect();
+    let file_numbers: HashSet<usize> = files.iter().filter_map(|f| scan.file_number(f)).collect();
+    let mut out = String::new();
+    for file in files {
+        let state = match scan.file_number(file) {
+            Some(number) if branch_numbers.contains(&number) => "file and branch",
+            _ => "file only",
+        };
+        out.push_str(&format!("{}\t{}\n", shown(file).display(), state));
+    }
+    if !branch_only {
+        return out;
+    }
+    let mut without_file: Vec<(usize, &String)> = branches
+        .iter()
+        .filter_map(|b| Some((scan.rfc_number(b)?, b)))
+        .filter(|(number, _)| !file_numbers.contains(number))
+        .collect();
+    without_file.sort();
+    for (_, branch) in without_file {
+        out.push_str(&format!("{}\tbranch only\n", branch));
+    }
+    out
+}
+
+/// The `--duplicates` listing: a line per number that more than one of
+/// `files` and `branches` use. A branch named like a file with its number is
+/// that RFC's branch, and doesn't count on its own.
+fn duplicates_list(
+    files: &[PathBuf],
+    scan: &Scan,
+    branches: &[String],
+    shown: &dyn Fn(&Path) -> PathBuf,
+) -> String {
+    let is_own_branch = |number: usize, branch: &str| {
+        files.iter().any(|f| {
+            scan.file_number(f) == Some(number) && f.file_stem().is_some_and(|stem| stem == branch)
+        })
+    };
+    let file_users = files
+        .iter()
+        .filter_map(|f| Some((scan.file_number(f)?, shown(f).display().to_string())));
+    let branch_users = branches
+        .iter()
+        .filter_map(|b| Some((scan.rfc_number(b)?, b)))
+        .filter(|(number, b)| !is_own_branch(*number, b))
+        .map(|(number, b)| (number, format!("branch {}", b)));
+    numbers_used_twice(file_users.chain(branch_users))
+        .iter()
+        .map(|(number, users)| format!("{:03}: {}\n", number, users.join(", ")))
+        .collect()
+}
+
+/// The `--format porcelain` listing of `files`, see `ListFormat::Porcelain`.
+/// Changing the columns or their order breaks scripts.
+fn porcelain_list(
+    files: &[PathBuf],
+    scan: &Scan,
+    front_matter_of: &FrontMatterOf,
+    shown: &dyn Fn(&Path) -> PathBuf,
+) -> Result<String> {
+    let mut rows = vec![];
+    for file in files {
+        let status = front_matter_of(file)?
+            .get("status")
+            .unwrap_or_default()
+            .to_string();
+        rows.push((scan.file_number(file), status, shown(file)));
+    }
+
+    Ok(rows
+        .iter()
+        .map(|(number, status, file)| {
+            format!(
+                "{}\t{}\t{}\n",
+                shown_number(*number),
+                status,
+                file.display()
+            )
+        })
+        .collect())
+}
+
+/// The `--count-by` summary of `files`: a line with each value of `count_by`
+/// and how many RFCs have it, separated by a tab, most first. RFCs without
+/// the field count as `(none)`.
+fn counts_by(
+    files: &[PathBuf],
+    scan: &Scan,
+    count_by: CountBy,
+    front_matter_of: &FrontMatterOf,
+) -> Result<String> {
+    Ok(value_counts(files, scan, count_by, front_matter_of)?
+        .iter()
+        .map(|(value, count)| format!("{}\t{}\n", value, count))
+        .collect())
+}
+
+/// The `--summary` line for `files`: how many there are, then how many of
+/// each status, most first.
+fn summary_line(files: &[PathBuf], scan: &Scan, front_matter_of: &FrontMatterOf) -> Result<String> {
+    let total = match files.len() {
+        1 => String::from("1 RFC"),
+        n => format!("{} RFCs", n),
+    };
+    let counts: Vec<String> = value_counts(files, scan, CountBy::Status, front_matter_of)?
+        .iter()
+        .map(|(status, count)| match status.as_str() {
+            "(none)" => format!("{} without a status", count),
+            status => format!("{} {}", count, status),
+        })
+        .collect();
+    Ok(match counts.is_empty() {
+        true => total,
+        false => format!("{}: {}", total, counts.join(", ")),
+    })
+}
+
+/// How many of `files` have each value of `count_by`, most first, and equal
+/// counts by value. RFCs without the field count as `(none)`, which comes
+/// last among equal counts.
+fn value_counts(
+    files: &[PathBuf],
+    scan: &Scan,
+    count_by: CountBy,
+    front_matter_of: &FrontMatterOf,
+) -> Result<Vec<(String, usize)>> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for file in files {
+        let value = match count_by {
+            CountBy::NumberPrefix => scan.file_number(file).map(|n| format!("{}xx", n / 100)),
+            CountBy::Status => front_matter_of(file)?.get("status").map(String::from),
+            CountBy::Author => front_matter_of(file)?.get("author").map(String::from),
+        };
+        *counts
+            .entry(value.unwrap_or_else(|| String::from("(none)")))
+            .or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| {
+        b_count
+            .cmp(a_count)
+            .then_with(|| (a == "(none)").cmp(&(b == "(none)")))
+            .then_with(|| a.cmp(b))
+    });
+    Ok(counts)
+}
+
+/// Which of `rfc_dirs` an RFC is in, if any, see `Field::Root`.
+type RootOf<'a> = dyn Fn(&Path) -> Option<String> + 'a;
+
+/// What git knows about an RFC, for `list --with-git`.
+type GitStatsOf<'a> = dyn Fn(&Path) -> Option<git::FileStats> + 'a;
+
+/// An RFC's number, path as shown, RFC directory and front matter.
+type ListRow = (
+    Option<usize>,
+    PathBuf,
+    Option<String>,
+    metadata::FrontMatter,
+);
+
+/// The `ListRow` of each of `files`.
+fn list_rows(
+    files: &[PathBuf],
+    scan: &Scan,
+    front_matter_of: &FrontMatterOf,
+    shown: &dyn Fn(&Path) -> PathBuf,
+    root_of: &RootOf,
+) -> Result<Vec<ListRow>> {
+    files
+        .iter()
+        .map(|file| list_row(file, scan, front_matter_of, shown, root_of))
+        .collect()
+}
+
+/// The `ListRow` of `file`.
+fn list_row(
+    file: &Path,
+    scan: &Scan,
+    front_matter_of: &FrontMatterOf,
+    shown: &dyn Fn(&Path) -> PathBuf,
+    root_of: &RootOf,
+) -> Result<ListRow> {
+    Ok((
+        scan.file_number(file),
+        shown(file),
+        root_of(file),
+        front_matter_of(file)?,
+    ))
+}
+
+/// An RFC number as listed: padded to three digits, or `pending` for drafts
+/// that don't have one yet.
+fn shown_number(number: Option<usize>) -> String {
+    match number {
+        Some(number) => format!("{:03}", number),
+        None => String::from("pending"),
+    }
+}
+
+/// The `--format csv` listing of `files`, with a column for each of
+/// `fields`. Records end in CRLF, as RFC 4180 has it.
+fn csv_list(
+    files: &[PathBuf],
+    scan: &Scan,
+    front_matter_of: &FrontMatterOf,
+    shown: &dyn Fn(&Path) -> PathBuf,
+    root_of: &RootOf,
+    fields: &[Field],
+) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(vec![]);
+    writer.write_record(fields.iter().map(|f| f.name()))?;
+    for row in list_rows(files, scan, front_matter_of, shown, root_of)? {
+        writer.write_record(fields.iter().map(|field| cell(&row, *field)))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// What `field` of the RFC in `row` is listed as, in text formats. Empty if
+/// it doesn't have it.
+fn cell((number, file, root, front_matter): &ListRow, field: Field) -> String {
+    match field {
+        Field::Number => shown_number(*number),
+        Field::Path => file.display().to_string(),
+        Field::Root => root.clone().unwrap_or_default(),
+        _ => front_matter
+            .get(field.name())
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+/// How wide the titles of `--format table` may get.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TitleWidth {
+    /// As wide as they are, for `--no-truncate`.
+    Full,
+    /// At most this many characters, for `--max-title-width`.
+    Max(usize),
+    /// What the other columns leave of lines this wide.
+    Fit(usize),
+}
+
+/// The terminal width `--format table` fits its titles into when it can't
+/// tell, like when its output is piped.
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+/// Titles aren't cut shorter than this to fit the terminal, however wide
+/// the other columns are.
+const MIN_TITLE_WIDTH: usize = 10;
+
+/// The `--format table` listing of `files`: a header and a row per RFC, with
+/// a column for each of `fields`, padded to line up. Titles are cut short as
+/// `title_width` says.
+fn table_list(
+    files: &[PathBuf],
+    scan: &Scan,
+    front_matter_of: &FrontMatterOf,
+    shown: &dyn Fn(&Path) -> PathBuf,
+    root_of: &RootOf,
+    fields: &[Field],
+    title_width: TitleWidth,
+) -> Result<String> {
+    let mut table: Vec<Vec<String>> =
+        vec![fields.iter().map(|f| f.name().to_uppercase()).collect()];
+    for row in list_rows(files, scan, front_matter_of, shown, root_of)? {
+        table.push(fields.iter().map(|field| cell(&row, *field)).collect());
+    }
+    let widths = |table: &[Vec<String>]| -> Vec<usize> {
+        (0..fields.len())
+            .map(|i| {
+                table
+                    .iter()
+                    .map(|row| color::width(&row[i]))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+
+    let max_title = match title_width {
+        TitleWidth::Full => None,
+        TitleWidth::Max(max) => Some(max),
+        TitleWidth::Fit(line) => {
+            let untruncated = widths(&table);
+            let others: usize = fields
+                .iter()
+                .zip(&untruncated)
+                .filter(|(field, _)| **field != Field::Title)
+                .map(|(_, width)| width + 2)
+                .sum();
+            Some(line.saturating_sub(others).max(MIN_TITLE_WIDTH))
+        }
+    };
+    if let (Some(max), Some(title)) = (max_title, fields.iter().position(|f| *f == Field::Title)) {
+        for row in table.iter_mut().skip(1) {
+            row[title] = color::truncate(&row[title], max);
+        }
+    }
+
+    let widths = widths(&table);
+    let mut out = String::new();
+    for row in &table {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - color::width(cell))))
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The `version` of `list --format json --envelope`. It goes up when members
+/// are renamed or removed, or change what they hold; new members don't count.
+const JSON_SCHEMA_VERSION: i64 = 1;
+
+/// The `--format json` listing of `files`, see `ListFormat::Json`, with a
+/// member for each of `fields` the RFC has.
+fn json_list(
+    files: &[PathBuf],
+    scan: &Scan,
+    front_matter_of: &FrontMatterOf,
+    shown: &dyn Fn(&Path) -> PathBuf,
+    root_of: &RootOf,
+    git_stats_of: &GitStatsOf,
+    fields: &[Field],
+) -> Result<serde_json::Value> {
+    let mut list = vec![];
+    let rows = list_rows(files, scan, front_matter_of, shown, root_of)?;
+    for (file, (number, shown, root, front_matter)) in files.iter().zip(rows) {
+        let mut row = json_row(number, &shown, root, &front_matter, fields);
+        add_git_stats(&mut row, git_stats_of(file));
+        list.push(row);
+    }
+    Ok(serde_json::Value::Array(list))
+}
+
+/// The `--format ndjson` listing of `files`, written to `out` a line at a
+/// time. `files` are found and sorted before the first line.
+fn ndjson_list(
+    files: &[PathBuf],
+    row_of: &dyn Fn(&Path) -> Result<ListRow>,
+    git_stats_of: &GitStatsOf,
+    fields: &[Field],
+    out: &mut impl Write,
+) -> Result<()> {
+    for file in files {
+        let (number, shown, root, front_matter) = row_of(file)?;
+        let mut row = json_row(number, &shown, root, &front_matter, fields);
+        add_git_stats(&mut row, git_stats_of(file));
+        writeln!(out, "{}", serde_json::to_string(&row)?)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// The JSON object for an RFC, with a member for each of `fields` it has.
+fn json_row(
+    number: Option<usize>,
+    file: &Path,
+    root: Option<String>,
+    front_matter: &metadata::FrontMatter,
+    fields: &[Field],
+) -> serde_json::Value {
+    let mut row = serde_json::Map::new();
+    for field in fields {
+        let value = match field {
+            Field::Number => number.map(serde_json::Value::from),
+            Field::Path => Some(serde_json::Value::from(file.display().to_string())),
+            Field::Root => root.clone().map(serde_json::Value::String),
+            _ => front_matter.get(field.name()).map(serde_json::Value::from),
+        };
+        if let Some(value) = value {
+            row.insert(field.name().to_string(), value);
+        }
+    }
+    serde_json::Value::Object(row)
+}
+
+/// Adds `stats` to the JSON object `row`, for `list --with-git`: the dates
+/// of the first and last commit, the last author, and the number of commits.
+fn add_git_stats(row: &mut serde_json::Value, stats: Option<git::FileStats>) {
+    let (Some(stats), Some(row)) = (stats, row.as_object_mut()) else {
+        return;
+    };
+    let date = |time: i64| commit_date(time).to_string();
+    row.insert(
+        String::from("first_commit"),
+        date(stats.first_commit).into(),
+    );
+    row.insert(String::from("last_commit"), date(stats.last_commit).into());
+    row.insert(String::from("last_author"), stats.last_author.into());
+    row.insert(String::from("commits"), stats.commits.into());
+}
+
+fn cmd_dump_info() -> Result<()> {
+    let layers = config::load_layers()?;
+    let config = layers.effective();
+
+    match config_path() {
+        Ok(path) => println!("Configuration location: {}", path.display()),
+        Err(e) => println!("Configuration location: none, {:#}", e),
+    }
+    for key in config::KEYS {
+        let value = match (key.get)(&config) {
+            Some(_) if key.secret => String::from("<redacted>"),
+            value => format!("{:?}", value),
+        };
+        let source = match layers.source_of(|c| (key.get)(c).is_some()) {
+            Some(source) => format!(" (from {})", source),
+            None => String::new(),
+        };
+        println!("{}: {}{}", key.name, value, source);
+    }
+    Ok(())
+}
+
+fn cmd_config_get(globals: &Globals, key: &str) -> Result<()> {
+    if let Some(deprecation) = config::deprecation(key) {
+        globals.warn(deprecation)?;
+    }
+    let config_key = config::find_key(key)?;
+    if config_key.secret {
+        bail!("{} is secret, it isn't printed.", key)
+    }
+    match (config_key.get)(&config::load_layers()?.effective()) {
+        Some(value) => println!("{}", value),
+        None => bail!("{} is not set.", key),
+    }
+    Ok(())
+}
+
+fn cmd_config(
+    globals: &Globals,
+    scope: Scope,
+    key: String,
+    value: String,
+    dry_run: bool,
+) -> Result<()> {
+    if let Some(deprecation) = config::deprecation(&key) {
+        globals.warn(deprecation)?;
+    }
+    let config_key = config::find_key(&key)?;
+    // Only the file being written to is loaded, so that values layered in
+    // from the other scope don't leak into it.
+    let mut config = config::load_scope(scope)?;
+    let path = config::scope_path(scope)?;
+    if dry_run {
+        let before = config.clone();
+        (config_key.set)(&mut config, &value)?;
+        let changes = config::changes(&before, &config);
+        if changes.is_empty() {
+            println!("No change to {}.", path.display());
+        }
+        for change in changes {
+            println!("{}", change);
+        }
+        globals.info(format_args!("Dry run, {} was not written.", path.display()));
+        return Ok(());
+    }
+    let shown = match config_key.secret {
+        true => "<redacted>",
+        false => value.as_str(),
+    };
+    globals.info(format_args!("Setting key {} to value {}", &key, shown));
+    (config_key.set)(&mut config, &value)?;
+
+    config::write_config_to(&path, &config)?;
+    globals.info(format_args!("Wrote config to {}.", path.display()));
+
+    Ok(())
+}
+
+/// Opens the config file of `scope` in the editor. If the edited file doesn't
+/// parse, the previous content is put back, and the edited one is kept next
+/// to it so the edits aren't lost.
+fn cmd_config_edit(globals: &Globals, scope: Scope) -> Result<()> {
+    let path = config::scope_path(scope)?;
+    if !path.exists() {
+        let config = match scope {
+            Scope::Global => config::default_config(),
+            Scope::Local => Config::default(),
+        };
+        config::write_config_to(&path, &config)?;
+        globals.info(format_args!("Created {}.", path.display()));
+    }
+    let previous = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+    // The configured editor is only known if the config parses.
+    let configured = config::load_config().ok().and_then(|c| c.editor);
+    let command = platform::editor_command(None, configured.as_deref(), platform::process_env);
+    run_editor(&runner::System, &command, &path)?;
+
+    if let Err(e) = config::read_config_file(&path) {
+        let mut rejected = path.clone().into_os_string();
+        rejected.push(".rejected");
+        let rejected = PathBuf::from(rejected);
+        fs::rename(&path, &rejected)
+            .and_then(|_| fs::write(&path, previous))
+            .with_context(|| format!("Failed to restore config file {}", path.display()))?;
+        bail!(
+            "{:#}\nRestored the previous {}, your edits are in {}.",
+            e,
+            path.display(),
+            rejected.display()
+        )
+    }
+    globals.info(format_args!("{} is valid.", path.display()));
+
+    Ok(())
+}
+
+fn cmd_create(globals: &Globals, config: Config, args: &CreateArgs) -> Result<()> {
+    let editor = config.editor.clone();
+    let created = create_rfc(globals, config, args)?;
+    report_created(globals, args, &created);
+    if let Some(file) = created.path.filter(|_| args.edit) {
+        let command = platform::editor_command(None, editor.as_deref(), platform::process_env);
+        run_editor(&runner::System, &command, &file)?;
+    }
+
+    Ok(())
+}
+
+/// What `create_rfc` did, for the caller to report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CreatedRfc {
+    number: usize,
+    branch: String,
+    /// The new RFC's file. With `--reuse`, there may be none, only a switch
+    /// to the existing RFC's branch.
+    path: Option<PathBuf>,
+    /// Whether the file was committed on the branch, rather than only staged
+    /// with `--no-commit`. False when there's no new file.
+    committed: bool,
+}
+
+/// Prints what `create_rfc` did, as `args` ask for.
+fn report_created(globals: &Globals, args: &CreateArgs, created: &CreatedRfc) {
+    let globals = Globals {
+        quiet: globals.quiet || args.porcelain,
+        ..globals.clone()
+    };
+    let Some(file) = &created.path else {
+        globals.info(format_args!(
+            "Reusing the existing RFC branch {}",
+            created.branch
+        ));
+        if args.porcelain {
+            println!("number {:03}", created.number);
+            println!("branch {}", created.branch);
+        }
+        return;
+    };
+    if args.file_only {
+        globals.info(format_args!("Wrote {}", file.display()));
+        if args.porcelain {
+            println!("number {:03}", created.number);
+            println!("path {}", file.display());
+        }
+        return;
+    }
+    match args.no_checkout {
+        true => globals.info(format_args!("Created git branch {}", created.branch)),
+        false => globals.info(format_args!(
+            "Created and checked out git branch {}",
+            created.branch
+        )),
+    }
+    match created.committed {
+        true => globals.info(format_args!("Added {}", file.display())),
+        false => globals.info(format_args!(
+            "Added {}, staged but not committed",
+            file.display()
+        )),
+    }
+    if args.porcelain {
+        println!("number {:03}", created.number);
+        println!("branch {}", created.branch);
+        println!("path {}", file.display());
+    }
+}
+
+/// Creates the RFC `args` describe, without printing what was created; see
+/// `report_created` for that.
+fn create_rfc(globals: &Globals, config: Config, args: &CreateArgs) -> Result<CreatedRfc> {
+    let scan = globals.scan();
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let base = args.base.clone().or_else(|| default_branch.clone());
+    let tag_prefix = config.git.as_ref().and_then(|g| g.tag_prefix.clone());
+    let separator = config.separator()?;
+    let date_format = config.date_format()?;
+    let (rfc_dir, numbered) = numbering_dirs(&config, &args.numbering)?;
+    let per_dir = config.per_dir_numbering();
+    let rfc_dirs = config.rfc_dirs();
+    let slug_case = config.slug_case();
+    let path = ensure_local_repo(globals, &config)?;
+    let transitions = config
+        .transitions
+        .unwrap_or_else(lifecycle::default_transitions);
+    let asks = args.interactive
+        && (args.title.is_none()
+            || args.status.is_none()
+            || args.author.is_none()
+            || args.label.is_empty());
+    if asks && !std::io::stdin().is_terminal() {
+        bail!(
+            "create --interactive asks for what isn't given as arguments, which \
+             needs a terminal. Pass it all instead, like `rfcs create --status \
+             draft --author 'Your Name' --label api 'The title'`."
+        )
+    }
+    let new = new_rfc(
+        args,
+        &transitions,
+        git::author_name(&path),
+        &mut prompt::Prompter::new(std::io::stdin().lock(), std::io::stderr()),
+    )?;
+    // Held until the branch exists, so that a concurrent create sees it when
+    // working out its number. Without a branch, there's nothing to hold it
+    // for.
+    let lock = match args.file_only {
+        true => None,
+        false => Some(lock::Lock::acquire(
+            &git::git_dir(&path)?.join("rfcs-create.lock"),
+            std::time::Duration::from_secs(30),
+        )?),
+    };
+    let path_base = config.paths_relative_to.unwrap_or_default().dir(&path)?;
+    let templates_dir = path_base.join(
+        config
+            .templates_dir
+            .unwrap_or_else(|| PathBuf::from(template::DEFAULT_DIR)),
+    );
+    let template = match &args.from_template {
+        Some(name) => Some(template::find(&templates_dir, name)?),
+        None => template::list(&templates_dir)?
+            .into_iter()
+            .find(|t| t.name == template::DEFAULT_NAME),
+    };
+    let next_rfc = match args.number {
+        Some(number) => {
+            let users = rfc_number_users(
+                &path,
+                &numbered,
+                per_dir,
+                tag_prefix.as_deref(),
+                scan,
+                number,
+            )?;
+            if !users.is_empty() {
+                bail!(
+                    "RFC number {:03} is already in use by {}.",
+                    number,
+                    users.join(", ")
+                )
+            }
+            number
+        }
+        None if args.file_only => {
+            let files = rfc_files(&path, &numbered, scan)?;
+            next_rfc_number(&[], &files, scan)
+        }
+        None => compute_next_rfc_number(
+            &path,
+            &numbered,
+            per_dir,
+            tag_prefix.as_deref(),
+            default_branch.as_deref(),
+            scan,
+            &args.numbering,
+        )?,
+    };
+
+    // The RFCs referred to have to exist, and the one to mark superseded has
+    // to be on the base branch, before anything is created.
+    let files = rfc_files(&path, &rfc_dirs, scan)?;
+    for number in [args.supersedes, args.amends].into_iter().flatten() {
+        Target::Number(number).resolve(&path, &files, scan)?;
+    }
+    let copied = match args.copy_from {
+        Some(number) => {
+            let file = Target::Number(number).resolve(&path, &files, scan)?;
+            let content = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let extension = file.extension().and_then(|e| e.to_str()).unwrap_or("md");
+            Some((extension.to_string(), content))
+        }
+        None => None,
+    };
+    let superseded = match args.supersedes {
+        Some(number) if args.mark_superseded => {
+            let file = Target::Number(number).resolve(&path, &files, scan)?;
+            let relative = git::relative_to_repo(&path, &file)?;
+            let (base_name, base_commit) = git::default_branch_tip(&path, base.as_deref())?;
+            let Some(content) = git::file_at(&path, &base_commit.to_string(), &relative)? else {
+                bail!(
+                    "RFC {:03} isn't committed on {}, so it can't be marked superseded.",
+                    number,
+                    base_name
+                )
+            };
+            Some((
+                number,
+                relative,
+                String::from_utf8_lossy(&content).to_string(),
+            ))
+        }
+        _ => None,
+    };
+
+    let slug = slug(
+        &new.title,
+        config
+            .slug_max_length
+            .unwrap_or(config::DEFAULT_SLUG_MAX_LENGTH),
+        slug_case,
+    );
+    let globals = Globals {
+        quiet: globals.quiet || args.porcelain,
+        ..globals.clone()
+    };
+    if !args.force_new && !args.file_only {
+        if let Some(existing) = git::branches_matching(&path, |b| slug_of(b) == Some(&slug))?
+            .into_iter()
+            .next()
+        {
+            if !args.reuse {
+                bail!(
+                    "There already is an RFC branch {} named like this. Switch to it \
+                     with `git switch {}`, or pass --reuse to have create do that, or \
+                     --force-new to create another RFC anyway.",
+                    existing,
+                    existing
+                )
+            }
+            drop(lock);
+            if !args.no_checkout {
+                git::switch_to_branch(&path, &existing)?;
+            }
+            return Ok(CreatedRfc {
+                number: scan
+                    .rfc_number(&existing)
+                    .expect("RFC branches start with their number."),
+                branch: existing,
+                path: None,
+                committed: false,
+            });
+        }
+    }
+    let slug = unique_slug(&globals, &path, &rfc_dirs, slug)?;
+    let branch_name = format!("{:03}{}{}", next_rfc, separator, slug);
+    let (extension, content) = match (&copied, &template) {
+        (Some((extension, source)), _) => (extension.as_str(), copied_rfc(source, &new.title)),
+        (None, Some(template)) => (
+            template.extension(),
+            template.render(
+                next_rfc,
+                &new.title,
+                &Utc::now().date_naive().format(&date_format).to_string(),
+            )?,
+        ),
+        (None, None) => ("md", rfc_stub(&new.title)),
+    };
+    let mut content = new.front_matter(content);
+    for (field, number) in [
+        ("supersedes", args.supersedes),
+        ("amends", args.amends),
+        ("derived_from", args.copy_from),
+    ] {
+        if let Some(number) = number {
+            content = metadata::set_field(&content, field, &format!("{:03}", number));
+        }
+    }
+    let dir = match &args.subdir {
+        Some(subdir) => rfc_dir.join(subdir),
+        None => rfc_dir,
+    };
+    let file_name = dir.join(format!("{}.{}", branch_name, extension));
+    let file = path.join(&file_name);
+    if args.file_only {
+        if file.exists() {
+            bail!("Can't create {}, it already exists.", file_name.display())
+        }
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(&file, &content)
+            .with_context(|| format!("Failed to write {}", file.display()))?;
+        return Ok(CreatedRfc {
+            number: next_rfc,
+            branch: branch_name,
+            path: Some(file),
+            committed: false,
+        });
+    }
+    globals.info(format_args!("Branch will be named {}", branch_name));
+
+    if let Some(head) = git::detached_head(&path)? {
+        let (base_name, base_commit) = git::default_branch_tip(&path, base.as_deref())?;
+        globals.warn(format_args!(
+            "HEAD is detached at {}. The RFC is based on {} at {}, not on the \
+             detached commit.",
+            short_id(head),
+            base_name,
+            short_id(base_commit)
+        ))?;
+    }
+    git::create_branch(&path, &branch_name, base.as_deref())?;
+    let superseded = superseded.map(|(number, relative, content)| {
+        let marked = metadata::set_field(&content, "superseded_by", &format!("{:03}", next_rfc));
+        (number, relative, marked)
+    });
+    if args.no_commit {
+        git::switch_to_branch(&path, &branch_name)?;
+        let mut staged = vec![(file_name.as_path(), content.as_str())];
+        if let Some((_, relative, marked)) = &superseded {
+            staged.push((relative.as_path(), marked.as_str()));
+        }
+        for (relative, content) in &staged {
+            let file = path.join(relative);
+            if let Some(dir) = file.parent() {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+            }
+            fs::write(&file, content)
+                .with_context(|| format!("Failed to write {}", file.display()))?;
+        }
+        let files: Vec<&Path> = staged.iter().map(|(relative, _)| *relative).collect();
+        git::stage_files(&path, &files)?;
+    } else {
+        git::add_file_to_branch(
+            &path,
+            &branch_name,
+            &file_name,
+            content.as_bytes(),
+            &format!("Add RFC {:03}: {}", next_rfc, new.title),
+        )?;
+        if let Some((number, relative, marked)) = &superseded {
+            git::add_file_to_branch(
+                &path,
+                &branch_name,
+                relative,
+                marked.as_bytes(),
+                &format!(
+                    "Mark RFC {:03} as superseded by RFC {:03}",
+                    number, next_rfc
+                ),
+            )?;
+        }
+    }
+    drop(lock);
+    if !args.no_checkout && !args.no_commit {
+        git::switch_to_branch(&path, &branch_name)?;
+    }
+
+    if let Some(command) = config.hooks.and_then(|h| h.post_create) {
+        let result = hook::run(
+            "post_create",
+            &command,
+            &[
+                ("number", format!("{:03}", next_rfc)),
+                ("branch", branch_name.clone()),
+                ("path", file.display().to_string()),
+            ],
+            &path,
+            &path_base,
+            args.capture_hook_output || args.porcelain,
+        );
+        if let Err(e) = result {
+            globals.warn(e)?;
+        }
+    }
+
+    Ok(CreatedRfc {
+        number: next_rfc,
+        branch: branch_name,
+        path: Some(file),
+        committed: !args.no_commit,
+    })
+}
+
+/// The arguments of `rfcs create <title>`, with every option at its default.
+fn create_args_for(title: &str) -> CreateArgs {
+    use clap::{Args as _, FromArgMatches as _};
+
+    let matches = CreateArgs::augment_args(clap::Command::new("create"))
+        .get_matches_from(["create", "--", title]);
+    CreateArgs::from_arg_matches(&matches).expect("create takes a title")
+}
+
+/// Moves the document at `source` into the repository as the next RFC,
+/// named after its title, and commits it unless `no_commit`.
+fn cmd_import(
+    globals: &Globals,
+    config: Config,
+    source: &Path,
+    no_commit: bool,
+    numbering: &NumberingArgs,
+) -> Result<()> {
+    let tag_prefix = config.git.as_ref().and_then(|g| g.tag_prefix.clone());
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let separator = config.separator()?;
+    let (rfc_dir, numbered) = numbering_dirs(&config, numbering)?;
+    let per_dir = config.per_dir_numbering();
+    let rfc_dirs = config.rfc_dirs();
+    let max_len = config
+        .slug_max_length
+        .unwrap_or(config::DEFAULT_SLUG_MAX_LENGTH);
+    let slug_case = config.slug_case();
+    let path = ensure_local_repo(globals, &config)?;
+
+    if !file_is_text_document(source) {
+        bail!(
+            "{} isn't a text document. Only .md, .markdown, .txt, .rst, .adoc and \
+             .org files can be imported.",
+            source.display()
+        )
+    }
+    let bytes = fs::read(source).with_context(|| format!("Failed to read {}", source.display()))?;
+    let content = match String::from_utf8(bytes) {
+        Ok(content) if !content.contains('\0') => content,
+        _ => bail!("{} isn't a text document.", source.display()),
+    };
+    // Moving a document that's already in the repository commits its removal
+    // as well.
+    let old = git::relative_to_repo(&path, source).ok();
+
+    let number = compute_next_rfc_number(
+        &path,
+        &numbered,
+        per_dir,
+        tag_prefix.as_deref(),
+        default_branch.as_deref(),
+        globals.scan(),
+        numbering,
+    )?;
+    let front_matter = metadata::parse(&content);
+    let title = rfc_title(source, &front_matter, &content);
+    let slug = unique_slug(globals, &path, &rfc_dirs, slug(&title, max_len, slug_case))?;
+    let extension = source.extension().unwrap_or_default().to_string_lossy();
+    let file_name = rfc_dir.join(format!("{:03}{}{}.{}", number, separator, slug, extension));
+    let file = path.join(&file_name);
+    if file.exists() {
+        bail!(
+            "Can't import {}, {} already exists.",
+            source.display(),
+            file_name.display()
+        )
+    }
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(&file, &content).with_context(|| format!("Failed to write {}", file.display()))?;
+    fs::remove_file(source).with_context(|| format!("Failed to remove {}", source.display()))?;
+
+    if !no_commit {
+        let mut files = vec![file_name.as_path()];
+        files.extend(old.as_deref());
+        git::commit_files(
+            &path,
+            &files,
+            &format!("Import RFC {:03}: {}", number, title),
+        )?;
+    }
+    globals.info(format_args!(
+        "Imported {} as {}",
+        source.display(),
+        file_name.display()
+    ));
+
+    Ok(())
+}
+
+fn cmd_export(globals: &Globals, config: Config, args: &ExportArgs) -> Result<()> {
+    let scan = globals.scan();
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, scan)?;
+
+    let files = match args.targets.as_slice() {
+        [] => files,
+        targets => TargetSet::expand(targets, &path, &files, scan, false)?,
+    };
+
+    let mut entries = vec![];
+    for file in files
+        .iter()
+        .filter(|f| number_in_range(f, scan, args.from, args.to))
+    {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let front_matter = metadata::parse(&content);
+        let status = front_matter
+            .get("status")
+            .unwrap_or(lifecycle::INITIAL_STATUS);
+        if !args.status.is_empty() && !args.status.iter().any(|s| s.eq_ignore_ascii_case(status)) {
+            continue;
+        }
+        entries.push(export::Entry {
+            number: scan.file_number(file).unwrap_or_default(),
+            title: rfc_title(file, &front_matter, &content),
+            body: metadata::body(&content).to_string(),
+        });
+    }
+    entries.sort_by_key(|e| e.number);
+
+    let document = export::document(&entries);
+    match &args.output {
+        Some(output) => {
+            fs::write(output, document)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            globals.info(format_args!(
+                "Exported {} RFCs to {}",
+                entries.len(),
+                output.display()
+            ));
+        }
+        None => print!("{}", document),
+    }
+
+    Ok(())
+}
+
+/// The title of the RFC `file`: the `title` of its front matter, or else its
+/// first heading, or else the file name.
+fn rfc_title(file: &Path, front_matter: &metadata::FrontMatter, content: &str) -> String {
+    front_matter
+        .get("title")
+        .or_else(|| export::heading(metadata::body(content)))
+        .map(String::from)
+        .unwrap_or_else(|| {
+            file.file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        })
+}
+
+fn cmd_graph(globals: &Globals, config: Config, format: graph::Format) -> Result<()> {
+    let scan = globals.scan();
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let mut files = rfc_files(&path, &rfc_dirs, scan)?;
+    files.sort_by_cached_key(|f| (scan.file_number(f), f.clone()));
+
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    for file in &files {
+        let Some(number) = scan.file_number(file) else {
+            continue;
+        };
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let front_matter = metadata::parse(&content);
+        edges.extend(graph::edges(number, &front_matter));
+        nodes.push(graph::Node {
+            number,
+            title: rfc_title(file, &front_matter, &content),
+        });
+    }
+    print!("{}", graph::render(&nodes, &edges, format));
+
+    Ok(())
+}
+
+fn cmd_templates(globals: &Globals, config: Config, validate: bool) -> Result<()> {
+    let path = ensure_local_repo(globals, &config)?;
+    let dir = config
+        .paths_relative_to
+        .unwrap_or_default()
+        .dir(&path)?
+        .join(
+            config
+                .templates_dir
+                .unwrap_or_else(|| PathBuf::from(template::DEFAULT_DIR)),
+        );
+    let mut broken = 0;
+    for template in template::list(&dir)? {
+        if !validate {
+            println!("{}", template.name);
+            continue;
+        }
+        let problems = template.problems()?;
+        if problems.is_empty() {
+            println!("{}: OK", template.name);
+        } else {
+            broken += 1;
+        }
+        for problem in problems {
+            println!("{}: {}", template.name, problem);
+        }
+    }
+    if broken > 0 {
+        bail!("{} template(s) in {} need fixing.", broken, dir.display())
+    }
+
+    Ok(())
+}
+
+/// The part of a new RFC's branch and file name after its number: `title`
+/// with dashes for spaces and without some punctuation, cased as `case`
+/// says, cut back to the last whole word that fits into `max_len`
+/// characters.
+fn slug(title: &str, max_len: usize, case: SlugCase) -> String {
+    let full = match case {
+        SlugCase::Keep => title.replace(' ', "-").replace([',', '.', '?', '!'], ""),
+        SlugCase::Lower => title
+            .to_lowercase()
+            .replace(' ', "-")
+            .replace([',', '.', '?', '!'], ""),
+        SlugCase::Kebab => title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-"),
+    };
+    if full.chars().count() <= max_len {
+        return full;
+    }
+    let cut: String = full.chars().take(max_len).collect();
+    let word_ends = full.chars().nth(max_len) == Some('-');
+    let kept = match cut.rfind('-') {
+        Some(i) if !word_ends && i > 0 => &cut[..i],
+        // A single word longer than the limit is cut where it has to be.
+        _ => &cut,
+    };
+    kept.trim_end_matches('-').to_string()
+}
+
+/// `slug`, or if another RFC already has it, `slug` with the first free
+/// suffix like `-2`.
+fn unique_slug(globals: &Globals, path: &Path, dirs: &[PathBuf], slug: String) -> Result<String> {
+    let taken = existing_slugs(path, dirs, globals.scan())?;
+    if !taken.contains(&slug) {
+        return Ok(slug);
+    }
+    let unique = (2..)
+        .map(|n| format!("{}-{}", slug, n))
+        .find(|s| !taken.contains(s))
+        .expect("There are only so many RFCs.");
+    globals.info(format_args!(
+        "Another RFC is already named {}, naming this one {}",
+        slug, unique
+    ));
+    Ok(unique)
+}
+
+/// Prints the branch name `create` would give a new RFC titled `title`,
+/// without creating anything.
+fn cmd_branch_name(
+    globals: &Globals,
+    config: Config,
+    title: &str,
+    numbering: &NumberingArgs,
+) -> Result<()> {
+    let tag_prefix = config.git.as_ref().and_then(|g| g.tag_prefix.clone());
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let separator = config.separator()?;
+    let (_, numbered) = numbering_dirs(&config, numbering)?;
+    let per_dir = config.per_dir_numbering();
+    let rfc_dirs = config.rfc_dirs();
+    let slug_case = config.slug_case();
+    let path = ensure_local_repo(globals, &config)?;
+    let number = compute_next_rfc_number(
+        &path,
+        &numbered,
+        per_dir,
+        tag_prefix.as_deref(),
+        default_branch.as_deref(),
+        globals.scan(),
+        numbering,
+    )?;
+    let slug = slug(
+        title,
+        config
+            .slug_max_length
+            .unwrap_or(config::DEFAULT_SLUG_MAX_LENGTH),
+        slug_case,
+    );
+    // Only the name goes to stdout, for scripts.
+    let quiet = Globals {
+        quiet: true,
+        ..globals.clone()
+    };
+    let slug = unique_slug(&quiet, &path, &rfc_dirs, slug)?;
+    println!("{:03}{}{}", number, separator, slug);
+    Ok(())
+}
+
+/// The slugs (see `slug`) of the RFC files and branches of the repository at
+/// `path`.
+fn existing_slugs(path: &Path, dirs: &[PathBuf], scan: &Scan) -> Result<HashSet<String>> {
+    let files = rfc_files(path, dirs, scan)?;
+    let names: Vec<String> = files
+        .iter()
+        .filter_map(|f| Some(f.file_stem()?.to_string_lossy().to_string()))
+        .chain(git::branches_matching(path, |b| slug_of(b).is_some())?)
+        .collect();
+    Ok(names
+        .iter()
+        .filter_map(|name| slug_of(name).map(String::from))
+        .collect())
+}
+
+/// The slug of an RFC branch or file stem, the part after the number and any
+/// of the `config::SEPARATORS`.
+fn slug_of(name: &str) -> Option<&str> {
+    static SLUG: OnceLock<Regex> = OnceLock::new();
+    let re = SLUG
+        .get_or_init(|| Regex::new(r"^\d{3,}[-_+](?<slug>.+)$").expect("Can't compile slug regex"));
+    Some(re.captures(name)?.name("slug")?.as_str())
+}
+
+/// The UTC day of a commit made `time` seconds after the epoch.
+fn commit_date(time: i64) -> NaiveDate {
+    chrono::DateTime::from_timestamp(time, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/// A commit id abbreviated the way git usually shows it.
+fn short_id(id: git2::Oid) -> String {
+    id.to_string()[..7].to_string()
+}
+
+/// What `create` puts into a new RFC besides its number: from the arguments,
+/// or with `--interactive`, asked for where they don't say.
+#[derive(Debug, PartialEq, Eq)]
+struct NewRfc {
+    title: String,
+    /// `None` keeps the status of the template, `draft` unless it says.
+    status: Option<String>,
+    author: Option<String>,
+    labels: Vec<String>,
+}
+
+impl NewRfc {
+    /// `content` with the status, author and labels in its front matter.
+    fn front_matter(&self, mut content: String) -> String {
+        if let Some(status) = &self.status {
+            content = metadata::set_field(&content, "status", status);
+        }
+        if let Some(author) = &self.author {
+            content = metadata::set_field(&content, "author", author);
+        }
+        if !self.labels.is_empty() {
+            content =
+                metadata::set_field(&content, "labels", &format!("[{}]", self.labels.join(", ")));
+        }
+        content
+    }
+}
+
+/// The `NewRfc` that `args` describe. With `--interactive`, `prompter` asks
+/// for the rest, suggesting `draft` as the status and `default_author` as the
+/// author. Statuses have to be known to `transitions`.
+fn new_rfc(
+    args: &CreateArgs,
+    transitions: &lifecycle::Transitions,
+    default_author: Option<String>,
+    prompter: &mut prompt::Prompter<impl std::io::BufRead, impl Write>,
+) -> Result<NewRfc> {
+    let check_status = |status: &str| {
+        if !transitions.contains_key(status) {
+            bail!(
+                "'{}' is not a known status, known statuses: {}",
+                status,
+                transitions.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        }
+        Ok(status.to_string())
+    };
+    if let Some(status) = &args.status {
+        check_status(status.as_str())?;
+    }
+    if !args.interactive {
+        return Ok(NewRfc {
+            title: args.title.clone().expect("clap requires a title"),
+            status: args.status.clone(),
+            author: args.author.clone(),
+            labels: args.label.clone(),
+        });
+    }
+
+    let title = match &args.title {
+        Some(title) => title.clone(),
+        None => prompter.ask("Title", None, |title| match title {
+            "" => bail!("The title can't be empty."),
+            title => Ok(title.to_string()),
+        })?,
+    };
+    let status = match &args.status {
+        Some(status) => status.clone(),
+        None => prompter.ask("Status", Some(lifecycle::INITIAL_STATUS), check_status)?,
+    };
+    let author = match &args.author {
+        Some(author) => Some(author.clone()),
+        None => prompter.ask("Author", default_author.as_deref(), |author| {
+            Ok(Some(author.to_string()).filter(|a| !a.is_empty()))
+        })?,
+    };
+    let labels = match args.label.as_slice() {
+        [] => prompter.ask("Labels, separated by commas", None, |labels| {
+            labels
+                .split(',')
+                .filter(|label| !label.trim().is_empty())
+                .map(parse_label)
+                .collect()
+        })?,
+        labels => labels.to_vec(),
+    };
+    Ok(NewRfc {
+        title,
+        status: Some(status),
+        author,
+        labels,
+    })
+}
+
+/// The content of a freshly created RFC.
+fn rfc_stub(title: &str) -> String {
+    format!("---\ntitle: {}\nstatus: draft\n---\n# {}\n", title, title)
+}
+
+/// A new RFC titled `title` copied from the RFC with `source`: the body of
+/// that, with its first heading retitled, under the front matter of
+/// `rfc_stub`. Nothing of the old front matter carries over.
+fn copied_rfc(source: &str, title: &str) -> String {
+    let mut retitled = false;
+    let body: String = metadata::body(source)
+        .split_inclusive('\n')
+        .map(|line| match line.starts_with("# ") && !retitled {
+            true => {
+                retitled = true;
+                format!("# {}\n", title)
+            }
+            false => line.to_string(),
+        })
+        .collect();
+    format!("---\ntitle: {}\nstatus: draft\n---\n{}", title, body)
+}
+
+/// What `rfcs version` prints: the version of `rfcs`, and with `verbose`,
+/// those of git2 and libgit2 and what libgit2 was built to support.
+fn version_info(verbose: bool) -> String {
+    let mut out = format!("rfcs {}\n", env!("CARGO_PKG_VERSION"));
+    if !verbose {
+        return out;
+    }
+    let git2 = git2::Version::get();
+    let (major, minor, patch) = git2.libgit2_version();
+    let yes_no = |supported: bool| if supported { "yes" } else { "no" };
+    out.push_str(&format!("git2 {}\n", git2.crate_version()));
+    out.push_str(&format!(
+        "libgit2 {}.{}.{}{}\n",
+        major,
+        minor,
+        patch,
+        if git2.vendored() { " (vendored)" } else { "" }
+    ));
+    out.push_str(&format!("HTTPS: {}\n", yes_no(git2.https())));
+    out.push_str(&format!("SSH: {}\n", yes_no(git2.ssh())));
+    out.push_str(&format!("Threads: {}\n", yes_no(git2.threads())));
+    out
+}
+
+fn cmd_doctor(globals: &Globals, ping: bool, fix: bool, commit: bool) -> Result<()> {
+    let checks = doctor::run(ping, globals.follow_symlinks, globals.deadline);
+    let color = globals.color();
+    for check in &checks {
+        println!(
+            "{} {}",
+            color::paint(color, check.status.color(), &check.status.to_string()),
+            check.message
+        );
+    }
+
+    let mut fixed = 0;
+    if fix {
+        for change in checks.iter().filter_map(|check| check.fix.as_ref()) {
+            println!("Fixed: {}", change.apply(commit)?);
+            fixed += 1;
+        }
+    }
+    let failures = checks
+        .iter()
+        .filter(|check| !(fix && check.fix.is_some()))
+        .filter(|check| {
+            check.status == doctor::Status::Fail
+                || (globals.strict && check.status == doctor::Status::Warn)
+        })
+        .count();
+    if fix && fixed == 0 {
+        println!("Nothing to fix.");
+    }
+    if failures > 0 {
+        bail!("{} of {} checks failed", failures, checks.len())
+    }
+    Ok(())
+}
+
+fn cmd_next(globals: &Globals, config: Config, numbering: &NumberingArgs) -> Result<()> {
+    let tag_prefix = config.git.as_ref().and_then(|g| g.tag_prefix.clone());
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let (_, numbered) = numbering_dirs(&config, numbering)?;
+    let per_dir = config.per_dir_numbering();
+    let path = ensure_local_repo(globals, &config)?;
+    println!(
+        "{:03}",
+        compute_next_rfc_number(
+            &path,
+            &numbered,
+            per_dir,
+            tag_prefix.as_deref(),
+            default_branch.as_deref(),
+            globals.scan(),
+            numbering
+        )?
+    );
+
+    Ok(())
+}
+
+fn cmd_probe(globals: &Globals, config: Config, url: &str) -> Result<()> {
+    let token = config.git.as_ref().and_then(|g| g.effective_token());
+    let url = git::clone_source(url, &std::env::current_dir()?);
+    let probed = git::probe(&url, token, globals.deadline)?;
+    match &probed.default_branch {
+        Some(branch) => globals.info(format_args!(
+            "Reached {}, its default branch is {}",
+            git::redact_url(&url),
+            branch
+        )),
+        None => globals.info(format_args!("Reached {}", git::redact_url(&url))),
+    }
+    for (name, id) in &probed.refs {
+        println!("{}\t{}", id, name);
+    }
+
+    Ok(())
+}
+
+fn cmd_reserve(
+    globals: &Globals,
+    config: Config,
+    number: Option<usize>,
+    release: bool,
+    numbering: &NumberingArgs,
+) -> Result<()> {
+    let tag_prefix = config.git.as_ref().and_then(|g| g.tag_prefix.clone());
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let (_, numbered) = numbering_dirs(&config, numbering)?;
+    let per_dir = config.per_dir_numbering();
+    let path = ensure_local_repo(globals, &config)?;
+    let git_dir = git::git_dir(&path)?;
+    // Like create, so that the two don't hand out the same number.
+    let _lock = lock::Lock::acquire(
+        &git_dir.join("rfcs-create.lock"),
+        std::time::Duration::from_secs(30),
+    )?;
+    let ledger = reserve::ledger_path(&git_dir);
+    let mut reservations = reserve::Reservations::read(&ledger)?;
+    let users = |number| {
+        rfc_number_users(
+            &path,
+            &numbered,
+            per_dir,
+            tag_prefix.as_deref(),
+            globals.scan(),
+            number,
+        )
+    };
+
+    if release {
+        let number = number.expect("--release requires a number");
+        if !reservations.numbers.contains(&number) {
+            bail!("RFC number {:03} isn't reserved.", number)
+        }
+        let users = users(number)?;
+        if !users.is_empty() {
+            bail!(
+                "RFC number {:03} is used by {} by now, so it can't be released.",
+                number,
+                users.join(", ")
+            )
+        }
+        reservations.numbers.remove(&number);
+        reservations.write(&ledger)?;
+        globals.info(format_args!("Released RFC number {:03}", number));
+        return Ok(());
+    }
+
+    let number = match number {
+        Some(number) => {
+            if reservations.numbers.contains(&number) {
+                bail!("RFC number {:03} is already reserved.", number)
+            }
+            let users = users(number)?;
+            if !users.is_empty() {
+                bail!(
+                    "RFC number {:03} is already in use by {}.",
+                    number,
+                    users.join(", ")
+                )
+            }
+            number
+        }
+        None => compute_next_rfc_number(
+            &path,
+            &numbered,
+            per_dir,
+            tag_prefix.as_deref(),
+            default_branch.as_deref(),
+            globals.scan(),
+            numbering,
+        )?,
+    };
+    reservations.numbers.insert(number);
+    reservations.write(&ledger)?;
+    println!("{:03}", number);
+
+    Ok(())
+}
+
+/// Copies `reader` to `writer` as text, replacing bytes that aren't valid
+/// UTF-8 with U+FFFD as `String::from_utf8_lossy` does, a buffer at a time.
+/// Returns whether anything was replaced.
+fn copy_lossy(mut reader: impl std::io::Read, mut writer: impl Write) -> std::io::Result<bool> {
+    const REPLACEMENT: &[u8] = "\u{FFFD}".as_bytes();
+    let mut buf = vec![0; 8192];
+    // How many bytes at the start of `buf` are left over from the last read,
+    // the start of a character cut off by the end of the buffer.
+    let mut pending = 0;
+    let mut replaced = false;
+    loop {
+        let read = match reader.read(&mut buf[pending..]) {
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let end = pending + read;
+        let mut rest = &buf[..end];
+        loop {
+            let e = match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    writer.write_all(text.as_bytes())?;
+                    rest = &[];
+                    break;
+                }
+                Err(e) => e,
+            };
+            writer.write_all(&rest[..e.valid_up_to()])?;
+            match e.error_len() {
+                Some(len) => rest = &rest[e.valid_up_to() + len..],
+                // Cut off, and there's no more to come.
+                None if read == 0 => rest = &[],
+                None => {
+                    rest = &rest[e.valid_up_to()..];
+                    break;
+                }
+            }
+            writer.write_all(REPLACEMENT)?;
+            replaced = true;
+        }
+        if read == 0 {
+            writer.flush()?;
+            return Ok(replaced);
+        }
+        pending = rest.len();
+        buf.copy_within(end - pending..end, 0);
+    }
+}
+
+fn cmd_show(globals: &Globals, config: Config, args: &ShowArgs) -> Result<()> {
+    let scan = globals.scan();
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    if let Some((from, to)) = args.range {
+        let files = rfc_files(&path, &rfc_dirs, scan)?;
+        print!("{}", range_text(&files, scan, from, to)?);
+        return Ok(());
+    }
+    // Each file, and its content at `--at`.
+    let rfcs: Vec<(PathBuf, Option<Vec<u8>>)> = match (&args.at, args.targets.as_slice()) {
+        (Some(rev), [TargetSet::One(target)]) => {
+            let (file, content) = rfc_at(&path, &rfc_dirs, scan, rev, target)?;
+            vec![(file, Some(content))]
+        }
+        (Some(_), _) => bail!("--at only takes a single RFC."),
+        (None, targets) => {
+            let files = rfc_files(&path, &rfc_dirs, scan)?;
+            TargetSet::expand(targets, &path, &files, scan, true)?
+                .into_iter()
+                .map(|file| (file, None))
+                .collect()
+        }
+    };
+    if !args.metadata {
+        for (file, content) in &rfcs {
+            // Copied over as it's read, however large the RFC is.
+            let mut reader: Box<dyn std::io::Read + '_> = match content {
+                Some(content) => Box::new(content.as_slice()),
+                None => Box::new(
+                    fs::File::open(file)
+                        .with_context(|| format!("Failed to read {}", file.display()))?,
+                ),
+            };
+            let copied = match args.raw_bytes {
+                true => std::io::copy(&mut reader, &mut std::io::stdout().lock()).map(|_| false),
+                false => copy_lossy(reader, std::io::stdout().lock()),
+            };
+            match copied {
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to print {}", file.display()))
+                }
+                Ok(true) => globals.warn(format_args!(
+                    "{} isn't valid UTF-8, the invalid bytes were replaced. \
+                     Pass --raw-bytes to print it as it is.",
+                    file.display()
+                ))?,
+                Ok(false) => {}
+            }
+        }
+        return Ok(());
+    }
+
+    let mut tables = vec![];
+    let mut blocks = vec![];
+    for (file, content) in &rfcs {
+        let number = scan.file_number(file);
+        let front_matter = match content {
+            Some(content) => metadata::parse(&String::from_utf8_lossy(content)),
+            None => file_front_matter(file)?,
+        };
+        let mut table = serde_json::Map::new();
+        let mut block = String::new();
+        if let Some(number) = number {
+            table.insert(String::from("number"), number.into());
+            block.push_str(&format!("number: {:03}\n", number));
+        }
+        for (key, value) in &front_matter.fields {
+            table.insert(key.clone(), value.clone().into());
+            block.push_str(&format!("{}: {}\n", key, value));
+        }
+        tables.push(serde_json::Value::Object(table));
+        blocks.push(block);
+    }
+    if args.json {
+        // A single RFC is an object of its own, several are an array.
+        let value = match tables.len() {
+            1 => tables.remove(0),
+            _ => serde_json::Value::Array(tables),
+        };
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+    print!("{}", blocks.join("\n"));
+
+    Ok(())
+}
+
+/// What `show --range` prints for the RFCs among `files` numbered `from` to
+/// `to`: each under a line with its number and title, with rules between
+/// them, and a note where numbers have no RFC.
+fn range_text(files: &[PathBuf], scan: &Scan, from: usize, to: usize) -> Result<String> {
+    let note = |first: usize, last: usize| match first == last {
+        true => format!("(There is no RFC {:03}.)\n", first),
+        false => format!("(There are no RFCs {:03} to {:03}.)\n", first, last),
+    };
+    let mut by_number: Vec<(usize, &PathBuf)> = files
+        .iter()
+        .filter_map(|f| Some((scan.file_number(f)?, f)))
+        .filter(|(number, _)| (from..=to).contains(number))
+        .collect();
+    by_number.sort();
+    if by_number.is_empty() {
+        bail!("There is no RFC numbered {:03} to {:03}.", from, to)
+    }
+    let mut sections = vec![];
+    // The next number that should have an RFC, if there's no gap.
+    let mut expected = from;
+    for (number, file) in by_number {
+        if number > expected {
+            sections.push(note(expected, number - 1));
+        }
+        expected = number + 1;
+        let bytes = fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let content = String::from_utf8_lossy(&bytes);
+        let title = rfc_title(file, &metadata::parse(&content), &content);
+        let mut section = format!("RFC {:03}: {}\n\n{}", number, title, content);
+        if !section.ends_with('\n') {
+            section.push('\n');
+        }
+        sections.push(section);
+    }
+    if expected <= to {
+        sections.push(note(expected, to));
+    }
+    Ok(sections.join(&format!("\n{}\n\n", "-".repeat(72))))
+}
+
+/// The RFC `target` refers to as it was at revision `rev` of the repository
+/// at `repo`: its path, and its content then. Numbers are looked up among
+/// the RFCs under `rfc_dirs` at that revision, so an RFC that was renamed
+/// since is found under its old name.
+fn rfc_at(
+    repo: &Path,
+    rfc_dirs: &[PathBuf],
+    scan: &Scan,
+    rev: &str,
+    target: &Target,
+) -> Result<(PathBuf, Vec<u8>)> {
+    match target {
+        Target::Number(number) => {
+            let is_rfc = |f: &Path| file_is_text_document(f) && scan.has_rfc_id(f);
+            let mut matches: Vec<(PathBuf, Vec<u8>)> = git::files_at(repo, rev, rfc_dirs, is_rfc)?
+                .into_iter()
+                .filter(|(f, _)| scan.file_number(f) == Some(*number))
+                .collect();
+            match matches.len() {
+                1 => {
+                    let (relative, content) = matches.remove(0);
+                    Ok((repo.join(relative), content))
+                }
+                0 => bail!("There was no RFC numbered {:03} at {}.", number, rev),
+                _ => bail!(
+                    "More than one file was numbered {:03} at {}: {}",
+                    number,
+                    rev,
+                    matches
+                        .iter()
+                        .map(|(f, _)| f.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }
+        }
+        Target::Path(path) => {
+            let relative = git::relative_to_repo(repo, path).unwrap_or_else(|_| path.clone());
+            match git::file_at(repo, rev, &relative)? {
+                Some(content) => Ok((repo.join(relative), content)),
+                None => bail!("There was no file at {} at {}.", relative.display(), rev),
+            }
+        }
+    }
+}
+
+fn cmd_edit(
+    globals: &Globals,
+    config: Config,
+    target: &Target,
+    editor: Option<String>,
+    create_if_missing: bool,
+) -> Result<()> {
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, globals.scan())?;
+    let file = match target {
+        Target::Path(title)
+            if create_if_missing
+                && target.resolve(&path, &files, globals.scan()).is_err()
+                && title_matches(&title.to_string_lossy(), &files).is_empty() =>
+        {
+            let args = create_args_for(&title.to_string_lossy());
+            let created = create_rfc(globals, config.clone(), &args)?;
+            report_created(globals, &args, &created);
+            match created.path {
+                Some(file) => file,
+                None => return Ok(()),
+            }
+        }
+        _ => target.resolve_or_search(&path, &files, globals.scan())?,
+    };
+
+    let command = platform::editor_command(
+        editor.as_deref(),
+        config.editor.as_deref(),
+        platform::process_env,
+    );
+    run_editor(&runner::System, &command, &file)
+}
+
+/// Runs the editor `command` on `file` with `runner`, and waits for it to
+/// exit.
+fn run_editor(runner: &dyn runner::Runner, command: &[String], file: &Path) -> Result<()> {
+    let mut editor = platform::launcher(command);
+    editor.arg(file);
+    let status = run_program(
+        runner,
+        &mut editor,
+        "Editor",
+        "Set $VISUAL or $EDITOR, or run `rfcs configure editor <command>`.",
+    )
+    .with_context(|| format!("Failed to start editor '{}'", command.join(" ")))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", command.join(" "), status)
+    }
+
+    Ok(())
+}
+
+/// Runs `command` with `runner` and waits for it to exit. If its program
+/// doesn't exist, that's said in so many words, naming it as `what` and
+/// adding `hint` on how to use another one, rather than with the bare OS
+/// error.
+fn run_program(
+    runner: &dyn runner::Runner,
+    command: &mut Cmd,
+    what: &str,
+    hint: &str,
+) -> Result<std::process::ExitStatus> {
+    match runner.status(command) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+            "{} '{}' not found. {}",
+            what,
+            command.get_program().to_string_lossy(),
+            hint
+        ),
+        result => Ok(result?),
+    }
+}
+
+fn cmd_open(globals: &Globals, config: Config, args: &OpenArgs) -> Result<()> {
+    let scan = globals.scan();
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let configured_url = config.git.as_ref().and_then(|g| g.url.clone());
+    let rfc_dirs = config.rfc_dirs();
+    let path = local_repo(globals, &config)?;
+    let Some(remote) = git::remote_url(&path, "origin").or(configured_url) else {
+        bail!(
+            "{} has no origin remote, and git.url isn't set, so there's no \
+             git host to open the RFC on.",
+            path.display()
+        )
+    };
+    let Some(web) = git::web_url(&remote) else {
+        bail!(
+            "{} isn't on a git host with web pages to open.",
+            git::redact_url(&remote)
+        )
+    };
+
+    let branches = git::branches_matching(&path, |b| scan.rfc_number(b) == Some(args.number))?;
+    let pr = match (args.file, args.pr) {
+        (true, _) => false,
+        (_, true) => true,
+        _ => !branches.is_empty(),
+    };
+    let url = if pr {
+        let branch = match branches.as_slice() {
+            [branch] => branch,
+            [] => bail!("No branch found for RFC {:03}.", args.number),
+            _ => bail!(
+                "More than one branch found for RFC {:03}: {}",
+                args.number,
+                branches.join(", ")
+            ),
+        };
+        format!("{}/pull/new/{}", web, branch)
+    } else {
+        let is_rfc = |f: &Path| file_is_text_document(f) && scan.has_rfc_id(f);
+        let committed = git::committed_files(&path, default_branch.as_deref(), &rfc_dirs, is_rfc)?;
+        let Some((file, _)) = committed
+            .iter()
+            .find(|(f, _)| scan.file_number(f) == Some(args.number))
+        else {
+            bail!(
+                "RFC {:03} isn't on the default branch yet, try --pr.",
+                args.number
+            )
+        };
+        let branch = git::default_branch_name(&path, default_branch.as_deref())?;
+        let file: Vec<_> = file.iter().map(|c| c.to_string_lossy()).collect();
+        format!("{}/blob/{}/{}", web, branch, file.join("/"))
+    };
+
+    if args.print {
+        println!("{}", url);
+        return Ok(());
+    }
+    let command = platform::browser_command(platform::process_env);
+    let mut browser = platform::launcher(&command);
+    browser.arg(&url);
+    let status = run_program(
+        &runner::System,
+        &mut browser,
+        "Browser",
+        "Set $BROWSER, or pass --print to print the URL instead.",
+    )
+    .with_context(|| format!("Failed to start browser '{}'", command.join(" ")))?;
+    if !status.success() {
+        bail!("Browser '{}' exited with {}", command.join(" "), status)
+    }
+    globals.info(format_args!("Opened {}", url));
+
+    Ok(())
+}
+
+fn cmd_touch(globals: &Globals, config: Config, target: &Target) -> Result<()> {
+    let scan = globals.scan();
+    let date_format = config.date_format()?;
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, scan)?;
+    let file = target.resolve(&path, &files, scan)?;
+    let content =
+        fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+
+    // Teams that already track `last_modified` keep doing so; everyone else
+    // gets `updated`.
+    let field = match metadata::parse(&content).get("last_modified") {
+        Some(_) => "last_modified",
+        None => "updated",
+    };
+    let today = Utc::now().date_naive().format(&date_format).to_string();
+    let updated = metadata::set_field(&content, field, &today);
+    if updated == content {
+        globals.info(format_args!(
+            "{} is already dated {}",
+            file.display(),
+            today
+        ));
+        return Ok(());
+    }
+    fs::write(&file, updated)?;
+
+    let relative = git::relative_to_repo(&path, &file)?;
+    let message = match scan.file_number(&file) {
+        Some(number) => format!("Update date of RFC {:03}", number),
+        None => format!("Update date of {}", relative.display()),
+    };
+    git::commit_files(&path, &[&relative], &message)?;
+    globals.info(format_args!(
+        "Set {} of {} to {}",
+        field,
+        relative.display(),
+        today
+    ));
+
+    Ok(())
+}
+
+/// Renames the file and branch of the RFC `target` after `title`, or with
+/// `None`, after the title the RFC has.
+fn cmd_rename(
+    globals: &Globals,
+    config: Config,
+    target: &Target,
+    title: Option<&str>,
+) -> Result<()> {
+    let scan = globals.scan();
+    let separator = config.separator()?;
+    let max_len = config
+        .slug_max_length
+        .unwrap_or(config::DEFAULT_SLUG_MAX_LENGTH);
+    let slug_case = config.slug_case();
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, scan)?;
+    let file = target.resolve(&path, &files, scan)?;
+    let Some(number) = scan.file_number(&file) else {
+        bail!("{} doesn't have an RFC number.", file.display())
+    };
+    let original =
+        fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let front_matter = metadata::parse(&original);
+    let (title, content) = match title {
+        Some(title) if front_matter.get("title").is_some() => (
+            title.to_string(),
+            metadata::set_field(&original, "title", title),
+        ),
+        Some(title) => (title.to_string(), original.clone()),
+        None => (rfc_title(&file, &front_matter, &original), original.clone()),
+    };
+
+    let name = format!(
+        "{:03}{}{}",
+        number,
+        separator,
+        slug(&title, max_len, slug_case)
+    );
+    let mut new_file = file.with_file_name(&name);
+    if let Some(extension) = file.extension() {
+        new_file.set_extension(extension);
+    }
+    let old = git::relative_to_repo(&path, &file)?;
+    let new = git::relative_to_repo(&path, &new_file)?;
+    if new_file != file {
+        if new_file.exists() {
+            bail!(
+                "Can't rename {}, {} already exists.",
+                old.display(),
+                new.display()
+            )
+        }
+        fs::write(&new_file, content)?;
+        fs::remove_file(&file)?;
+        git::commit_files(
+            &path,
+            &[&old, &new],
+            &format!("Rename RFC {:03} to {}", number, name),
+        )?;
+        globals.info(format_args!(
+            "Renamed {} to {}",
+            old.display(),
+            new.display()
+        ));
+    } else if content != original {
+        fs::write(&file, content)?;
+        git::commit_files(&path, &[&old], &format!("Retitle RFC {:03}", number))?;
+        globals.info(format_args!("Set the title of {}", old.display()));
+    } else {
+        globals.info(format_args!("{} is already named {}", old.display(), name));
+    }
+
+    let branches = git::branches_matching(&path, |b| scan.rfc_number(b) == Some(number))?;
+    match branches.as_slice() {
+        [branch] if *branch != name => {
+            git::rename_branch(&path, branch, &name)?;
+            globals.info(format_args!("Renamed branch {} to {}", branch, name));
+        }
+        [_] | [] => {}
+        _ => globals.warn(format_args!(
+            "Not renaming the branches of RFC {:03}, there's more than one: {}",
+            number,
+            branches.join(", ")
+        ))?,
+    }
+
+    Ok(())
+}
+
+fn cmd_promote(
+    globals: &Globals,
+    config: Config,
+    targets: &[TargetSet],
+    status: &str,
+) -> Result<()> {
+    let scan = globals.scan();
+    let transitions = config
+        .transitions
+        .clone()
+        .unwrap_or_else(lifecycle::default_transitions);
+    let rfc_dirs = config.rfc_dirs();
+    let status_dir = config
+        .status_dirs
+        .as_ref()
+        .and_then(|dirs| dirs.get(status));
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, scan)?;
+    // Every transition is checked before any RFC is changed, so that one
+    // that isn't allowed leaves them all alone.
+    let mut promoted = vec![];
+    for file in TargetSet::expand(targets, &path, &files, scan, false)? {
+        let content = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let current = metadata::parse(&content)
+            .get("status")
+            .unwrap_or(lifecycle::INITIAL_STATUS)
+            .to_string();
+        lifecycle::check_transition(&transitions, &current, status)
+            .with_context(|| format!("Can't promote {}", file.display()))?;
+        let relative = git::relative_to_repo(&path, &file)?;
+        // Where `status_dirs` files the RFC, if it isn't there already.
+        let moved = match (status_dir, file.file_name()) {
+            (Some(dir), Some(name)) => {
+                let root = rfc_root(
+                    &path,
+                    rfc_dir_of(&path, &rfc_dirs, &file).map(|d| d.as_path()),
+                );
+                Some(root.join(dir).join(name)).filter(|moved| *moved != file)
+            }
+            _ => None,
+        };
+        let moved = match moved {
+            Some(moved) if moved.exists() => bail!(
+                "Can't file {} as {}, which already exists.",
+                relative.display(),
+                moved.display()
+            ),
+            Some(moved) => Some((git::relative_to_repo(&path, &moved)?, moved)),
+            None => None,
+        };
+        promoted.push((file, relative, content, current, moved));
+    }
+
+    let names: Vec<String> = promoted
+        .iter()
+        .map(|(_, relative, _, _, _)| relative.display().to_string())
+        .collect();
+    if !globals.confirm(format_args!(
+        "Move {} to {} and commit that?",
+        names.join(", "),
+        status
+    ))? {
+        bail!(
+            "Not promoting {}. Pass --yes to promote without asking.",
+            names.join(", ")
+        )
+    }
+    for (file, _, content, _, moved) in &promoted {
+        let content = metadata::set_field(content, "status", status);
+        match moved {
+            Some((_, moved)) => {
+                if let Some(dir) = moved.parent() {
+                    fs::create_dir_all(dir)
+                        .with_context(|| format!("Failed to create {}", dir.display()))?;
+                }
+                fs::write(moved, content)?;
+                fs::remove_file(file)?;
+            }
+            None => fs::write(file, content)?,
+        }
+    }
+    let message = match promoted.as_slice() {
+        [(file, relative, _, _, _)] => match scan.file_number(file) {
+            Some(number) => format!("Mark RFC {:03} as {}", number, status),
+            None => format!("Mark {} as {}", relative.display(), status),
+        },
+        _ => format!(
+            "Mark RFCs {} as {}",
+            promoted
+                .iter()
+                .map(|(file, relative, _, _, _)| match scan.file_number(file) {
+                    Some(number) => format!("{:03}", number),
+                    None => relative.display().to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            status
+        ),
+    };
+    let relatives: Vec<&Path> = promoted
+        .iter()
+        .flat_map(|(_, relative, _, _, moved)| {
+            std::iter::once(relative.as_path()).chain(moved.as_ref().map(|(m, _)| m.as_path()))
+        })
+        .collect();
+    git::commit_files(&path, &relatives, &message)?;
+    for (_, relative, _, current, moved) in &promoted {
+        globals.info(format_args!(
+            "Moved {} from {} to {}",
+            relative.display(),
+            current,
+            status
+        ));
+        if let Some((moved, _)) = moved {
+            globals.info(format_args!("Filed it as {}", moved.display()));
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_log(globals: &Globals, config: Config, target: &Target) -> Result<()> {
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, globals.scan())?;
+    let file = target.resolve(&path, &files, globals.scan())?;
+    let relative = git::relative_to_repo(&path, &file)?;
+
+    let history = git::file_history(&path, &relative)?;
+    for entry in &history {
+        let date = commit_date(entry.time);
+        let renamed = match entry.path == relative {
+            true => String::new(),
+            false => format!(" ({})", entry.path.display()),
+        };
+        println!(
+            "{} {} {}{}",
+            short_id(entry.id),
+            date,
+            entry.summary,
+            renamed
+        );
+    }
+
+    Ok(())
+}
+
+fn cmd_diff(globals: &Globals, config: Config, target: &Target, from: Option<&str>) -> Result<()> {
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, globals.scan())?;
+    let file = target.resolve(&path, &files, globals.scan())?;
+    let relative = git::relative_to_repo(&path, &file)?;
+    let from = match from {
+        Some(rev) => rev.to_string(),
+        None => git::default_branch_name(&path, default_branch.as_deref())?,
+    };
+
+    let lines = git::diff_file(&path, &from, &relative)?;
+    if lines.is_empty() {
+        globals.info(format_args!(
+            "{} is the same as on {}",
+            relative.display(),
+            from
+        ));
+        return Ok(());
+    }
+    print!("{}", diff_text(&lines, globals.color()));
+    Ok(())
+}
+
+/// `lines` as a unified diff, with additions in green, deletions in red and
+/// hunk headers in cyan if `color`.
+fn diff_text(lines: &[git::DiffLine], color: bool) -> String {
+    let mut out = String::new();
+    for line in lines {
+        let (prefix, paint) = match line.origin {
+            '+' => ("+", Some(color::Color::Green)),
+            '-' => ("-", Some(color::Color::Red)),
+            ' ' => (" ", None),
+            'H' => ("", Some(color::Color::Cyan)),
+            _ => ("", None),
+        };
+        let text = format!("{}{}", prefix, line.text);
+        // Painted up to the newline, so the next line starts uncolored.
+        let (text, newline) = match text.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (text.as_str(), ""),
+        };
+        match paint {
+            Some(c) => out.push_str(&color::paint(color, c, text)),
+            None => out.push_str(text),
+        }
+        out.push_str(newline);
+    }
+    out
+}
+
+fn cmd_reconcile(globals: &Globals, config: Config) -> Result<()> {
+    let scan = globals.scan();
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let files = rfc_files(&path, &rfc_dirs, scan)?;
+    let file_numbers: HashSet<usize> = files.iter().filter_map(|f| scan.file_number(f)).collect();
+    let branches = rfc_branches(&path, scan)?;
+    let branch_numbers: HashSet<usize> =
+        branches.iter().filter_map(|b| scan.rfc_number(b)).collect();
+
+    // A branch with its RFC committed on it is one in progress, even if
+    // the default branch doesn't have the file yet.
+    let without_file: Vec<String> = branches
+        .into_iter()
+        .filter(|b| {
+            scan.rfc_number(b)
+                .is_some_and(|n| !file_numbers.contains(&n))
+        })
+        .collect();
+    let with_rfc = branches_with_rfc_in(&path, without_file.clone(), &rfc_dirs)?;
+    let mut branch_only: Vec<String> = without_file
+        .into_iter()
+        .filter(|b| !with_rfc.contains(b))
+        .collect();
+    branch_only.sort_by_key(|b| (scan.rfc_number(b), b.clone()));
+    let mut file_only: Vec<&PathBuf> = files
+        .iter()
+        .filter(|f| {
+            scan.file_number(f)
+                .is_some_and(|n| !branch_numbers.contains(&n))
+        })
+        .collect();
+    file_only.sort_by_cached_key(|f| (scan.file_number(f), *f));
+
+    let file_only: Vec<String> = file_only
+        .iter()
+        .map(|f| f.strip_prefix(&path).unwrap_or(f).display().to_string())
+        .collect();
+    for (heading, entries) in [
+        ("Branches without an RFC file:", branch_only),
+        ("RFC files without a branch:", file_only),
+    ] {
+        println!("{}", heading);
+        if entries.is_empty() {
+            println!("  none");
+        }
+        for entry in entries {
+            println!("  {}", entry);
+        }
+    }
+    Ok(())
+}
+
+fn cmd_prune(globals: &Globals, config: Config) -> Result<()> {
+    let scan = globals.scan();
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let path = ensure_local_repo(globals, &config)?;
+    let merged: Vec<String> = git::merged_branches(&path, default_branch.as_deref())?
+        .into_iter()
+        .filter(|b| scan.rfc_number(b).is_some())
+        .collect();
+    if merged.is_empty() {
+        globals.info("No merged RFC branches to prune.");
+        return Ok(());
+    }
+
+    if !globals.confirm(format_args!(
+        "Delete the merged RFC branches {}?",
+        merged.join(", ")
+    ))? {
+        bail!(
+            "Not deleting {}. Pass --yes to delete without asking.",
+            merged.join(", ")
+        )
+    }
+    for branch in &merged {
+        git::delete_branch(&path, branch)?;
+        globals.info(format_args!("Deleted branch {}", branch));
+    }
+
+    Ok(())
+}
+
+fn cmd_sync(globals: &Globals, config: Config, all: bool) -> Result<()> {
+    let token = config.git.as_ref().and_then(|g| g.effective_token());
+    let path = ensure_local_repo(globals, &config)?;
+    let remotes = match all {
+        true => git::remotes(&path)?,
+        false => vec![String::from("origin")],
+    };
+    if remotes.is_empty() {
+        bail!(
+            "The repository at {} has no remotes to fetch from.",
+            path.display()
+        )
+    }
+    for remote in &remotes {
+        let fetched = git::fetch(
+            &runner::System,
+            &path,
+            remote,
+            token.clone(),
+            globals.deadline,
+        )?;
+        globals.info(format_args!(
+            "Fetched {}: {} new, {} updated, {} pruned",
+            remote,
+            fetched.new.len(),
+            fetched.updated.len(),
+            fetched.pruned.len()
+        ));
+        for (what, branches) in [
+            ("new", &fetched.new),
+            ("updated", &fetched.updated),
+            ("pruned", &fetched.pruned),
+        ] {
+            for branch in branches {
+                globals.info(format_args!("  {:<8} {}", what, branch));
+            }
+        }
+    }
+
+    Ok(())
+}
 
-#[derive(Debug, Clone, Subcommand)]
-enum Command {
-    List,
-    DumpInfo,
-    Configure { key: String, value: String },
-    Create { title: String },
-    // Show,
-    // Edit,
+fn cmd_merge(globals: &Globals, config: Config, number: usize, keep_status: bool) -> Result<()> {
+    let scan = globals.scan();
+    let default_branch = config.git.as_ref().and_then(|g| g.default_branch.clone());
+    let rfc_dirs = config.rfc_dirs();
+    let path = ensure_local_repo(globals, &config)?;
+    let branches = git::branches_matching(&path, |b| scan.rfc_number(b) == Some(number))?;
+    let branch = match branches.as_slice() {
+        [branch] => branch,
+        [] => bail!("No branch found for RFC {:03}.", number),
+        _ => bail!(
+            "More than one branch found for RFC {:03}: {}",
+            number,
+            branches.join(", ")
+        ),
+    };
+
+    if !globals.confirm(format_args!(
+        "Merge branch {} into the default branch and delete it?",
+        branch
+    ))? {
+        bail!(
+            "Not merging {}. Pass --yes to merge without asking.",
+            branch
+        )
+    }
+
+    let outcome = git::merge_into_default_branch(&path, branch, default_branch.as_deref())?;
+    globals.info(match outcome {
+        git::MergeOutcome::UpToDate => format!("Branch {} was already merged", branch),
+        git::MergeOutcome::FastForward => format!("Fast-forwarded to branch {}", branch),
+        git::MergeOutcome::MergeCommit => format!("Merged branch {}", branch),
+    });
+
+    if !keep_status {
+        let files = rfc_files(&path, &rfc_dirs, scan)?;
+        let file = match files.iter().find(|f| scan.file_number(f) == Some(number)) {
+            Some(file) => file,
+            None => bail!(
+                "Merged branch {}, but there's no file for RFC {:03} to mark as accepted.",
+                branch,
+                number
+            ),
+        };
+        let content = fs::read_to_string(file)?;
+        if metadata::parse(&content).get("status") != Some("accepted") {
+            fs::write(file, metadata::set_field(&content, "status", "accepted"))?;
+            let relative = git::relative_to_repo(&path, file)?;
+            git::commit_files(
+                &path,
+                &[&relative],
+                &format!("Mark RFC {:03} as accepted", number),
+            )?;
+            globals.info(format_args!("Marked {} as accepted", relative.display()));
+        }
+    }
+
+    git::delete_branch(&path, branch)?;
+    globals.info(format_args!("Deleted branch {}", branch));
+
+    Ok(())
 }
 
-#[derive(Parser, Debug)]
-#[command(author, version, about)]
-struct Args {
-    #[command(subcommand)]
-    command: Command,
+/// Whether the RFC number of `file` lies within `from..=to`, either bound
+/// being optional.
+fn number_in_range(file: &Path, scan: &Scan, from: Option<usize>, to: Option<usize>) -> bool {
+    match scan.file_number(file) {
+        Some(number) => from.is_none_or(|f| number >= f) && to.is_none_or(|t| number <= t),
+        None => false,
+    }
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let config = load_config()?;
-    match args.command {
-        Command::List => cmd_list(config),
-        Command::DumpInfo => cmd_dump_info(config),
-        Command::Configure { key, value } => cmd_config(config, key, value),
-        Command::Create { title } => cmd_create(config, title),
+/// How RFC files are found and numbered, see `rfc_files`.
+#[derive(Debug, Clone, Default)]
+struct Scan {
+    /// See `--follow-symlinks`.
+    follow_symlinks: bool,
+    /// See `Config::number_in_front_matter`.
+    number_in_front_matter: bool,
+    /// How deep `files_in_rfc_repo` looks, see `--max-depth`. No limit if
+    /// unset.
+    max_depth: Option<usize>,
+    /// See `Config::preferred_extensions`.
+    preferred_extensions: Option<Vec<String>>,
+    /// The regex RFC numbers are found with, see `--pattern`. Unset for
+    /// `RFC_REGEX_PATTERN`.
+    pattern: Option<Regex>,
+}
+
+impl Scan {
+    /// Whether `file` has an RFC number, in its name or, if numbers may be
+    /// there, its front matter.
+    fn is_numbered(&self, file: &Path) -> bool {
+        self.has_rfc_id(file)
+            || (self.number_in_front_matter && front_matter_number(file).is_some())
+    }
+
+    /// Whether the name of `file` has an RFC number, going by `pattern`.
+    fn has_rfc_id(&self, file: &Path) -> bool {
+        match &self.pattern {
+            Some(pattern) => file
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| pattern.is_match(name)),
+            None => file_has_rfc_id(file),
+        }
+    }
+
+    /// The RFC number in `name`, a file or branch name, going by `pattern`.
+    fn rfc_number(&self, name: &str) -> Option<usize> {
+        match &self.pattern {
+            Some(pattern) => number_matched(pattern, name),
+            None => rfc_number(name),
+        }
+    }
+
+    /// The RFC number in the file name of `file`, or else, with
+    /// `number_in_front_matter` set, the `number:` of its front matter.
+    fn file_number(&self, file: &Path) -> Option<usize> {
+        let in_name = file.file_name().and_then(|n| n.to_str());
+        match in_name.and_then(|name| self.rfc_number(name)) {
+            Some(number) => Some(number),
+            None if self.number_in_front_matter => front_matter_number(file),
+            None => None,
+        }
     }
 }
 
-fn cmd_list(config: Config) -> Result<()> {
-    let path = ensure_local_repo(config.git)?;
-    let files = files_in_rfc_repo(&path)?;
+fn front_matter_number(file: &Path) -> Option<usize> {
+    file_front_matter(file)
+        .ok()?
+        .get("number")?
+        .trim()
+        .parse()
+        .ok()
+}
 
-    files.iter().for_each(|f| println!("{}", f.display()));
+/// The RFC numbers used by more than one of `files`, with the files using
+/// each.
+fn number_collisions<'f>(files: &'f [PathBuf], scan: &Scan) -> BTreeMap<usize, Vec<&'f PathBuf>> {
+    numbers_used_twice(files.iter().filter_map(|f| Some((scan.file_number(f)?, f))))
+}
 
-    Ok(())
+/// Groups `users` of RFC numbers by number, keeping the numbers with more
+/// than one user.
+fn numbers_used_twice<T>(users: impl IntoIterator<Item = (usize, T)>) -> BTreeMap<usize, Vec<T>> {
+    let mut by_number: BTreeMap<usize, Vec<T>> = BTreeMap::new();
+    for (n, user) in users {
+        by_number.entry(n).or_default().push(user);
+    }
+    by_number.retain(|_, users| users.len() > 1);
+    by_number
 }
 
-fn cmd_dump_info(config: Config) -> Result<()> {
-    println!("Configuration location: {}", config_path().display());
-    println!(
-        "git.repo: {:?}",
-        config.git.as_ref().and_then(|g| g.repo.as_ref())
-    );
-    println!(
-        "git.url: {:?}",
-        config.git.as_ref().and_then(|g| g.url.as_ref())
-    );
-    Ok(())
+/// The RFC number in `name`, going by `RFC_REGEX_PATTERN`.
+fn rfc_number(name: &str) -> Option<usize> {
+    number_matched(rfc_regex(), name)
 }
 
-fn cmd_config(mut config: Config, key: String, value: String) -> Result<()> {
-    println!("Setting key {} to value {}", &key, &value);
-    match key.as_str() {
-        "git.url" => {
-            config.git = match config.git {
-                Some(git) => Some(Git {
-                    url: Some(value),
-                    repo: git.repo,
-                }),
-                None => Some(Git {
-                    url: Some(value),
-                    repo: None,
-                }),
-            }
-        }
-        "git.repo" => match PathBuf::try_from(value.clone()) {
-            Ok(path) => {
-                config.git = match config.git {
-                    Some(git) => Some(Git {
-                        url: git.url,
-                        repo: Some(path),
-                    }),
-                    None => Some(Git {
-                        url: None,
-                        repo: Some(path),
-                    }),
-                }
-            }
-            Err(_) => {
-                bail!(
-                    "Was not able to convert given value '{}' into a file path, \
-                       please supply a valid path.",
-                    value
-                )
-            }
-        },
-        _ => {
-            bail!(
-                "Unknown configuration key '{}', known keys: git.url, git.repo",
-                key
-            )
-        }
-    };
+/// The `rfc_number` group of `pattern` in `name`, as a number.
+fn number_matched(pattern: &Regex, name: &str) -> Option<usize> {
+    pattern
+        .captures(name)?
+        .name("rfc_number")?
+        .as_str()
+        .parse()
+        .ok()
+}
 
-    write_config(config)?;
-    println!("Wrote config.");
+/// Gathers the branches, files and tags of the repository at `path` that
+/// count towards the next RFC number, and works out that number. Tags only
+/// count with a `tag_prefix`, see `config::Git::tag_prefix`. Files count if
+/// they're in one of `dirs`; with `per_dir` numbering, so do only the
+/// branches whose RFC is. Numbers set aside with `rfcs reserve` count in
+/// every directory.
+fn compute_next_rfc_number(
+    path: &Path,
+    dirs: &[PathBuf],
+    per_dir: bool,
+    tag_prefix: Option<&str>,
+    default_branch: Option<&str>,
+    scan: &Scan,
+    numbering: &NumberingArgs,
+) -> Result<usize> {
+    let mut branches = rfc_branches(path, scan)?;
+    if numbering.exclude_merged {
+        let merged = git::merged_branches(path, default_branch)?;
+        branches.retain(|b| !merged.contains(b));
+    }
+    if per_dir {
+        branches = branches_with_rfc_in(path, branches, dirs)?;
+    }
+    if let Some(prefix) = tag_prefix {
+        branches.extend(git::rfc_tags(path, prefix)?);
+    }
+    let mut files = rfc_files(path, dirs, scan)?;
+    if !numbering.include_untracked {
+        let untracked = git::untracked_files(path)?;
+        files.retain(|f| {
+            git::relative_to_repo(path, f).map_or(true, |relative| !untracked.contains(&relative))
+        });
+    }
 
-    Ok(())
+    let reserved = reserve::Reservations::read(&reserve::ledger_path(&git::git_dir(path)?))?;
+
+    Ok(next_rfc_number(&branches, &files, scan).max(reserved.next()))
 }
 
-fn cmd_create(config: Config, title: String) -> Result<()> {
-    let path = ensure_local_repo(config.git)?;
-    let branches = git::list_branches(&path)?;
-    let files = files_in_rfc_repo(&path)?;
-    let next_rfc = next_rfc_number(&branches, &files);
+/// The files, local branches and tags of the repository at `path` that
+/// already use RFC `number`, going by `dirs` and `per_dir` like
+/// `compute_next_rfc_number`.
+fn rfc_number_users(
+    path: &Path,
+    dirs: &[PathBuf],
+    per_dir: bool,
+    tag_prefix: Option<&str>,
+    scan: &Scan,
+    number: usize,
+) -> Result<Vec<String>> {
+    let files = rfc_files(path, dirs, scan)?;
+    let mut branches = git::branches_matching(path, |b| scan.rfc_number(b) == Some(number))?;
+    if per_dir {
+        branches = branches_with_rfc_in(path, branches, dirs)?;
+    }
+    let tags = match tag_prefix {
+        Some(prefix) => git::rfc_tags(path, prefix)?,
+        None => vec![],
+    };
+    Ok(files
+        .iter()
+        .filter(|f| scan.file_number(f) == Some(number))
+        .map(|f| f.display().to_string())
+        .chain(branches.into_iter().map(|b| format!("branch {}", b)))
+        .chain(
+            tags.into_iter()
+                .filter(|t| scan.rfc_number(t) == Some(number))
+                .map(|t| format!("tag {}{}", tag_prefix.unwrap_or_default(), t)),
+        )
+        .collect())
+}
 
-    let branch_name = format!(
-        "{:03}-{}",
-        next_rfc,
-        title.replace(' ', "-").replace([',', '.', '?', '!'], "")
-    );
-    println!("Branch will be named {}", branch_name);
+/// The local branches of the repository at `path` that have an RFC number.
+fn rfc_branches(path: &Path, scan: &Scan) -> Result<Vec<String>> {
+    git::branches_matching(path, |b| scan.rfc_number(b).is_some())
+}
 
-    git::create_and_switch_to_branch(&path, &branch_name)?;
-    println!("Created and checked out git branch {}", branch_name);
+/// The ones of `branches` whose RFC, the file named like the branch, is in
+/// one of `dirs` on that branch.
+fn branches_with_rfc_in(
+    path: &Path,
+    branches: Vec<String>,
+    dirs: &[PathBuf],
+) -> Result<Vec<String>> {
+    let mut kept = vec![];
+    for branch in branches {
+        let own_rfc = |f: &Path| f.file_stem() == Some(std::ffi::OsStr::new(&branch));
+        if !git::files_on_branch(path, &branch, dirs, own_rfc)?.is_empty() {
+            kept.push(branch);
+        }
+    }
+    Ok(kept)
+}
 
-    Ok(())
+/// The directory among `rfc_dirs` a new RFC goes into, the one `--dir`
+/// names or else the first, and the directories whose RFCs count towards
+/// its number: all of them, or with `numbering.per_dir` just that one.
+fn numbering_dirs(config: &Config, numbering: &NumberingArgs) -> Result<(PathBuf, Vec<PathBuf>)> {
+    let dirs = config.rfc_dirs();
+    let dir = match &numbering.dir {
+        None => dirs[0].clone(),
+        Some(dir) if dirs.contains(dir) => dir.clone(),
+        Some(dir) => bail!(
+            "{} isn't one of the RFC directories in rfc_dirs: {}",
+            dir.display(),
+            dirs.iter()
+                .map(|d| d.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    match config.per_dir_numbering() {
+        true => Ok((dir.clone(), vec![dir])),
+        false => Ok((dir, dirs)),
+    }
 }
 
 /// Find the next appropriate RFC number by looking through the present files,
 /// and the local git branches, find the highest RFC number, then add one.
-fn next_rfc_number(git_branches: &[String], rfcs_in_repo: &[PathBuf]) -> usize {
-    let re = regex::Regex::new(RFC_REGEX_PATTERN).expect("RFC_REGEX_PATTERN failed to compile.");
+fn next_rfc_number(git_branches: &[String], rfcs_in_repo: &[PathBuf], scan: &Scan) -> usize {
     rfcs_in_repo
         .iter()
-        .map(|f| f.to_string_lossy().to_string())
-        .chain(git_branches.to_owned())
-        .filter_map(|f| match re.captures(&f) {
-            Some(m) => {
-                let number: usize = m
-                    .name("rfc_number")
-                    .expect(
-                        "A present match with no match for the only capture \
-                         group ought not to happen.",
-                    )
-                    .as_str()
-                    .parse()
-                    .expect(
-                        "A match with a digit regex that does not parse into \
-                         numbers also ought not to happen.",
-                    );
-                Some(number)
-            }
-            // IF no match is present, we simply drop the value. This is here
-            // because while the file-based RFC list might be valid, the git
-            // branches are not validated/searched on retrieval.
-            None => None,
-        })
-        .fold(1, |acc, num| acc.max(num)) + 1
+        // Only the file name counts, the directories the repository lives in
+        // might well contain digits of their own.
+        .filter_map(|f| scan.file_number(f))
+        // The git branches are not validated/searched on retrieval, the ones
+        // without a number are simply dropped.
+        .chain(git_branches.iter().filter_map(|b| scan.rfc_number(b)))
+        .fold(1, |acc, num| acc.max(num))
+        + 1
+}
+
+/// The directory inside `repo` that RFCs live in, see `Config::rfc_dir`.
+fn rfc_root(repo: &Path, rfc_dir: Option<&Path>) -> PathBuf {
+    match rfc_dir {
+        Some(dir) if !dir.as_os_str().is_empty() => repo.join(dir),
+        _ => repo.to_path_buf(),
+    }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct Config {
-    pub git: Option<Git>,
+/// The RFC files in each of `dirs` of `repo`, see `Config::rfc_dirs`. A file
+/// in nested directories is only listed once.
+fn rfc_files(repo: &Path, dirs: &[PathBuf], scan: &Scan) -> Result<Vec<PathBuf>> {
+    let mut seen = HashSet::new();
+    let mut files = vec![];
+    for dir in dirs {
+        for file in files_in_rfc_repo(&rfc_root(repo, Some(dir)), scan)? {
+            if seen.insert(file.clone()) {
+                files.push(file);
+            }
+        }
+    }
+    Ok(files)
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct Git {
-    pub repo: Option<PathBuf>,
-    pub url: Option<String>,
+/// The one of `dirs` that `file` in `repo` is in, the innermost if they're
+/// nested.
+fn rfc_dir_of<'d>(repo: &Path, dirs: &'d [PathBuf], file: &Path) -> Option<&'d PathBuf> {
+    dirs.iter()
+        .filter(|dir| file.starts_with(rfc_root(repo, Some(dir))))
+        .max_by_key(|dir| dir.components().count())
 }
 
-fn files_in_rfc_repo(local_repo: &Path) -> Result<Vec<PathBuf>> {
+/// The RFC files under `local_repo`. Symbolic links are skipped unless
+/// `scan.follow_symlinks` is set; walkdir notices links that loop back up
+/// the tree, which are reported and skipped.
+fn files_in_rfc_repo(local_repo: &Path, scan: &Scan) -> Result<Vec<PathBuf>> {
     let res = walkdir::WalkDir::new(local_repo)
+        .follow_links(scan.follow_symlinks)
+        .max_depth(scan.max_depth.unwrap_or(usize::MAX))
         .into_iter()
         .filter_map(|dir_entry| match dir_entry {
-            Ok(entry) => Some(PathBuf::from(entry.path())),
+            // Without following, a symbolic link's file type is the link
+            // itself, so this drops links and directories alike.
+            Ok(entry) if entry.file_type().is_file() => Some(PathBuf::from(entry.path())),
+            Ok(_) => None,
             Err(err) => {
                 eprintln!("Error while processing/reading a file: {}", err);
                 None
             }
         })
         .filter(|f| file_is_text_document(f))
-        .filter(|f| file_has_rfc_id(f))
+        .filter(|f| scan.is_numbered(f))
         .collect();
 
-    Ok(res)
+    Ok(match &scan.preferred_extensions {
+        Some(extensions) => preferred_formats(res, extensions),
+        None => res,
+    })
 }
 
-fn ensure_local_repo(git: Option<Git>) -> Result<PathBuf> {
-    match git {
-        Some(g) => match g.repo {
-            Some(repo) => Ok(repo),
-            None => match g.url {
-                Some(ref url) => {
-                    let config_dir = config_path()
-                        .parent()
-                        .expect("Config path must have parent")
-                        .to_path_buf();
-                    checkout_git_url_locally(config_dir, url.clone())
-                }
-                None => {
-                    bail!(
-                        "No local git repo configured, and no git URL given, \
-                         can't do anything.\n \
-                         To configure, run `rfcs configure git-url <git URL>`, \
-                         or `rfcs configure git-checkout /path/to/rfcs`."
-                    )
-                }
-            },
-        },
-        None => bail!(
-            "No local git repo configured, and no git URL given, \
-             can't do anything.\n \
-             To configure, run `rfcs configure git-url <git URL>`, \
-             or `rfcs configure git-checkout /path/to/rfcs`."
-        ),
+/// `files` without the ones that are also there in a format that comes
+/// first in `extensions`, going by the path without its extension. A file
+/// whose extension isn't in `extensions` is only left out if the same RFC is
+/// there in one that is.
+fn preferred_formats(files: Vec<PathBuf>, extensions: &[String]) -> Vec<PathBuf> {
+    let rank = |f: &Path| {
+        let extension = f.extension()?.to_str()?.to_lowercase();
+        extensions.iter().position(|e| *e == extension)
+    };
+    let mut best: HashMap<PathBuf, usize> = HashMap::new();
+    for file in &files {
+        if let Some(rank) = rank(file) {
+            let best = best.entry(file.with_extension("")).or_insert(rank);
+            *best = (*best).min(rank);
+        }
+    }
+    files
+        .into_iter()
+        .filter(|f| match best.get(&f.with_extension("")) {
+            Some(best) => rank(f) == Some(*best),
+            None => true,
+        })
+        .collect()
+}
+
+/// The text documents under `dir` without an RFC number, see
+/// `list --include-unnumbered`. There are none if `dir` doesn't exist.
+fn unnumbered_drafts(dir: &Path, scan: &Scan) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut drafts: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .follow_links(scan.follow_symlinks)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|f| file_is_text_document(f) && !scan.is_numbered(f))
+        .collect();
+    drafts.sort();
+    Ok(drafts)
+}
+
+/// The local checkout of the RFC repository: `repo.path`, or else a clone of
+/// `git.url` in `--clone-dir`, `git.clone_dir` or the config directory, which
+/// is made on first use unless `--no-clone` is given.
+fn ensure_local_repo(globals: &Globals, config: &Config) -> Result<PathBuf> {
+    let repo = local_repo(globals, config)?;
+    if git::is_bare(&repo) {
+        bail!(
+            "repo.path {} is a bare repository, which has no files to work \
+             with. Point repo.path at a regular checkout, e.g. one made with \
+             `git clone {} rfcs`.",
+            repo.display(),
+            repo.display()
+        )
+    }
+    Ok(repo)
+}
+
+/// Like `ensure_local_repo`, but the repository may be bare.
+fn local_repo(globals: &Globals, config: &Config) -> Result<PathBuf> {
+    let g = config.git.clone().unwrap_or_default();
+    if let Some(repo) = config.repo.as_ref().and_then(|r| r.path.clone()) {
+        if let Some(url) = &g.url {
+            check_origin(globals, &repo, url)?;
+        }
+        return Ok(repo);
+    }
+    // Like git itself, for repositories laid out unusually.
+    if let Some(repo) = git::repo_from_env()? {
+        return Ok(repo);
+    }
+    let Some(ref url) = g.url else {
+        if g.discover.unwrap_or(true) {
+            if let Some(repo) = git::discover(Path::new(".")) {
+                return Ok(repo);
+            }
+        }
+        bail!(NOT_CONFIGURED)
+    };
+
+    let custom_dir = globals.clone_dir.clone().or(g.clone_dir.clone());
+    let clone_dir = match &custom_dir {
+        Some(dir) => std::path::absolute(dir)?,
+        None => config_path()?
+            .parent()
+            .expect("Config path must have parent")
+            .to_path_buf(),
+    };
+    let clone = clone_dir.join("rfcs");
+    if clone.exists() {
+        return Ok(clone);
+    }
+    if globals.no_clone {
+        bail!(
+            "repo.path is not set, and --no-clone keeps {} from being cloned to {}. \
+             Run without --no-clone, or point repo.path at an existing checkout.",
+            git::redact_url(url),
+            clone.display()
+        )
+    }
+    if g.auto_clone == Some(false) {
+        let url = git::redact_url(&git::clone_source(url, &std::env::current_dir()?));
+        let dest = clone.to_string_lossy();
+        let depth = (g.shallow == Some(true)).then_some("--depth=1");
+        let words: Vec<&str> = ["git", "clone"]
+            .into_iter()
+            .chain(depth)
+            .chain([url.as_str(), &*dest])
+            .collect();
+        bail!(
+            "repo.path is not set, and git.auto_clone is off, so {} isn't cloned. \
+             Clone it yourself, then run this again:\n\n    {}",
+            url,
+            platform::shell_command(&words)
+        )
+    }
+    let token = g.effective_token();
+    if token.is_some() && g.shallow == Some(true) {
+        globals.warn(
+            "Cloning with a token always fetches the whole history, git.shallow is ignored.",
+        )?;
+    }
+    ensure_writable_dir(&clone_dir)?;
+    let repo = checkout_git_url_locally(
+        &runner::System,
+        clone_dir,
+        url.clone(),
+        token,
+        g.clone_timeout(),
+        globals.deadline,
+        g.shallow.unwrap_or(false),
+    )?;
+    // Outside the config directory, the clone would only be found again with
+    // the same clone directory, so repo.path remembers it instead.
+    if custom_dir.is_some() && platform::process_env("RFCS_NO_CONFIG_WRITE").is_none() {
+        let mut config = config::load_scope(Scope::Global)?;
+        config.repo.get_or_insert_with(Repo::default).path = Some(repo.clone());
+        config::write_config(config)?;
+        eprintln!("Set repo.path to '{}'", repo.display());
+    }
+    Ok(repo)
+}
+
+/// Warns when `repo`, which repo.path points at, isn't a clone of `url` from
+/// git.url, since repo.path wins and the URL is ignored.
+fn check_origin(globals: &Globals, repo: &Path, url: &str) -> Result<()> {
+    match git::remote_url(repo, "origin") {
+        Some(origin) if git::same_remote(&origin, url) => Ok(()),
+        Some(origin) => globals.warn(format!(
+            "repo.path {} is a clone of {}, not of git.url {}. Using repo.path; \
+             point it at a clone of git.url, or change git.url to match.",
+            repo.display(),
+            git::redact_url(&origin),
+            git::redact_url(url)
+        )),
+        None => globals.warn(format!(
+            "repo.path {} has no origin remote to check against git.url {}. \
+             Using repo.path.",
+            repo.display(),
+            git::redact_url(url)
+        )),
     }
 }
 
+/// Creates `dir` if need be, and fails early if nothing can be written to it,
+/// rather than partway through a clone.
+fn ensure_writable_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let probe = dir.join(".rfcs-write-check");
+    fs::write(&probe, "")
+        .with_context(|| format!("Can't clone into {}, it isn't writable", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+const NOT_CONFIGURED: &str = "No local git repo configured, and no git URL given, \
+                              can't do anything.\n \
+                              To configure, run `rfcs configure git.url <git URL>`, \
+                              or `rfcs configure repo.path /path/to/rfcs`.";
+
 fn file_is_text_document(f: &Path) -> bool {
-    match f.extension() {
+    match f.extension().and_then(|e| e.to_str()) {
         Some(e) => matches!(
-            e.to_str().unwrap(),
+            e.to_lowercase().as_str(),
             "txt" | "md" | "markdown" | "rst" | "adoc" | "org"
         ),
         None => false,
@@ -237,30 +4786,61 @@ fn file_is_text_document(f: &Path) -> bool {
 /// extension.
 const RFC_REGEX_PATTERN: &str = r"(?<rfc_number>\d{3,})";
 
-fn file_has_rfc_id(f: &Path) -> bool {
-    let re = Regex::new(RFC_REGEX_PATTERN).expect("Can't compile RFC regex");
+fn rfc_regex() -> &'static Regex {
+    static RFC_REGEX: OnceLock<Regex> = OnceLock::new();
+    RFC_REGEX.get_or_init(|| Regex::new(RFC_REGEX_PATTERN).expect("Can't compile RFC regex"))
+}
 
+fn file_has_rfc_id(f: &Path) -> bool {
     match f.file_name() {
         Some(name) => match name.to_str() {
-            Some(name) => re.is_match(name),
+            Some(name) => rfc_regex().is_match(name),
             None => false,
         },
         None => false,
     }
 }
 
-fn checkout_git_url_locally(target_location: PathBuf, url: String) -> Result<PathBuf> {
-    eprintln!("Cloning git repository from URL: '{}'", url);
+/// Clones `url` into `rfcs` in `target_location`, with system git run by
+/// `runner`, or with git2 if there's a `token`.
+fn checkout_git_url_locally(
+    runner: &dyn runner::Runner,
+    target_location: PathBuf,
+    url: String,
+    token: Option<String>,
+    clone_timeout: std::time::Duration,
+    deadline: platform::Deadline,
+    shallow: bool,
+) -> Result<PathBuf> {
+    let url = git::clone_source(&url, &std::env::current_dir()?);
+    eprintln!(
+        "Cloning git repository from URL: '{}'",
+        git::redact_url(&url)
+    );
 
     let mut repo = target_location.clone();
     repo.push("rfcs");
 
-    let command_result = Cmd::new("git")
+    // System git already knows about the SSH agent and credential helpers, so
+    // git2 (and its credential callbacks) are only needed to hand it a token.
+    // Tokens are no use over SSH, which git2 may be built without anyway.
+    if token.is_some() && (git::ssh_supported() || !git::is_ssh_url(&url)) {
+        git::clone_with_credentials(&url, &repo, token, deadline)?;
+        eprintln!(
+            "Successfully cloned git repository to path '{}'",
+            repo.display()
+        );
+        return Ok(repo);
+    }
+
+    let mut command = Cmd::new("git");
+    command
         .arg("clone")
+        .args(shallow.then_some("--depth=1"))
         .arg(&url)
         .arg("rfcs")
-        .current_dir(target_location)
-        .output();
+        .current_dir(target_location);
+    let command_result = runner.output(&mut command, deadline.bound(clone_timeout));
 
     match command_result {
         Ok(output) => match output.status.success() {
@@ -272,7 +4852,10 @@ fn checkout_git_url_locally(target_location: PathBuf, url: String) -> Result<Pat
                 Ok(repo)
             }
             false => {
-                eprintln!("Error while cloning repository from URL {}, ", url);
+                eprintln!(
+                    "Error while cloning repository from URL {}, ",
+                    git::redact_url(&url)
+                );
                 eprintln!(
                     "output: \n\nStderr: {}\nStdout:{}",
                     String::from_utf8(output.stderr).unwrap(),
@@ -281,6 +4864,23 @@ fn checkout_git_url_locally(target_location: PathBuf, url: String) -> Result<Pat
                 bail!("Can't proceed any further without a repository present.")
             }
         },
+        Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+            // Whatever the clone got to is of no use.
+            let _ = fs::remove_dir_all(&repo);
+            if let Some(total) = deadline.total().filter(|_| deadline.expired()) {
+                bail!(
+                    "Cloning {} was stopped when --timeout ran out after {}.",
+                    git::redact_url(&url),
+                    platform::seconds(total)
+                )
+            }
+            bail!(
+                "Cloning {} took longer than {} and was stopped. If the \
+                 repository is just big, raise git.clone_timeout_secs.",
+                git::redact_url(&url),
+                platform::seconds(clone_timeout)
+            )
+        }
         Err(e) => {
             eprintln!("Error while trying to clone git repository: {}", e);
             bail!("Can't proceed any further without a repository present.")
@@ -288,44 +4888,9 @@ fn checkout_git_url_locally(target_location: PathBuf, url: String) -> Result<Pat
     }
 }
 
-fn config_path() -> PathBuf {
-    let home = std::env::var("HOME").expect("$HOME isn't set, can't proceed.");
-
-    [&home, ".config", "rfcs", "config.toml"].iter().collect()
-}
-
-fn default_config() -> Config {
-    Config { git: None }
-}
-
-fn load_config() -> Result<Config> {
-    match fs::read_to_string(config_path()) {
-        Ok(content) => Ok(toml::from_str(&content)?),
-        Err(e) => match e.kind() {
-            std::io::ErrorKind::NotFound => {
-                let config = default_config();
-                write_config(config.clone())?;
-                Ok(config)
-            }
-            _ => {
-                let context = format!(
-                    "Unexpected error when reading config file from {}",
-                    config_path().display()
-                );
-                Err(anyhow::Error::new(e).context(context))
-            }
-        },
-    }
-}
-
-fn write_config(config: Config) -> Result<()> {
-    let p = config_path();
-    fs::create_dir_all(p.parent().expect("Config path must have parent"))?;
-
-    Ok(fs::write(p, toml::to_string(&config)?)?)
-}
-
 #[cfg(test)]
+// The first tests list their paths in `vec!`s.
+#[allow(clippy::useless_vec)]
 mod test {
     use super::*;
 
@@ -358,4 +4923,550 @@ mod test {
             .iter()
             .for_each(|f| assert!(!(file_has_rfc_id(f) && file_is_text_document(f))));
     }
+
+    #[test]
+    fn test_number_in_range_is_inclusive() {
+        let file = Path::new("./042-answers.md");
+        assert!(number_in_range(file, &Scan::default(), None, None));
+        assert!(number_in_range(file, &Scan::default(), Some(42), Some(42)));
+        assert!(number_in_range(file, &Scan::default(), Some(1), None));
+        assert!(!number_in_range(file, &Scan::default(), Some(43), None));
+        assert!(!number_in_range(file, &Scan::default(), None, Some(41)));
+    }
+
+    #[test]
+    fn test_text_document_extensions_ignore_case() {
+        for name in ["002-PROPOSAL.MD", "003-a.Md", "004-b.TXT", "005-c.md"] {
+            assert!(file_is_text_document(Path::new(name)), "{}", name);
+        }
+        for name in ["006-schema.SQL", "007-noext", "008-c.md.bak"] {
+            assert!(!file_is_text_document(Path::new(name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_every_command_has_examples() {
+        use clap::CommandFactory;
+        for command in Args::command().get_subcommands() {
+            let help = command.get_after_help().map(|h| h.to_string());
+            let example = format!("  rfcs {}", command.get_name());
+            assert!(
+                help.is_some_and(|h| h.starts_with("Examples:\n") && h.contains(&example)),
+                "{} has no examples",
+                command.get_name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_error() {
+        let e = anyhow::anyhow!("version must be a number").context("Failed to parse \"config\"");
+        assert_eq!(
+            format_error(&e, ErrorFormat::Text),
+            "Error: Failed to parse \"config\"\n\nCaused by:\n    version must be a number"
+        );
+        let outer = anyhow::anyhow!("version must be a number")
+            .context("Failed to parse \"config\"")
+            .context("Failed to load the config");
+        assert_eq!(
+            format_error(&outer, ErrorFormat::Text),
+            "Error: Failed to load the config\n\nCaused by:\n    \
+             0: Failed to parse \"config\"\n    \
+             1: version must be a number"
+        );
+        assert_eq!(
+            format_error(&anyhow::anyhow!("No RFC 042"), ErrorFormat::Text),
+            "Error: No RFC 042"
+        );
+        assert_eq!(
+            format_error(&e, ErrorFormat::Json),
+            r#"{"causes":["version must be a number"],"code":1,"message":"Failed to parse \"config\""}"#
+        );
+    }
+
+    #[test]
+    fn test_clone_runs_git_clone() {
+        let dir = test_util::temp_dir("clone");
+        for (shallow, expected) in [
+            (
+                false,
+                &["git", "clone", "https://example.com/rfcs.git", "rfcs"][..],
+            ),
+            (
+                true,
+                &[
+                    "git",
+                    "clone",
+                    "--depth=1",
+                    "https://example.com/rfcs.git",
+                    "rfcs",
+                ][..],
+            ),
+        ] {
+            let mock = runner::Mock::default();
+            let repo = checkout_git_url_locally(
+                &mock,
+                dir.clone(),
+                String::from("https://example.com/rfcs.git"),
+                None,
+                std::time::Duration::from_secs(300),
+                platform::Deadline::default(),
+                shallow,
+            )
+            .unwrap();
+            assert_eq!(repo, dir.join("rfcs"));
+            assert_eq!(
+                mock.calls.into_inner(),
+                [runner::Call {
+                    words: expected.iter().map(|w| w.to_string()).collect(),
+                    cwd: Some(dir.clone()),
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_editor_gets_the_file() {
+        let mock = runner::Mock::default();
+        let command = [String::from("code"), String::from("--wait")];
+        run_editor(&mock, &command, Path::new("001-first.md")).unwrap();
+        assert_eq!(
+            mock.calls.into_inner(),
+            [runner::Call {
+                words: vec![
+                    String::from("code"),
+                    String::from("--wait"),
+                    String::from("001-first.md")
+                ],
+                cwd: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_copy_lossy() {
+        // A tiny reader, so that characters get cut off between reads.
+        struct Trickle<'a>(&'a [u8]);
+        impl std::io::Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = buf.len().min(self.0.len()).min(2);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+        for (input, expected) in [
+            (&b"caf\xc3\xa9 \xe2\x9c\x93"[..], Some("café ✓")),
+            (&b"a\xffb\xc3"[..], None),
+        ] {
+            let mut output = vec![];
+            let replaced = copy_lossy(Trickle(input), &mut output).unwrap();
+            let lossy = String::from_utf8_lossy(input);
+            assert_eq!(String::from_utf8(output).unwrap(), lossy);
+            assert_eq!(replaced, expected.is_none());
+            if let Some(expected) = expected {
+                assert_eq!(lossy, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_yes() {
+        for answer in ["y\n", "Y", " yes \r\n", "YES"] {
+            assert!(is_yes(answer), "{:?}", answer);
+        }
+        for answer in ["", "\n", "n", "no", "yep", "y y"] {
+            assert!(!is_yes(answer), "{:?}", answer);
+        }
+    }
+
+    #[test]
+    fn test_target_parsing() {
+        assert_eq!("042".parse(), Ok(Target::Number(42)));
+        assert_eq!(
+            "042-answers.md".parse(),
+            Ok(Target::Path(PathBuf::from("042-answers.md")))
+        );
+        assert_eq!("+42".parse(), Ok(Target::Path(PathBuf::from("+42"))));
+    }
+
+    #[test]
+    fn test_target_set_parsing() {
+        assert_eq!("all".parse(), Ok(TargetSet::All));
+        assert_eq!("10-12".parse(), Ok(TargetSet::Range(10, 12)));
+        assert_eq!("3".parse(), Ok(TargetSet::One(Target::Number(3))));
+        assert_eq!(
+            "042-answers.md".parse(),
+            Ok(TargetSet::One(Target::Path(PathBuf::from(
+                "042-answers.md"
+            ))))
+        );
+        assert!("12-10".parse::<TargetSet>().is_err());
+    }
+
+    #[test]
+    fn test_target_sets_expand_to_each_rfc_once() {
+        let repo = test_util::temp_dir("repo");
+        for name in [
+            "003-c.md", "005-e.md", "010-j.md", "011-k.md", "012-l.md", "013-m.md",
+        ] {
+            fs::write(repo.join(name), "").unwrap();
+        }
+        let files = files_in_rfc_repo(&repo, &Scan::default()).unwrap();
+        let parse = |args: &str| -> Vec<TargetSet> {
+            args.split(' ').map(|a| a.parse().unwrap()).collect()
+        };
+        let names = |sets: &[TargetSet]| -> Vec<String> {
+            TargetSet::expand(sets, &repo, &files, &Scan::default(), false)
+                .unwrap()
+                .iter()
+                .map(|f| f.file_name().unwrap().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(
+            names(&parse("3 5 10-12")),
+            ["003-c.md", "005-e.md", "010-j.md", "011-k.md", "012-l.md"]
+        );
+        assert_eq!(
+            names(&parse("3 5 10-12 all")),
+            ["003-c.md", "005-e.md", "010-j.md", "011-k.md", "012-l.md", "013-m.md"]
+        );
+        assert!(TargetSet::expand(&parse("3 4"), &repo, &files, &Scan::default(), false).is_err());
+        assert!(
+            TargetSet::expand(&parse("20-30"), &repo, &files, &Scan::default(), false).is_err()
+        );
+    }
+
+    #[test]
+    fn test_slug() {
+        let keep = SlugCase::Keep;
+        assert_eq!(slug("Caches, revisited?", 60, keep), "Caches-revisited");
+        assert_eq!(slug("Caches are bad actually", 15, keep), "Caches-are-bad");
+        assert_eq!(slug("Caches are bad actually", 14, keep), "Caches-are-bad");
+        assert_eq!(slug("Caches are bad actually", 13, keep), "Caches-are");
+        assert_eq!(slug("Supercalifragilistic", 5, keep), "Super");
+        assert_eq!(slug("Ünïcödé wörds", 9, keep), "Ünïcödé");
+    }
+
+    #[test]
+    fn test_slug_case() {
+        let title = "Caches (v2), Revisited";
+        assert_eq!(slug(title, 60, SlugCase::Keep), "Caches-(v2)-Revisited");
+        assert_eq!(slug(title, 60, SlugCase::Lower), "caches-(v2)-revisited");
+        assert_eq!(slug(title, 60, SlugCase::Kebab), "caches-v2-revisited");
+        assert_eq!(slug("Ünïcödé Wörds", 9, SlugCase::Kebab), "ünïcödé");
+    }
+
+    #[test]
+    fn test_number_and_path_targets_resolve_to_same_rfc() {
+        let repo = test_util::temp_dir("repo");
+        fs::create_dir_all(repo.join("docs")).unwrap();
+        fs::write(repo.join("docs/042-answers.md"), "").unwrap();
+        fs::write(repo.join("043-questions.md"), "").unwrap();
+        let files = files_in_rfc_repo(&repo, &Scan::default()).unwrap();
+
+        let by_number = Target::Number(42)
+            .resolve(&repo, &files, &Scan::default())
+            .unwrap();
+        let by_path = Target::Path(PathBuf::from("docs/042-answers.md"))
+            .resolve(&repo, &files, &Scan::default())
+            .unwrap();
+
+        assert_eq!(by_number, by_path);
+        assert!(Target::Number(44)
+            .resolve(&repo, &files, &Scan::default())
+            .is_err());
+        assert!(Target::Path(PathBuf::from("nope.md"))
+            .resolve(&repo, &files, &Scan::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_path_targets_ignore_case_if_the_repo_does() {
+        let repo_path = test_util::temp_dir("repo");
+        let repo = git2::Repository::init(&repo_path).unwrap();
+        fs::write(repo_path.join("042-answers.md"), "").unwrap();
+        let files = files_in_rfc_repo(&repo_path, &Scan::default()).unwrap();
+        let target = Target::Path(PathBuf::from("042-ANSWERS.md"));
+
+        repo.config()
+            .unwrap()
+            .set_bool("core.ignorecase", true)
+            .unwrap();
+        assert_eq!(
+            target
+                .resolve(&repo_path, &files, &Scan::default())
+                .unwrap(),
+            repo_path.join("042-answers.md")
+        );
+
+        repo.config()
+            .unwrap()
+            .set_bool("core.ignorecase", false)
+            .unwrap();
+        #[cfg(target_os = "linux")]
+        assert!(target
+            .resolve(&repo_path, &files, &Scan::default())
+            .is_err());
+    }
+
+    fn create_args(args: &[&str]) -> CreateArgs {
+        let args = Args::try_parse_from([&["rfcs", "create"][..], args].concat()).unwrap();
+        match args.command {
+            Command::Create(create_args) => create_args,
+            command => panic!("{:?} isn't create", command),
+        }
+    }
+
+    /// A repository on `main` with RFC 001 committed, and a config that
+    /// points at it.
+    fn repo_with_first_rfc() -> (PathBuf, Config) {
+        let path = test_util::temp_dir("repo");
+        let repo = git2::Repository::init_opts(
+            &path,
+            git2::RepositoryInitOptions::new().initial_head("main"),
+        )
+        .unwrap();
+        let mut git_config = repo.config().unwrap();
+        git_config.set_str("user.name", "Test Author").unwrap();
+        git_config
+            .set_str("user.email", "test@example.com")
+            .unwrap();
+        fs::write(path.join("001-first.md"), "# First\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("001-first.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "First", &tree, &[])
+            .unwrap();
+
+        let config = Config {
+            repo: Some(Repo {
+                path: Some(path.clone()),
+            }),
+            ..config::default_config()
+        };
+        (path, config)
+    }
+
+    #[test]
+    fn test_preferred_formats() {
+        let files = |names: &[&str]| -> Vec<PathBuf> { names.iter().map(PathBuf::from).collect() };
+        let extensions = [String::from("md"), String::from("org")];
+        assert_eq!(
+            preferred_formats(
+                files(&["rfcs/042-foo.org", "rfcs/042-foo.MD", "rfcs/043-bar.org"]),
+                &extensions
+            ),
+            files(&["rfcs/042-foo.MD", "rfcs/043-bar.org"])
+        );
+        assert_eq!(
+            preferred_formats(files(&["042-foo.txt", "042-foo.org"]), &extensions),
+            files(&["042-foo.org"])
+        );
+        assert_eq!(
+            preferred_formats(
+                files(&["042-foo.txt", "042-foo.rst", "a/042-foo.md"]),
+                &extensions
+            ),
+            files(&["042-foo.txt", "042-foo.rst", "a/042-foo.md"])
+        );
+    }
+
+    #[test]
+    fn test_path_from() {
+        let path_from = |base: &str, target: &str| path_from(Path::new(base), Path::new(target));
+        assert_eq!(
+            path_from("/repo/docs", "/repo/rfcs/001-caches.md"),
+            Some(PathBuf::from("../rfcs/001-caches.md"))
+        );
+        assert_eq!(
+            path_from("/repo", "/repo/001-caches.md"),
+            Some(PathBuf::from("001-caches.md"))
+        );
+        assert_eq!(
+            path_from("/elsewhere/a/b", "/repo/001-caches.md"),
+            Some(PathBuf::from("../../../repo/001-caches.md"))
+        );
+        assert_eq!(path_from("relative", "/repo/001-caches.md"), None);
+    }
+
+    #[test]
+    fn test_copied_rfc() {
+        assert_eq!(
+            copied_rfc(
+                "---\ntitle: Caches\nnumber: 4\n---\n# Caches\n\nWhy.\n# Caches, too\n",
+                "Caches v2"
+            ),
+            "---\ntitle: Caches v2\nstatus: draft\n---\n# Caches v2\n\nWhy.\n# Caches, too\n"
+        );
+        assert_eq!(
+            copied_rfc("No heading.\n", "Caches v2"),
+            "---\ntitle: Caches v2\nstatus: draft\n---\nNo heading.\n"
+        );
+    }
+
+    #[test]
+    fn test_is_by() {
+        let ada = (String::from("Ada"), String::from("ada@example.com"));
+        let bob = (String::from("Bob"), String::from("bob@example.com"));
+        let by = |content: &str, first_author| {
+            is_by(
+                &metadata::parse(content),
+                first_author,
+                "Ada",
+                "ada@example.com",
+            )
+        };
+        assert!(by(
+            "---
+author: ada
+---
+",
+            Some(&bob)
+        ));
+        assert!(by(
+            "---
+author: [Bob, Ada <ADA@example.com>]
+---
+",
+            None
+        ));
+        assert!(!by(
+            "---
+author: Bob
+---
+",
+            Some(&ada)
+        ));
+        assert!(by(
+            "# No front matter
+",
+            Some(&ada)
+        ));
+        assert!(!by(
+            "# No front matter
+",
+            Some(&bob)
+        ));
+        assert!(!by(
+            "# Not committed
+",
+            None
+        ));
+    }
+
+    #[test]
+    fn test_create_rfc_returns_what_it_created() {
+        let (path, config) = repo_with_first_rfc();
+        let globals = Args::try_parse_from(["rfcs", "--quiet", "list"])
+            .unwrap()
+            .globals;
+
+        let created = create_rfc(&globals, config.clone(), &create_args(&["Caches"])).unwrap();
+        assert_eq!(
+            created,
+            CreatedRfc {
+                number: 2,
+                branch: String::from("002-Caches"),
+                path: Some(path.join("002-Caches.md")),
+                committed: true,
+            }
+        );
+
+        let args = create_args(&["--reuse", "Caches"]);
+        let created = create_rfc(&globals, config.clone(), &args).unwrap();
+        assert_eq!(
+            created,
+            CreatedRfc {
+                number: 2,
+                branch: String::from("002-Caches"),
+                path: None,
+                committed: false,
+            }
+        );
+
+        let args = create_args(&["--number", "7", "--no-checkout", "Queues"]);
+        let created = create_rfc(&globals, config.clone(), &args).unwrap();
+        assert_eq!(created.number, 7);
+        assert_eq!(created.branch, "007-Queues");
+        assert!(created.committed);
+
+        let args = create_args(&["--no-commit", "Retries"]);
+        let created = create_rfc(&globals, config, &args).unwrap();
+        assert_eq!(created.number, 8);
+        assert_eq!(created.path, Some(path.join("008-Retries.md")));
+        assert!(!created.committed);
+    }
+
+    #[test]
+    fn test_new_rfc_asks_for_what_is_missing() {
+        let args = create_args(&["--interactive", "--label", "api"]);
+        let transitions = lifecycle::default_transitions();
+        let mut output = vec![];
+        let mut prompter = prompt::Prompter::new(
+            &b"Caches, revisited\nfinished\nin-review\n\n"[..],
+            &mut output,
+        );
+
+        let new = new_rfc(
+            &args,
+            &transitions,
+            Some(String::from("Ada")),
+            &mut prompter,
+        )
+        .unwrap();
+
+        assert_eq!(
+            new,
+            NewRfc {
+                title: String::from("Caches, revisited"),
+                status: Some(String::from("in-review")),
+                author: Some(String::from("Ada")),
+                labels: vec![String::from("api")],
+            }
+        );
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("'finished' is not a known status"),
+            "{}",
+            output
+        );
+        assert!(output.ends_with("Author [Ada]: "), "{}", output);
+
+        let content = new.front_matter(rfc_stub(&new.title));
+        let front_matter = metadata::parse(&content);
+        assert_eq!(front_matter.get("status"), Some("in-review"));
+        assert_eq!(front_matter.get("author"), Some("Ada"));
+        assert_eq!(front_matter.list("labels"), ["api"]);
+    }
+
+    #[test]
+    fn test_new_rfc_without_interactive_asks_nothing() {
+        let args = create_args(&["--author", "Ada", "Caches"]);
+        let mut prompter = prompt::Prompter::new(&b""[..], vec![]);
+
+        let new = new_rfc(
+            &args,
+            &lifecycle::default_transitions(),
+            None,
+            &mut prompter,
+        )
+        .unwrap();
+
+        assert_eq!(new.title, "Caches");
+        assert_eq!(new.status, None);
+        assert_eq!(new.author.as_deref(), Some("Ada"));
+
+        let args = create_args(&["--status", "finished", "Caches"]);
+        assert!(new_rfc(
+            &args,
+            &lifecycle::default_transitions(),
+            None,
+            &mut prompter
+        )
+        .is_err());
+    }
 }
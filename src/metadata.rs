@@ -0,0 +1,381 @@
+//! Front matter: a block of `key: value` lines at the very top of an RFC,
+//! between two `---` lines, like so:
+//!
+//! ```text
+//! ---
+//! title: Caches are bad, actually
+//! status: draft
+//! ---
+//! ```
+//!
+//! Hugo and Zola style TOML front matter works too, with `key = value` lines
+//! between two `+++` lines. Either way, this reads just enough of the format
+//! for the flat fields RFCs use.
+
+use std::io::{self, BufRead};
+
+/// The kinds of front matter block, told apart by their delimiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// `key: value` lines between `---` lines.
+    Yaml,
+    /// `key = value` lines between `+++` lines.
+    Toml,
+}
+
+impl Style {
+    /// The style of the block that `line` opens, if it opens one.
+    fn opened_by(line: &str) -> Option<Style> {
+        match line.trim_end() {
+            "---" => Some(Style::Yaml),
+            "+++" => Some(Style::Toml),
+            _ => None,
+        }
+    }
+
+    fn delimiter(self) -> &'static str {
+        match self {
+            Style::Yaml => "---",
+            Style::Toml => "+++",
+        }
+    }
+
+    fn is_delimiter(self, line: &str) -> bool {
+        line.trim_end() == self.delimiter()
+    }
+
+    fn parse_field(self, line: &str) -> Option<(String, String)> {
+        let separator = match self {
+            Style::Yaml => ':',
+            Style::Toml => '=',
+        };
+        let (key, value) = line.split_once(separator)?;
+        let key = key.trim();
+        if key.is_empty() || key.starts_with('#') || key.contains(char::is_whitespace) {
+            return None;
+        }
+        Some((key.to_string(), unquote(value.trim()).to_string()))
+    }
+
+    /// The line setting `key` to `value`. TOML wants strings quoted, and a
+    /// list like `[api, infra]` as an array of strings.
+    fn field_line(self, key: &str, value: &str) -> String {
+        match self {
+            Style::Yaml => format!("{}: {}", key, value),
+            Style::Toml => {
+                let value = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    Some(items) => toml::Value::Array(
+                        items
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|item| !item.is_empty())
+                            .map(|item| toml::Value::String(item.to_string()))
+                            .collect(),
+                    ),
+                    None => toml::Value::String(value.to_string()),
+                };
+                format!("{} = {}", key, value)
+            }
+        }
+    }
+}
+
+/// The fields of a front matter block, in the order they appear in the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+    pub fields: Vec<(String, String)>,
+}
+
+impl FrontMatter {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The items of `key`, a list like `[api, infra]`, or without the
+    /// brackets. Quotes around items are dropped.
+    pub fn list(&self, key: &str) -> Vec<&str> {
+        let Some(value) = self.get(key) else {
+            return vec![];
+        };
+        let value = value.trim();
+        let value = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .unwrap_or(value);
+        value
+            .split(',')
+            .map(|item| item.trim().trim_matches(['"', '\'']).trim())
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+}
+
+/// Parses the front matter of `content`. Files without front matter have no
+/// fields.
+pub fn parse(content: &str) -> FrontMatter {
+    let fields = match block_lines(content) {
+        Some((style, lines)) => lines
+            .filter_map(|(_, line)| style.parse_field(line))
+            .collect(),
+        None => vec![],
+    };
+    FrontMatter { fields }
+}
+
+/// Whether `content` starts with a front matter block, even an empty one.
+pub fn has_front_matter(content: &str) -> bool {
+    block_lines(content).is_some()
+}
+
+/// Like `parse`, but reads only as far into `reader` as the front matter goes,
+/// so that the rest of a large file is never read.
+pub fn read(reader: impl BufRead) -> io::Result<FrontMatter> {
+    let mut lines = reader.lines();
+    let Some(style) = lines
+        .next()
+        .transpose()?
+        .and_then(|first| Style::opened_by(&first))
+    else {
+        return Ok(FrontMatter::default());
+    };
+    let mut fields = vec![];
+    for line in lines {
+        let line = line?;
+        if style.is_delimiter(&line) {
+            return Ok(FrontMatter { fields });
+        }
+        fields.extend(style.parse_field(line.trim_end_matches('\r')));
+    }
+    // An opening delimiter without a closing one is just a horizontal rule.
+    Ok(FrontMatter::default())
+}
+
+/// Sets `key` to `value` in the front matter of `content`, replacing the
+/// existing line for `key` or appending one to the block. A file without
+/// front matter gets a new `---` block holding just that field. Everything
+/// else in the file is left untouched.
+pub fn set_field(content: &str, key: &str, value: &str) -> String {
+    let Some((style, lines)) = block_lines(content) else {
+        let delimiter = Style::Yaml.delimiter();
+        let line = Style::Yaml.field_line(key, value);
+        return format!("{}\n{}\n{}\n{}", delimiter, line, delimiter, content);
+    };
+    let line = style.field_line(key, value);
+
+    let mut closing = None;
+    for (offset, existing) in lines {
+        if style.parse_field(existing).is_some_and(|(k, _)| k == key) {
+            let end = offset + existing.len();
+            return format!("{}{}{}", &content[..offset], line, &content[end..]);
+        }
+        closing = Some(offset + existing.len());
+    }
+
+    // Append after the last field, or straight after the opening delimiter
+    // for an empty block.
+    let opening = style.delimiter().len();
+    let insert_at = match closing {
+        Some(end) => end + line_ending_len(&content[end..]),
+        None => opening + line_ending_len(&content[opening..]),
+    };
+    format!(
+        "{}{}\n{}",
+        &content[..insert_at],
+        line,
+        &content[insert_at..]
+    )
+}
+
+/// `content` without its front matter block, if it has one.
+pub fn body(content: &str) -> &str {
+    let Some((style, _)) = block_lines(content) else {
+        return content;
+    };
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        offset += line.len();
+        if offset > line.len() && style.is_delimiter(line) {
+            break;
+        }
+    }
+    &content[offset..]
+}
+
+/// The style of the front matter block and the lines between its delimiters,
+/// with the byte offset each starts at, or `None` if `content` doesn't start
+/// with a front matter block.
+fn block_lines(content: &str) -> Option<(Style, impl Iterator<Item = (usize, &str)>)> {
+    let style = Style::opened_by(content.lines().next()?)?;
+
+    let mut offset = 0;
+    let mut lines = vec![];
+    for line in content.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        if offset > 0 && style.is_delimiter(text) {
+            return Some((style, lines.into_iter()));
+        }
+        if offset > 0 {
+            lines.push((offset, text));
+        }
+        offset += line.len();
+    }
+
+    // An opening delimiter without a closing one is just a horizontal rule.
+    None
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+fn line_ending_len(rest: &str) -> usize {
+    if rest.starts_with("\r\n") {
+        2
+    } else if rest.starts_with('\n') {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RFC: &str =
+        "---\ntitle: \"Caches: bad\"\nstatus: draft\n---\n# Caches\n\nstatus: not front matter\n";
+
+    #[test]
+    fn test_body() {
+        assert_eq!(body(RFC), "# Caches\n\nstatus: not front matter\n");
+        assert_eq!(body("# Caches\n"), "# Caches\n");
+        assert_eq!(body("---\n---\n"), "");
+        assert_eq!(body("---\nno closing\n"), "---\nno closing\n");
+    }
+
+    #[test]
+    fn test_parse_front_matter() {
+        let front_matter = parse(RFC);
+        assert_eq!(front_matter.get("title"), Some("Caches: bad"));
+        assert_eq!(front_matter.get("status"), Some("draft"));
+        assert_eq!(front_matter.fields.len(), 2);
+        assert_eq!(parse("# No front matter\n"), FrontMatter::default());
+        assert_eq!(parse("---\nunterminated: yes\n"), FrontMatter::default());
+    }
+
+    const TOML_RFC: &str = "+++\ntitle = \"Caches: bad\"\nstatus = 'draft'\n\
+                            labels = [\"api\", \"infra\"]\nnumber = 7\n+++\n# Caches\n";
+
+    #[test]
+    fn test_parse_toml_front_matter() {
+        let front_matter = parse(TOML_RFC);
+        assert_eq!(front_matter.get("title"), Some("Caches: bad"));
+        assert_eq!(front_matter.get("status"), Some("draft"));
+        assert_eq!(front_matter.get("number"), Some("7"));
+        assert_eq!(front_matter.list("labels"), ["api", "infra"]);
+        assert_eq!(read(TOML_RFC.as_bytes()).unwrap(), front_matter);
+        assert_eq!(body(TOML_RFC), "# Caches\n");
+
+        // Each block is only closed by its own delimiter, and read in its
+        // own style.
+        assert_eq!(parse("+++\ntitle = A\n---\n"), FrontMatter::default());
+        assert_eq!(parse("---\ntitle = A\n---\n"), FrontMatter::default());
+    }
+
+    #[test]
+    fn test_set_field_in_toml_front_matter() {
+        assert_eq!(
+            set_field(
+                "+++\ntitle = \"A\"\nstatus = \"draft\"\n+++\nBody\n",
+                "status",
+                "accepted"
+            ),
+            "+++\ntitle = \"A\"\nstatus = \"accepted\"\n+++\nBody\n"
+        );
+        assert_eq!(
+            set_field("+++\ntitle = \"A\"\n+++\nBody\n", "labels", "[api, infra]"),
+            "+++\ntitle = \"A\"\nlabels = [\"api\", \"infra\"]\n+++\nBody\n"
+        );
+        assert_eq!(
+            set_field("+++\n+++\nBody\n", "number", "007"),
+            "+++\nnumber = \"007\"\n+++\nBody\n"
+        );
+    }
+
+    /// Counts the bytes read through it.
+    struct Counting<'a> {
+        inner: &'a [u8],
+        read: usize,
+    }
+
+    impl io::Read for Counting<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.read += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_list() {
+        let front_matter =
+            parse("---\nlabels: [api, \"infra\" ]\ntags: process,  api\nempty: []\n---\n");
+        assert_eq!(front_matter.list("labels"), ["api", "infra"]);
+        assert_eq!(front_matter.list("tags"), ["process", "api"]);
+        assert!(front_matter.list("empty").is_empty());
+        assert!(front_matter.list("missing").is_empty());
+    }
+
+    #[test]
+    fn test_read_stops_after_front_matter() {
+        let large = format!("{}{}", RFC, "All work and no play.\n".repeat(100_000));
+        let mut counting = Counting {
+            inner: large.as_bytes(),
+            read: 0,
+        };
+        let front_matter = read(io::BufReader::with_capacity(64, &mut counting)).unwrap();
+        assert_eq!(front_matter, parse(RFC));
+        assert!(counting.read < 128, "read {} bytes", counting.read);
+
+        for content in [
+            "# No front matter\n",
+            "---\nunterminated: yes\n",
+            "---\r\na: b\r\n---\r\n",
+        ] {
+            assert_eq!(read(content.as_bytes()).unwrap(), parse(content));
+        }
+    }
+
+    #[test]
+    fn test_set_field_replaces_only_that_line() {
+        let updated = set_field(RFC, "status", "accepted");
+        assert_eq!(
+            updated,
+            "---\ntitle: \"Caches: bad\"\nstatus: accepted\n---\n# Caches\n\nstatus: not front matter\n"
+        );
+    }
+
+    #[test]
+    fn test_set_field_appends_or_creates_block() {
+        assert_eq!(
+            set_field("---\ntitle: A\n---\nBody\n", "status", "draft"),
+            "---\ntitle: A\nstatus: draft\n---\nBody\n"
+        );
+        assert_eq!(
+            set_field("---\n---\nBody\n", "status", "draft"),
+            "---\nstatus: draft\n---\nBody\n"
+        );
+        assert_eq!(
+            set_field("Body\n", "status", "draft"),
+            "---\nstatus: draft\n---\nBody\n"
+        );
+    }
+}
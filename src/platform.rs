@@ -0,0 +1,412 @@
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The directory that per-user configuration lives under: `$HOME/.config` on
+/// Unix-likes, `%APPDATA%` on Windows. `env` is the environment lookup, taken
+/// as a parameter so the resolution can be tested without touching the real
+/// process environment.
+#[cfg(unix)]
+pub fn config_home(env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    env("HOME").map(|home| [&home, ".config"].iter().collect())
+}
+
+#[cfg(windows)]
+pub fn config_home(env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    env("APPDATA").map(PathBuf::from)
+}
+
+/// Looks up a variable in the actual process environment, for use with the
+/// `env` parameters above.
+pub fn process_env(key: &str) -> Option<String> {
+    std::env::var(key).ok()
+}
+
+/// The width of the terminal output goes to, if `is_terminal`, going by
+/// `$COLUMNS`. `None` if that isn't set to a width.
+pub fn terminal_width(is_terminal: bool, env: impl Fn(&str) -> Option<String>) -> Option<usize> {
+    if !is_terminal {
+        return None;
+    }
+    env("COLUMNS")?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&width| width > 0)
+}
+
+/// Like `Command::output`, but kills the process if it's still running after
+/// `timeout`, returning an error of kind `TimedOut`.
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Drained on their own threads, a child filling up a pipe would otherwise
+    // block forever.
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut buffer = vec![];
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as _));
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("still running after {}", seconds(timeout)),
+            ));
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// `duration` in whole seconds, for messages: `1 second`, `30 seconds`.
+pub fn seconds(duration: Duration) -> String {
+    match duration.as_secs() {
+        1 => String::from("1 second"),
+        n => format!("{} seconds", n),
+    }
+}
+
+/// Whether a process with id `pid` is running.
+#[cfg(target_os = "linux")]
+pub fn process_running(pid: u32) -> bool {
+    std::path::Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn process_running(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(windows)]
+pub fn process_running(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+}
+
+/// When all network operations of a run have to be done by, see `--timeout`.
+/// Without one, there's no limit beyond each operation's own timeout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Deadline(Option<(Instant, Duration)>);
+
+impl Deadline {
+    /// A deadline `total` from now.
+    pub fn after(total: Duration) -> Deadline {
+        Deadline(Instant::now().checked_add(total).map(|at| (at, total)))
+    }
+
+    /// How long is left, which is forever without a deadline.
+    pub fn remaining(self) -> Duration {
+        match self.0 {
+            Some((at, _)) => at.saturating_duration_since(Instant::now()),
+            None => Duration::MAX,
+        }
+    }
+
+    /// `timeout`, or less if the deadline comes first.
+    pub fn bound(self, timeout: Duration) -> Duration {
+        timeout.min(self.remaining())
+    }
+
+    pub fn expired(self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// The total time given with `--timeout`, if any.
+    pub fn total(self) -> Option<Duration> {
+        self.0.map(|(_, total)| total)
+    }
+}
+
+/// The editor to open files with, as program and arguments: `flag` or else
+/// `configured` if given, otherwise `$VISUAL`, `$EDITOR`, and finally a
+/// platform default.
+pub fn editor_command(
+    flag: Option<&str>,
+    configured: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    [
+        flag.map(String::from),
+        configured.map(String::from),
+        env("VISUAL"),
+        env("EDITOR"),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|command| split_command(&command))
+    .find(|words| !words.is_empty())
+    .unwrap_or_else(|| vec![String::from(DEFAULT_EDITOR)])
+}
+
+#[cfg(unix)]
+const DEFAULT_EDITOR: &str = "vi";
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+
+/// The program to open web pages with, as program and arguments:
+/// `$BROWSER`, or else whatever the platform opens URLs with.
+pub fn browser_command(env: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    env("BROWSER")
+        .map(|command| split_command(&command))
+        .filter(|words| !words.is_empty())
+        .unwrap_or_else(|| DEFAULT_BROWSER.iter().map(|w| w.to_string()).collect())
+}
+
+#[cfg(target_os = "macos")]
+const DEFAULT_BROWSER: &[&str] = &["open"];
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const DEFAULT_BROWSER: &[&str] = &["xdg-open"];
+
+// `start` is built into `cmd`, see `launcher`. Its first quoted argument is
+// the window title, hence the empty one.
+#[cfg(windows)]
+const DEFAULT_BROWSER: &[&str] = &["start", ""];
+
+/// A `Command` running `words`, a program and its arguments as from
+/// `editor_command` or `browser_command`. On Windows, it runs through
+/// `cmd /C`, which also finds programs that are `.cmd` or `.bat` scripts, like
+/// `code`, and knows `start`.
+#[cfg(unix)]
+pub fn launcher(words: &[String]) -> Command {
+    let mut command = Command::new(&words[0]);
+    command.args(&words[1..]);
+    command
+}
+
+#[cfg(windows)]
+pub fn launcher(words: &[String]) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").args(words);
+    command
+}
+
+/// Splits a command line into words at whitespace, the way a shell would for
+/// simple cases: single and double quotes group words, and a backslash
+/// outside single quotes escapes the next character.
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let word = word.get_or_insert_with(String::new);
+                word.extend(chars.next());
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// `words` as a command line to paste into a shell: words with anything but
+/// letters, digits and `-_./:@=,+%~` in them are single-quoted.
+pub fn shell_command(words: &[&str]) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./:@=,+%~".contains(c);
+    let quoted: Vec<String> = words
+        .iter()
+        .map(|word| match !word.is_empty() && word.chars().all(safe) {
+            true => word.to_string(),
+            false => format!("'{}'", word.replace('\'', r"'\''")),
+        })
+        .collect();
+    quoted.join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_seconds() {
+        assert_eq!(seconds(Duration::from_secs(1)), "1 second");
+        assert_eq!(seconds(Duration::from_millis(30_500)), "30 seconds");
+        assert_eq!(seconds(Duration::ZERO), "0 seconds");
+    }
+
+    #[test]
+    fn test_output_with_timeout() {
+        let output =
+            output_with_timeout(Command::new("echo").arg("hi"), Duration::from_secs(5)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hi\n");
+
+        let started = Instant::now();
+        let err = output_with_timeout(Command::new("sleep").arg("10"), Duration::from_millis(100))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_terminal_width() {
+        let columns = |value: &'static str| move |k: &str| (k == "COLUMNS").then(|| value.into());
+        assert_eq!(terminal_width(true, columns("120")), Some(120));
+        assert_eq!(terminal_width(false, columns("120")), None);
+        assert_eq!(terminal_width(true, columns("0")), None);
+        assert_eq!(terminal_width(true, columns("wide")), None);
+        assert_eq!(terminal_width(true, |_: &str| None), None);
+    }
+
+    #[test]
+    fn test_shell_command() {
+        assert_eq!(
+            shell_command(&["git", "clone", "https://example.com/rfcs.git", "/tmp/rfcs"]),
+            "git clone https://example.com/rfcs.git /tmp/rfcs"
+        );
+        assert_eq!(
+            shell_command(&[
+                "git",
+                "clone",
+                "https://example.com/rfcs?a&b",
+                "/my rfcs/it's",
+                ""
+            ]),
+            r"git clone 'https://example.com/rfcs?a&b' '/my rfcs/it'\''s' ''"
+        );
+    }
+
+    #[test]
+    fn test_deadline() {
+        let unbounded = Deadline::default();
+        assert!(!unbounded.expired());
+        assert_eq!(unbounded.total(), None);
+        assert_eq!(
+            unbounded.bound(Duration::from_secs(300)),
+            Duration::from_secs(300)
+        );
+
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert_eq!(deadline.total(), Some(Duration::from_secs(60)));
+        assert!(deadline.bound(Duration::from_secs(300)) <= Duration::from_secs(60));
+        assert_eq!(
+            deadline.bound(Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+
+        assert!(Deadline::after(Duration::ZERO).expired());
+    }
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("code --wait"), ["code", "--wait"]);
+        assert_eq!(split_command("  vim  "), ["vim"]);
+        assert_eq!(
+            split_command(r#"'/opt/My Editor/edit' -n "a b" c\ d ''"#),
+            ["/opt/My Editor/edit", "-n", "a b", "c d", ""]
+        );
+        assert!(split_command(" ").is_empty());
+    }
+
+    #[test]
+    fn test_editor_command_precedence() {
+        let env = |k: &str| match k {
+            "VISUAL" => Some(String::from("visual")),
+            "EDITOR" => Some(String::from("editor")),
+            _ => None,
+        };
+        assert_eq!(
+            editor_command(Some("flag -x"), Some("configured"), env),
+            ["flag", "-x"]
+        );
+        assert_eq!(
+            editor_command(None, Some("configured"), env),
+            ["configured"]
+        );
+        assert_eq!(editor_command(None, None, env), ["visual"]);
+        let env = |k: &str| (k == "EDITOR").then(|| String::from("nano"));
+        assert_eq!(editor_command(None, None, env), ["nano"]);
+        assert_eq!(editor_command(None, None, |_| None), [DEFAULT_EDITOR]);
+    }
+
+    #[test]
+    fn test_browser_command() {
+        let env = |k: &str| (k == "BROWSER").then(|| String::from("firefox --new-tab"));
+        assert_eq!(browser_command(env), ["firefox", "--new-tab"]);
+        assert_eq!(browser_command(|_| None), DEFAULT_BROWSER);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launcher_unix() {
+        let command = launcher(&split_command("code --wait"));
+        assert_eq!(command.get_program(), "code");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["--wait"]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_launcher_windows() {
+        let command = launcher(&split_command("code --wait"));
+        assert_eq!(command.get_program(), "cmd");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["/C", "code", "--wait"]
+        );
+        let words = browser_command(|_| None);
+        let args: Vec<_> = launcher(&words).get_args().map(|a| a.to_owned()).collect();
+        assert_eq!(args, ["/C", "start", ""]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_config_home_unix() {
+        let env = |k: &str| (k == "HOME").then(|| String::from("/home/rfc"));
+        assert_eq!(config_home(env), Some(PathBuf::from("/home/rfc/.config")));
+        assert_eq!(config_home(|_| None), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_config_home_windows() {
+        let env = |k: &str| (k == "APPDATA").then(|| String::from(r"C:\Users\rfc\AppData\Roaming"));
+        assert_eq!(
+            config_home(env),
+            Some(PathBuf::from(r"C:\Users\rfc\AppData\Roaming"))
+        );
+        assert_eq!(config_home(|_| None), None);
+    }
+}
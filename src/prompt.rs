@@ -0,0 +1,85 @@
+//! Asking questions on the terminal, for `create --interactive`.
+
+use std::io::{BufRead, Write};
+
+use anyhow::{bail, Result};
+
+/// Asks questions on `output` and reads the answers from `input`, a line
+/// each.
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W) -> Prompter<R, W> {
+        Prompter { input, output }
+    }
+
+    /// Asks `question` until `check` accepts the answer, and returns what
+    /// `check` made of it. An empty answer stands for `default`, which is
+    /// shown in brackets. Fails if the input ends before there's an answer.
+    pub fn ask<T>(
+        &mut self,
+        question: &str,
+        default: Option<&str>,
+        check: impl Fn(&str) -> Result<T>,
+    ) -> Result<T> {
+        loop {
+            match default {
+                Some(default) if !default.is_empty() => {
+                    write!(self.output, "{} [{}]: ", question, default)?
+                }
+                _ => write!(self.output, "{}: ", question)?,
+            }
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                bail!("No answer to '{}', the input ended.", question)
+            }
+            let answer = match line.trim() {
+                "" => default.unwrap_or(""),
+                answer => answer,
+            };
+            match check(answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => writeln!(self.output, "{:#}", e)?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn number(answer: &str) -> Result<usize> {
+        Ok(answer.parse()?)
+    }
+
+    #[test]
+    fn test_ask_retries_until_the_answer_checks_out() {
+        let mut output = vec![];
+        let mut prompter = Prompter::new(&b"many\n 3 \n"[..], &mut output);
+        assert_eq!(prompter.ask("How many", None, number).unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "How many: invalid digit found in string\nHow many: "
+        );
+    }
+
+    #[test]
+    fn test_ask_takes_the_default_for_an_empty_answer() {
+        let mut output = vec![];
+        let mut prompter = Prompter::new(&b"\n"[..], &mut output);
+        assert_eq!(prompter.ask("How many", Some("2"), number).unwrap(), 2);
+        assert_eq!(String::from_utf8(output).unwrap(), "How many [2]: ");
+    }
+
+    #[test]
+    fn test_ask_fails_when_the_input_ends() {
+        let mut prompter = Prompter::new(&b""[..], vec![]);
+        let err = prompter.ask("How many", Some("2"), number).unwrap_err();
+        assert_eq!(err.to_string(), "No answer to 'How many', the input ended.");
+    }
+}
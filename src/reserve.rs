@@ -0,0 +1,75 @@
+//! `rfcs reserve`: RFC numbers set aside ahead of time, say for an RFC that's
+//! announced before it's written. They're kept in `reserved.toml` in the git
+//! directory, so they're the local repository's own, and new RFCs are
+//! numbered past them until they're released.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Where the reservations of the repository with the git directory
+/// `git_dir` are kept.
+pub fn ledger_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("rfcs").join("reserved.toml")
+}
+
+/// The reserved numbers, as kept in `reserved.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Reservations {
+    #[serde(default)]
+    pub numbers: BTreeSet<usize>,
+}
+
+impl Reservations {
+    /// Reads the ledger at `path`. Without one, nothing is reserved.
+    pub fn read(path: &Path) -> Result<Reservations> {
+        match fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Reservations::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Writes the ledger to `path`, creating its directory if need be.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The number after the highest reserved one, the lowest a new RFC may
+    /// get. 0 if nothing is reserved.
+    pub fn next(&self) -> usize {
+        self.numbers.last().map_or(0, |number| number + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let dir = test_util::temp_dir("reserve");
+        let path = ledger_path(&dir);
+        assert_eq!(Reservations::read(&path).unwrap(), Reservations::default());
+        assert_eq!(Reservations::default().next(), 0);
+
+        let reservations = Reservations {
+            numbers: BTreeSet::from([100, 7]),
+        };
+        reservations.write(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "numbers = [7, 100]\n");
+        assert_eq!(Reservations::read(&path).unwrap(), reservations);
+        assert_eq!(reservations.next(), 101);
+    }
+}
@@ -0,0 +1,92 @@
+//! Running other programs: `git clone` and `git fetch`, the editor and the
+//! browser. They're run through a `Runner`, so that tests can check what
+//! would have been run without running anything.
+
+use std::io;
+use std::process::{Command, ExitStatus, Output};
+use std::time::Duration;
+
+use crate::platform;
+use crate::trace;
+
+pub trait Runner {
+    /// Runs `command` with its output captured, see
+    /// `platform::output_with_timeout`.
+    fn output(&self, command: &mut Command, timeout: Duration) -> io::Result<Output>;
+
+    /// Runs `command` on the terminal `rfcs` runs on, and waits for it to
+    /// exit.
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus>;
+}
+
+/// Actually runs the programs, logging them with `-v`.
+pub struct System;
+
+impl Runner for System {
+    fn output(&self, command: &mut Command, timeout: Duration) -> io::Result<Output> {
+        trace::command(command);
+        platform::output_with_timeout(command, timeout)
+    }
+
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus> {
+        trace::command(command);
+        command.status()
+    }
+}
+
+/// A program `Mock` was asked to run.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    /// The program, followed by its arguments.
+    pub words: Vec<String>,
+    pub cwd: Option<std::path::PathBuf>,
+}
+
+/// Runs nothing, but records what it's asked to run, and has every program
+/// succeed without output.
+#[cfg(test)]
+#[derive(Default)]
+pub struct Mock {
+    pub calls: std::cell::RefCell<Vec<Call>>,
+}
+
+#[cfg(test)]
+impl Mock {
+    fn record(&self, command: &Command) -> ExitStatus {
+        let words = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|word| word.to_string_lossy().into_owned())
+            .collect();
+        self.calls.borrow_mut().push(Call {
+            words,
+            cwd: command.get_current_dir().map(|dir| dir.to_path_buf()),
+        });
+        success()
+    }
+}
+
+#[cfg(test)]
+impl Runner for Mock {
+    fn output(&self, command: &mut Command, _: Duration) -> io::Result<Output> {
+        Ok(Output {
+            status: self.record(command),
+            stdout: vec![],
+            stderr: vec![],
+        })
+    }
+
+    fn status(&self, command: &mut Command) -> io::Result<ExitStatus> {
+        Ok(self.record(command))
+    }
+}
+
+#[cfg(all(test, unix))]
+fn success() -> ExitStatus {
+    std::os::unix::process::ExitStatusExt::from_raw(0)
+}
+
+#[cfg(all(test, windows))]
+fn success() -> ExitStatus {
+    std::os::windows::process::ExitStatusExt::from_raw(0)
+}
@@ -0,0 +1,179 @@
+//! Templates for new RFCs: the files in the templates directory, each named
+//! after its file name without the extension, e.g. `design` for `design.md`.
+//! `{{title}}` and `{{number}}` in a template are replaced with those of the
+//! RFC being created, and `{{date}}` with the day it's created on;
+//! `rfcs templates --validate` flags any other placeholder.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use regex::Regex;
+
+/// The templates directory, relative to the repository root, unless the
+/// config has `templates_dir`.
+pub const DEFAULT_DIR: &str = "templates";
+
+/// The template `create` uses without `--from-template`, if there is one.
+pub const DEFAULT_NAME: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Template {
+    /// The extension RFCs created from this template get.
+    pub fn extension(&self) -> &str {
+        self.path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("md")
+    }
+
+    /// The template's content, filled in for RFC `number` titled `title`,
+    /// created on `date`.
+    pub fn render(&self, number: usize, title: &str, date: &str) -> Result<String> {
+        let mut content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read template {}", self.path.display()))?;
+        for (placeholder, value) in substitutions(number, title, date) {
+            content = content.replace(&format!("{{{{{}}}}}", placeholder), &value);
+        }
+        Ok(content)
+    }
+
+    /// What's wrong with the template that would only show once an RFC is
+    /// created from it: content that isn't UTF-8, and placeholders `render`
+    /// doesn't fill in. Empty if nothing is.
+    pub fn problems(&self) -> Result<Vec<String>> {
+        let bytes = fs::read(&self.path)
+            .with_context(|| format!("Failed to read template {}", self.path.display()))?;
+        let Ok(content) = String::from_utf8(bytes) else {
+            return Ok(vec![String::from("is not valid UTF-8")]);
+        };
+        let known: Vec<&str> = substitutions(0, "", "").map(|(p, _)| p).to_vec();
+        let re = Regex::new(r"\{\{([^{}]*)\}\}").expect("Can't compile placeholder regex");
+        Ok(re
+            .captures_iter(&content)
+            .filter_map(|c| c.get(1))
+            .filter(|p| !known.contains(&p.as_str()))
+            .map(|p| {
+                format!(
+                    "has an unknown placeholder {{{{{}}}}}, known ones are {}",
+                    p.as_str(),
+                    known.join(", ")
+                )
+            })
+            .collect())
+    }
+}
+
+/// What each placeholder is replaced with in an RFC numbered `number`,
+/// titled `title` and created on `date`.
+fn substitutions(number: usize, title: &str, date: &str) -> [(&'static str, String); 3] {
+    [
+        ("title", title.to_string()),
+        ("number", format!("{:03}", number)),
+        ("date", date.to_string()),
+    ]
+}
+
+/// The templates in `dir`, sorted by name. A missing directory has none.
+pub fn list(dir: &Path) -> Result<Vec<Template>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut templates = vec![];
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+            templates.push(Template {
+                name: name.to_string(),
+                path: path.clone(),
+            });
+        }
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// The template called `name` in `dir`, failing with the available names if
+/// there is none.
+pub fn find(dir: &Path, name: &str) -> Result<Template> {
+    let templates = list(dir)?;
+    if let Some(template) = templates.iter().find(|t| t.name == name) {
+        return Ok(template.clone());
+    }
+    match templates.as_slice() {
+        [] => bail!(
+            "There is no template '{}', {} has no templates.",
+            name,
+            dir.display()
+        ),
+        _ => bail!(
+            "There is no template '{}', available templates: {}",
+            name,
+            templates
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn test_find_and_render() {
+        let dir = test_util::temp_dir("templates");
+        fs::write(dir.join("design.md"), "# {{number}} {{title}}\n{{date}}\n").unwrap();
+        fs::write(dir.join("process.org"), "* {{title}}\n").unwrap();
+
+        let names: Vec<String> = list(&dir).unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["design", "process"]);
+        let design = find(&dir, "design").unwrap();
+        assert_eq!(
+            design.render(7, "Caches", "2024-02-29").unwrap(),
+            "# 007 Caches\n2024-02-29\n"
+        );
+        assert_eq!(find(&dir, "process").unwrap().extension(), "org");
+
+        let err = find(&dir, "nope").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "There is no template 'nope', available templates: design, process"
+        );
+        assert!(list(&dir.join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_problems() {
+        let dir = test_util::temp_dir("templates");
+        fs::write(dir.join("good.md"), "# {{number}} {{title}}\n{ not one }\n").unwrap();
+        fs::write(dir.join("typo.md"), "# {{titel}} {{ number }}\n").unwrap();
+        fs::write(dir.join("binary.md"), [0xff, 0xfe]).unwrap();
+
+        assert!(find(&dir, "good").unwrap().problems().unwrap().is_empty());
+        assert_eq!(
+            find(&dir, "typo").unwrap().problems().unwrap(),
+            [
+                "has an unknown placeholder {{titel}}, known ones are title, number, date",
+                "has an unknown placeholder {{ number }}, known ones are title, number, date",
+            ]
+        );
+        assert_eq!(
+            find(&dir, "binary").unwrap().problems().unwrap(),
+            ["is not valid UTF-8"]
+        );
+    }
+}
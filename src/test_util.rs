@@ -0,0 +1,24 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A fresh, empty directory under the system temp dir, unique per call. The
+/// unique suffix is spelled in letters so that fixture paths never contain
+/// anything resembling an RFC number.
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let mut n = (std::process::id() as usize) * 1000 + COUNTER.fetch_add(1, Ordering::SeqCst);
+    let mut unique = String::new();
+    loop {
+        unique.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+        if n == 0 {
+            break;
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("rfcs-ut-{}-{}", name, unique));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
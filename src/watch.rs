@@ -0,0 +1,136 @@
+//! `list --watch`: noticing that a repository changed. There's no file system
+//! notification crate to build on, so `Polling` compares the modification
+//! times of the repository's files every so often instead.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Something that can wait for a change.
+pub trait Watcher {
+    /// Blocks until something changed. Returns `false` if there will be no
+    /// more changes to wait for.
+    fn wait(&mut self) -> Result<bool>;
+}
+
+/// Calls `render`, and again after every change `watcher` sees.
+pub fn run(watcher: &mut dyn Watcher, mut render: impl FnMut() -> Result<()>) -> Result<()> {
+    render()?;
+    while watcher.wait()? {
+        render()?;
+    }
+    Ok(())
+}
+
+/// Watches the files of the repository at `root`, including what's in its
+/// git directory `git_dir` apart from the objects and logs, so that commits
+/// and branch switches count as changes too.
+pub struct Polling {
+    root: PathBuf,
+    skipped: [PathBuf; 2],
+    last: Snapshot,
+}
+
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+impl Polling {
+    pub fn new(root: &Path, git_dir: &Path) -> Polling {
+        let mut polling = Polling {
+            root: root.to_path_buf(),
+            skipped: [git_dir.join("objects"), git_dir.join("logs")],
+            last: Snapshot::new(),
+        };
+        polling.last = polling.snapshot();
+        polling
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        walkdir::WalkDir::new(&self.root)
+            .into_iter()
+            .filter_entry(|e| !self.skipped.iter().any(|s| s == e.path()))
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                Some((
+                    e.path().to_path_buf(),
+                    (metadata.modified().ok(), metadata.len()),
+                ))
+            })
+            .collect()
+    }
+}
+
+impl Watcher for Polling {
+    /// Once something changed, waits for the files to settle for a poll
+    /// interval, so that e.g. a checkout is only one change.
+    fn wait(&mut self) -> Result<bool> {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = self.snapshot();
+            if current == self.last {
+                continue;
+            }
+            self.last = current;
+            loop {
+                thread::sleep(POLL_INTERVAL);
+                let current = self.snapshot();
+                if current == self.last {
+                    return Ok(true);
+                }
+                self.last = current;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util;
+    use std::fs;
+
+    /// A watcher that sees `changes` changes, and then none.
+    struct Simulated {
+        changes: usize,
+    }
+
+    impl Watcher for Simulated {
+        fn wait(&mut self) -> Result<bool> {
+            match self.changes {
+                0 => Ok(false),
+                _ => {
+                    self.changes -= 1;
+                    Ok(true)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_renders_again_on_every_change() {
+        let mut renders = 0;
+        run(&mut Simulated { changes: 2 }, || {
+            renders += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(renders, 3);
+    }
+
+    #[test]
+    fn test_polling_sees_new_files() {
+        let root = test_util::temp_dir("watched");
+        fs::write(root.join("001-first.md"), "# First\n").unwrap();
+        let mut polling = Polling::new(&root, &root.join(".git"));
+        assert_eq!(polling.snapshot(), polling.last);
+
+        fs::write(root.join("002-second.md"), "# Second\n").unwrap();
+        assert!(polling.wait().unwrap());
+        assert!(polling.last.contains_key(&root.join("002-second.md")));
+    }
+}
@@ -0,0 +1,253 @@
+mod common;
+
+use std::path::Path;
+
+use common::*;
+
+/// A bare repository at `<dir>/rfcs.git` holding `001-first.md`.
+fn bare_fixture(dir: &Path) -> std::path::PathBuf {
+    let source = dir.join("source");
+    init_repo(&source, &[("001-first.md", "# First\n")]);
+    let bare = dir.join("rfcs.git");
+    git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(&format!("file://{}", source.display()), &bare)
+        .unwrap();
+    bare
+}
+
+fn assert_cloned(home: &Path, output: std::process::Output) {
+    assert!(output.status.success(), "{}", stderr(&output));
+    let clone = home.join(".config").join("rfcs").join("rfcs");
+    assert_eq!(
+        stdout(&output),
+        format!("{}\n", clone.join("001-first.md").display())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_clone_from_file_url() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+    let home = home_with_config(&format!(
+        "[git]\nurl = {:?}\n",
+        format!("file://{}", bare.display())
+    ));
+
+    assert_cloned(&home, rfcs(&home, &["list", "--absolute"]));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shallow_clone() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+    let home = home_with_config(&format!(
+        "[git]\nurl = {:?}\nshallow = true\n",
+        format!("file://{}", bare.display())
+    ));
+
+    assert_cloned(&home, rfcs(&home, &["list", "--absolute"]));
+    let clone = home.join(".config").join("rfcs").join("rfcs");
+    assert!(git2::Repository::open(clone).unwrap().is_shallow());
+}
+
+#[test]
+fn test_clone_from_absolute_and_relative_paths() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+
+    let home = home_with_config(&format!("[git]\nurl = {:?}\n", bare.display().to_string()));
+    assert_cloned(&home, rfcs(&home, &["list", "--absolute"]));
+
+    let home = home_with_config("[git]\nurl = \"rfcs.git\"\n");
+    assert_cloned(&home, rfcs_in(&home, &fixtures, &["list", "--absolute"]));
+}
+
+#[test]
+fn test_no_clone_fails_instead_of_cloning() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+    let home = home_with_config(&format!("[git]\nurl = {:?}\n", bare.display().to_string()));
+    let clone = home.join(".config").join("rfcs").join("rfcs");
+
+    let output = rfcs(&home, &["list", "--no-clone"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--no-clone"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!clone.exists());
+
+    // Once there is a clone, it's used as it is.
+    assert_cloned(&home, rfcs(&home, &["list", "--absolute"]));
+    assert_cloned(&home, rfcs(&home, &["--no-clone", "list", "--absolute"]));
+}
+
+#[test]
+fn test_auto_clone_off_prints_the_clone_command() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+    let url = format!("file://{}", bare.display());
+    let home = home_with_config(&format!(
+        "[git]\nurl = {:?}\nauto_clone = false\nshallow = true\n",
+        url
+    ));
+    let clone = home.join(".config").join("rfcs").join("rfcs");
+
+    let output = rfcs(&home, &["list"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains(&format!(
+            "git clone --depth=1 {} {}\n",
+            url,
+            clone.display()
+        )),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!clone.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_clone_timeout_kills_git() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // A `git` that records its PID and then hangs, like a stuck remote.
+    let bin = temp_dir("bin");
+    let pid_file = bin.join("pid");
+    let fake_git = bin.join("git");
+    std::fs::write(
+        &fake_git,
+        format!(
+            "#!/bin/sh\necho $$ > {}\nexec sleep 30\n",
+            pid_file.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&fake_git, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let home =
+        home_with_config("[git]\nurl = \"https://example.com/rfcs.git\"\nclone_timeout_secs = 1\n");
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let started = std::time::Instant::now();
+    let output = rfcs_env(&home, &[("PATH", &path)], &["list"]);
+
+    assert!(!output.status.success());
+    assert!(started.elapsed() < std::time::Duration::from_secs(20));
+    assert!(
+        stderr(&output).contains("took longer than 1 second"),
+        "{}",
+        stderr(&output)
+    );
+    let pid = std::fs::read_to_string(&pid_file).unwrap();
+    let alive = std::process::Command::new("kill")
+        .args(["-0", pid.trim()])
+        .status()
+        .unwrap();
+    assert!(!alive.success(), "fake git {} is still running", pid.trim());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_timeout_bounds_clone() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // A `git` that hangs, well within the default clone timeout.
+    let bin = temp_dir("bin");
+    let fake_git = bin.join("git");
+    std::fs::write(&fake_git, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    std::fs::set_permissions(&fake_git, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let home = home_with_config("[git]\nurl = \"https://example.com/rfcs.git\"\n");
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let started = std::time::Instant::now();
+    let output = rfcs_env(&home, &[("PATH", &path)], &["--timeout", "1", "list"]);
+
+    assert!(!output.status.success());
+    assert!(started.elapsed() < std::time::Duration::from_secs(20));
+    assert!(
+        stderr(&output).contains("--timeout ran out after 1 second"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_clone_into_clone_dir_sets_repo() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+    let clones = temp_dir("clones").join("elsewhere");
+    let home = home_with_config(&format!(
+        "[git]\nurl = {:?}\nclone_dir = {:?}\n",
+        bare.display().to_string(),
+        clones.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list", "--absolute"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let clone = clones.join("rfcs");
+    assert_eq!(
+        stdout(&output),
+        format!("{}\n", clone.join("001-first.md").display())
+    );
+    assert!(!home.join(".config").join("rfcs").join("rfcs").exists());
+    let output = rfcs(&home, &["config-get", "repo.path"]);
+    assert_eq!(stdout(&output), format!("{}\n", clone.display()));
+}
+
+#[test]
+fn test_clone_dir_flag_overrides_config() {
+    let fixtures = temp_dir("fixtures");
+    let bare = bare_fixture(&fixtures);
+    let clones = temp_dir("clones");
+    let home = home_with_config(&format!("[git]\nurl = {:?}\n", bare.display().to_string()));
+
+    let output = rfcs(
+        &home,
+        &["list", "--clone-dir", &clones.display().to_string()],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(clones.join("rfcs").join("001-first.md").exists());
+}
+
+#[test]
+fn test_repo_and_url_that_disagree() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "")]);
+    repo.remote("origin", "git@example.com:org/rfcs-a.git")
+        .unwrap();
+    let config = |url: &str| {
+        format!(
+            "[repo]\npath = {:?}\n[git]\nurl = {:?}\n",
+            repo_path.display().to_string(),
+            url
+        )
+    };
+
+    let home = home_with_config(&config("https://example.com/org/rfcs-b.git"));
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+    assert!(
+        stderr(&output).contains(
+            "is a clone of git@example.com:org/rfcs-a.git, not of git.url \
+             https://example.com/org/rfcs-b.git"
+        ),
+        "{}",
+        stderr(&output)
+    );
+    let output = rfcs(&home, &["--strict", "list"]);
+    assert!(!output.status.success());
+
+    // The same repository over HTTPS is no mismatch.
+    let home = home_with_config(&config("https://example.com/org/rfcs-a"));
+    let output = rfcs(&home, &["--strict", "list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stderr(&output), "");
+}
@@ -0,0 +1,186 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A fresh, empty directory under the system temp dir, unique per call. The
+/// unique suffix is spelled in letters so that fixture paths never contain
+/// anything resembling an RFC number.
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let unique = (std::process::id() as usize) * 1000 + COUNTER.fetch_add(1, Ordering::SeqCst);
+    let dir = std::env::temp_dir().join(format!("rfcs-it-{}-{}", name, letters(unique)));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn letters(mut n: usize) -> String {
+    let mut s = String::new();
+    loop {
+        s.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+        if n == 0 {
+            return s;
+        }
+    }
+}
+
+/// Initialises a git repository on `main` at `path`, and commits `files`
+/// (path, content) into it as the first commit.
+pub fn init_repo(path: &Path, files: &[(&str, &str)]) -> git2::Repository {
+    let repo = git2::Repository::init_opts(
+        path,
+        git2::RepositoryInitOptions::new().initial_head("main"),
+    )
+    .unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Test Author").unwrap();
+    config.set_str("user.email", "test@example.com").unwrap();
+    write_files(path, files);
+    commit_all(&repo, "Initial commit");
+    repo
+}
+
+pub fn write_files(root: &Path, files: &[(&str, &str)]) {
+    for (name, content) in files {
+        let file = root.join(name);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+}
+
+/// Commits `files` on `branch` (created from HEAD if it doesn't exist yet),
+/// then switches back to where HEAD was.
+pub fn commit_on_branch(
+    repo: &git2::Repository,
+    branch: &str,
+    files: &[(&str, &str)],
+) -> git2::Oid {
+    let original = repo.head().unwrap().name().unwrap().to_string();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    if repo.find_branch(branch, git2::BranchType::Local).is_err() {
+        repo.branch(branch, &head, false).unwrap();
+    }
+    switch_to(repo, &format!("refs/heads/{}", branch));
+    write_files(repo.workdir().unwrap(), files);
+    let oid = commit_all(repo, &format!("Commit on {}", branch));
+    switch_to(repo, &original);
+    oid
+}
+
+/// Force-checks out `reference` and points HEAD at it.
+pub fn switch_to(repo: &git2::Repository, reference: &str) {
+    let target = repo
+        .find_reference(reference)
+        .unwrap()
+        .peel_to_tree()
+        .unwrap();
+    repo.checkout_tree(
+        target.as_object(),
+        Some(git2::build::CheckoutBuilder::new().force()),
+    )
+    .unwrap();
+    repo.set_head(reference).unwrap();
+}
+
+/// The content of `path` in the tree of `branch`.
+pub fn file_on_branch(repo: &git2::Repository, branch: &str, path: &str) -> Option<String> {
+    let tree = repo
+        .find_branch(branch, git2::BranchType::Local)
+        .ok()?
+        .get()
+        .peel_to_tree()
+        .ok()?;
+    let blob = tree.get_path(Path::new(path)).ok()?.to_object(repo).ok()?;
+    Some(String::from_utf8(blob.as_blob()?.content().to_vec()).unwrap())
+}
+
+/// Stages everything in the working directory and commits it on HEAD.
+pub fn commit_all(repo: &git2::Repository, message: &str) -> git2::Oid {
+    let sig = git2::Signature::now("Test Author", "test@example.com").unwrap();
+    commit_all_as(repo, message, &sig)
+}
+
+/// Like `commit_all`, but committed at `time`, in seconds since the epoch.
+pub fn commit_all_at(repo: &git2::Repository, message: &str, time: i64) -> git2::Oid {
+    let sig =
+        git2::Signature::new("Test Author", "test@example.com", &git2::Time::new(time, 0)).unwrap();
+    commit_all_as(repo, message, &sig)
+}
+
+/// Like `commit_all`, but authored and committed by `sig`.
+pub fn commit_all_as(repo: &git2::Repository, message: &str, sig: &git2::Signature) -> git2::Oid {
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), sig, sig, message, &tree, &parents)
+        .unwrap()
+}
+
+/// A temp `$HOME` with a config file containing `config`.
+pub fn home_with_config(config: &str) -> PathBuf {
+    let home = temp_dir("home");
+    let dir = home.join(".config").join("rfcs");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("config.toml"), config).unwrap();
+    home
+}
+
+/// A temp `$HOME` whose config points `repo.path` at `repo`.
+pub fn home_for_repo(repo: &Path) -> PathBuf {
+    home_with_config(&format!(
+        "version = 1\n[repo]\npath = {:?}\n",
+        repo.display().to_string()
+    ))
+}
+
+/// Runs the `rfcs` binary with `$HOME` set to `home`, from inside `home`.
+pub fn rfcs(home: &Path, args: &[&str]) -> Output {
+    rfcs_in(home, home, args)
+}
+
+/// Runs the `rfcs` binary with `$HOME` set to `home`, from inside `cwd`.
+pub fn rfcs_in(home: &Path, cwd: &Path, args: &[&str]) -> Output {
+    rfcs_cmd(home, cwd, args).output().unwrap()
+}
+
+/// Runs the `rfcs` binary with `$HOME` set to `home` and the extra `env`.
+pub fn rfcs_env(home: &Path, env: &[(&str, &str)], args: &[&str]) -> Output {
+    rfcs_cmd(home, home, args)
+        .envs(env.iter().copied())
+        .output()
+        .unwrap()
+}
+
+/// The `rfcs` command with `$HOME` set to `home`, run from inside `cwd`, and
+/// with any `RFCS_*` variables of the surrounding environment removed.
+pub fn rfcs_cmd(home: &Path, cwd: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rfcs"));
+    command.args(args).env("HOME", home).current_dir(cwd);
+    for (key, _) in std::env::vars() {
+        if key.starts_with("RFCS_") {
+            command.env_remove(key);
+        }
+    }
+    command
+}
+
+pub fn global_config(home: &Path) -> String {
+    fs::read_to_string(home.join(".config").join("rfcs").join("config.toml")).unwrap()
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
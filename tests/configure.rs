@@ -0,0 +1,498 @@
+mod common;
+
+use std::fs;
+
+use common::*;
+
+#[test]
+fn test_configure_local_writes_repo_file_only() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let global_before = global_config(&home);
+
+    let output = rfcs_in(
+        &home,
+        &repo_path,
+        &[
+            "configure",
+            "--local",
+            "git.url",
+            "https://example.com/rfcs.git",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let local = fs::read_to_string(repo_path.join(".rfcs.toml")).unwrap();
+    assert!(local.contains("https://example.com/rfcs.git"));
+    assert!(!local.contains("repo ="));
+    assert_eq!(global_config(&home), global_before);
+}
+
+#[test]
+fn test_configure_defaults_to_global() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs_in(
+        &home,
+        &repo_path,
+        &["configure", "git.url", "https://example.com/rfcs.git"],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!repo_path.join(".rfcs.toml").exists());
+    assert!(global_config(&home).contains("https://example.com/rfcs.git"));
+}
+
+#[test]
+fn test_env_overrides_configured_repo() {
+    let configured = temp_dir("repo");
+    init_repo(&configured, &[("001-configured.md", "# Configured\n")]);
+    let from_env = temp_dir("repo");
+    init_repo(&from_env, &[("001-from-env.md", "# From env\n")]);
+    let home = home_for_repo(&configured);
+    let env_repo = from_env.display().to_string();
+
+    let output = rfcs_env(&home, &[("RFCS_GIT_REPO", &env_repo)], &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("001-from-env.md"));
+    assert!(!stdout(&output).contains("001-configured.md"));
+
+    let output = rfcs_env(&home, &[("RFCS_GIT_REPO", &env_repo)], &["dump-info"]);
+    assert!(stdout(&output).contains("(from environment)"));
+}
+
+#[test]
+fn test_config_get_prints_only_the_effective_value() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    fs::write(
+        repo_path.join(".rfcs.toml"),
+        "[git]\ndefault_branch = \"trunk\"\n",
+    )
+    .unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs_in(&home, &repo_path, &["config-get", "repo.path"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n", repo_path.display()));
+
+    let output = rfcs_in(&home, &repo_path, &["config-get", "git.default_branch"]);
+    assert_eq!(stdout(&output), "trunk\n");
+
+    let output = rfcs_env(
+        &home,
+        &[("RFCS_GIT_REPO", "/env/repo")],
+        &["config-get", "repo.path"],
+    );
+    assert_eq!(stdout(&output), "/env/repo\n");
+}
+
+#[test]
+fn test_config_get_unset_key_fails() {
+    let home = home_with_config("");
+
+    let output = rfcs(&home, &["config-get", "git.url"]);
+
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("git.url is not set"));
+}
+
+#[test]
+fn test_unversioned_global_config_is_migrated() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_with_config(&format!(
+        "[git]\nrepo = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let migrated = global_config(&home);
+    assert!(migrated.starts_with("version = 1\n"), "{}", migrated);
+    assert!(migrated.contains(&format!("path = {:?}", repo_path.display().to_string())));
+}
+
+#[test]
+fn test_renamed_keys_are_read_and_rewritten() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_with_config(&format!(
+        "version = 1\n[git]\nrepo = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["config-get", "repo.path"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n", repo_path.display()));
+    assert!(
+        stderr(&output).contains("git.repo in ")
+            && stderr(&output).contains("is deprecated, it's called repo.path now."),
+        "{}",
+        stderr(&output)
+    );
+    let rewritten = global_config(&home);
+    assert!(!rewritten.contains("[git]"), "{}", rewritten);
+    assert!(
+        rewritten.contains(&format!(
+            "[repo]\npath = {:?}\n",
+            repo_path.display().to_string()
+        )),
+        "{}",
+        rewritten
+    );
+
+    let output = rfcs(&home, &["config-get", "repo.path"]);
+    assert_eq!(stderr(&output), "");
+
+    let output = rfcs(&home, &["config-get", "git.repo"]);
+    assert_eq!(stdout(&output), format!("{}\n", repo_path.display()));
+    assert!(stderr(&output).contains("git.repo is deprecated, it's called repo.path now."));
+}
+
+#[test]
+fn test_renamed_keys_in_the_local_config_are_rewritten() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let deprecated = format!("[git]\nrepo = {:?}\n", repo_path.display().to_string());
+    fs::write(repo_path.join(".rfcs.toml"), &deprecated).unwrap();
+    let home = home_with_config("version = 1\n");
+
+    let output = rfcs_in(&home, &repo_path, &["--strict", "config-get", "repo.path"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("is deprecated, it's called repo.path now."),
+        "{}",
+        stderr(&output)
+    );
+
+    fs::write(repo_path.join(".rfcs.toml"), &deprecated).unwrap();
+    let output = rfcs_in(&home, &repo_path, &["config-get", "repo.path"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{}\n", repo_path.display()));
+    let rewritten = fs::read_to_string(repo_path.join(".rfcs.toml")).unwrap();
+    assert!(!rewritten.contains("[git]"), "{}", rewritten);
+    assert!(
+        rewritten.contains(&format!(
+            "[repo]\npath = {:?}\n",
+            repo_path.display().to_string()
+        )),
+        "{}",
+        rewritten
+    );
+
+    let output = rfcs_in(&home, &repo_path, &["--strict", "config-get", "repo.path"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let output = rfcs_in(&home, &repo_path, &["--strict", "config-get", "git.repo"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_newer_global_config_warns() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_with_config(&format!(
+        "version = 99\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("config version 99"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(global_config(&home).starts_with("version = 99\n"));
+}
+
+#[test]
+fn test_no_config_write_leaves_missing_config_missing() {
+    let home = temp_dir("home");
+    let config_dir = home.join(".config").join("rfcs");
+
+    let output = rfcs_env(&home, &[("RFCS_NO_CONFIG_WRITE", "1")], &["dump-info"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!config_dir.exists());
+
+    let output = rfcs(&home, &["dump-info"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(config_dir.join("config.toml").exists());
+}
+
+#[test]
+fn test_without_home() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = temp_dir("home");
+    let run = |env: &[(&str, &str)], args: &[&str]| {
+        rfcs_cmd(&home, &home, args)
+            .env_remove("HOME")
+            .env_remove("APPDATA")
+            .envs(env.iter().copied())
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[], &["--version"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let repo = repo_path.display().to_string();
+    let output = run(&[("RFCS_GIT_REPO", &repo)], &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+
+    let output = run(&[], &["configure", "repo.path", &repo]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Neither $HOME nor %APPDATA% is set"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!stderr(&output).contains("panicked"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_configure_edit() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Editors that append a valid setting, or break the file.
+    let bin = temp_dir("bin");
+    for (name, line) in [
+        ("valid", "slug_max_length = 20"),
+        ("invalid", "slug_max_length ="),
+    ] {
+        let editor = bin.join(name);
+        std::fs::write(&editor, format!("#!/bin/sh\necho '{}' >> \"$1\"\n", line)).unwrap();
+        std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let home = temp_dir("home");
+    let valid = bin.join("valid").display().to_string();
+    let invalid = bin.join("invalid").display().to_string();
+
+    // Without a config file, the defaults are written first.
+    let output = rfcs_env(&home, &[("VISUAL", &valid)], &["configure", "--edit"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let config = global_config(&home);
+    assert!(config.starts_with("version = 1\n"), "{}", config);
+    assert!(config.ends_with("slug_max_length = 20\n"), "{}", config);
+
+    let output = rfcs_env(&home, &[("VISUAL", &invalid)], &["configure", "--edit"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Failed to parse config file"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(global_config(&home), config);
+    let rejected = home.join(".config/rfcs/config.toml.rejected");
+    assert!(std::fs::read_to_string(rejected)
+        .unwrap()
+        .ends_with("slug_max_length =\n"));
+}
+
+#[test]
+fn test_configure_help_has_examples() {
+    let home = temp_dir("home");
+
+    let output = rfcs(&home, &["configure", "--help"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("rfcs configure git.url git@github.com:org/rfcs.git"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_discovers_the_enclosing_repo() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[("001-first.md", "# First\n"), ("docs/notes.txt", "\n")],
+    );
+    let home = home_with_config("version = 1\n");
+
+    let output = rfcs_in(&home, &repo_path.join("docs"), &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+
+    let output = rfcs_in(&home, &home, &["list"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No local git repo configured"));
+
+    let home = home_with_config("version = 1\n[git]\ndiscover = false\n");
+    let output = rfcs_in(&home, &repo_path, &["list"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("No local git repo configured"));
+}
+
+#[test]
+fn test_uses_the_repo_git_dir_points_at() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    // The repository, apart from its working directory.
+    let git_dir = temp_dir("git-dir").join("rfcs.git");
+    fs::rename(repo_path.join(".git"), &git_dir).unwrap();
+    let home = home_with_config("version = 1\n");
+    let env = [
+        ("GIT_DIR", git_dir.to_str().unwrap()),
+        ("GIT_WORK_TREE", repo_path.to_str().unwrap()),
+    ];
+
+    let output = rfcs_env(&home, &env, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+
+    let output = rfcs_env(&home, &env, &["create", "--no-checkout", "Second"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let repo = git2::Repository::open(&git_dir).unwrap();
+    assert!(repo
+        .find_branch("002-Second", git2::BranchType::Local)
+        .is_ok());
+    assert!(!repo_path.join(".git").exists());
+}
+
+#[test]
+fn test_error_format_json() {
+    let home = home_with_config("version = true\n");
+    let config_file = home.join(".config").join("rfcs").join("config.toml");
+
+    let output = rfcs(&home, &["--error-format", "json", "list"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        format!(
+            "{{\"causes\":[\"version must be a number, not true\"],\"code\":1,\
+             \"message\":\"Failed to parse config file {}\"}}\n",
+            config_file.display()
+        )
+    );
+
+    let output = rfcs(&home, &["list"]);
+    assert!(stderr(&output).starts_with("Error: Failed to parse config file"));
+}
+
+#[test]
+fn test_toml_in_a_json_config_says_so() {
+    let home = temp_dir("home");
+    let config_file = home.join("config.json");
+    std::fs::write(&config_file, "version = 1\n[git]\nurl = \"x\"\n").unwrap();
+
+    let output = rfcs(&home, &["--config", config_file.to_str().unwrap(), "list"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains(
+            "It's named like a JSON file, but looks like TOML. Rename it to end \
+             in .toml, or write it as JSON."
+        ),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_configure_dry_run_leaves_file_alone() {
+    let home = home_with_config("version = 1\n[git]\nurl = \"https://example.com/old.git\"\n");
+    let before = global_config(&home);
+
+    let output = rfcs(
+        &home,
+        &[
+            "configure",
+            "--dry-run",
+            "git.url",
+            "https://example.com/new.git",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output)
+            .contains("git.url: https://example.com/old.git → https://example.com/new.git\n"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(stdout(&output).contains("was not written"));
+    assert_eq!(global_config(&home), before);
+
+    let output = rfcs(
+        &home,
+        &[
+            "configure",
+            "--dry-run",
+            "git.url",
+            "https://example.com/old.git",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("No change to "));
+}
+
+#[test]
+fn test_config_path_prints_only_the_path() {
+    let home = temp_dir("home");
+
+    let output = rfcs(&home, &["config-path"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let expected = home.join(".config").join("rfcs").join("config.toml");
+    assert_eq!(stdout(&output), format!("{}\n", expected.display()));
+    assert_eq!(stderr(&output), "");
+
+    fs::write(home.join("other.toml"), "version = 1\nrfc_dir = \"docs\"\n").unwrap();
+    let output = rfcs(&home, &["--config", "other.toml", "config-path"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!("{}\n", home.join("other.toml").display())
+    );
+    let output = rfcs(&home, &["--config", "other.toml", "config-get", "rfc_dir"]);
+    assert_eq!(stdout(&output), "docs\n");
+}
+
+#[test]
+fn test_config_fragments_override_in_name_order() {
+    let home = home_with_config("version = 1\nrfc_dir = \"proposals\"\n");
+    let fragments = home.join(".config").join("rfcs").join("config.d");
+    fs::create_dir_all(&fragments).unwrap();
+    fs::write(
+        fragments.join("10-git.toml"),
+        "[git]\nurl = \"https://example.com/first.git\"\ndefault_branch = \"trunk\"\n",
+    )
+    .unwrap();
+    fs::write(
+        fragments.join("20-work.toml"),
+        "[git]\nurl = \"https://example.com/second.git\"\n",
+    )
+    .unwrap();
+
+    for (key, value) in [
+        ("rfc_dir", "proposals\n"),
+        ("git.url", "https://example.com/second.git\n"),
+        ("git.default_branch", "trunk\n"),
+    ] {
+        let output = rfcs(&home, &["config-get", key]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), value);
+    }
+
+    // Writes go to the config file, without the fragments baked in.
+    let output = rfcs(&home, &["configure", "slug_max_length", "40"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let config = global_config(&home);
+    assert!(config.contains("slug_max_length = 40"), "{}", config);
+    assert!(!config.contains("example.com"), "{}", config);
+}
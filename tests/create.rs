@@ -0,0 +1,1239 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_create_quiet_prints_nothing() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["--quiet", "create", "A new RFC"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+    assert!(repo
+        .find_branch("002-A-new-RFC", git2::BranchType::Local)
+        .is_ok());
+}
+
+#[test]
+fn test_create_reports_branch() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "A new RFC"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Created and checked out git branch 002-A-new-RFC"));
+}
+
+#[test]
+fn test_create_logs_git_operations_when_very_verbose() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["-vv", "create", "A new RFC"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let log = stderr(&output);
+    assert!(
+        log.contains("debug: git create_branch ") && log.contains("branch=002-A-new-RFC"),
+        "{}",
+        log
+    );
+    assert!(log.contains("debug: git create_branch done"), "{}", log);
+
+    let output = rfcs(&home, &["-v", "create", "Another RFC"]);
+    assert!(
+        !stderr(&output).contains("debug: git"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_uses_configured_default_branch() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let trunk = repo.branch("trunk", &head, false).unwrap();
+    repo.set_head(trunk.get().name().unwrap()).unwrap();
+    std::fs::write(repo_path.join("002-second.md"), "# Second\n").unwrap();
+    let trunk_tip = commit_all(&repo, "Second RFC");
+    repo.set_head("refs/heads/main").unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+    let home = home_with_config(&format!(
+        "[repo]\npath = {:?}\n[git]\ndefault_branch = \"trunk\"\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["create", "Third"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let created = repo
+        .find_branch("002-Third", git2::BranchType::Local)
+        .unwrap();
+    let rfc_commit = created.get().peel_to_commit().unwrap();
+    assert_eq!(rfc_commit.parent_id(0).unwrap(), trunk_tip);
+}
+
+#[test]
+fn test_create_no_checkout_leaves_head_alone() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_on_branch(&repo, "wip", &[("notes.txt", "wip\n")]);
+    switch_to(&repo, "refs/heads/wip");
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Second"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.head().unwrap().shorthand(), Some("wip"));
+    assert!(repo_path.join("notes.txt").exists());
+    let created = repo
+        .find_branch("002-Second", git2::BranchType::Local)
+        .unwrap();
+    let main = repo.find_branch("main", git2::BranchType::Local).unwrap();
+    let rfc_commit = created.get().peel_to_commit().unwrap();
+    assert_eq!(Some(rfc_commit.parent_id(0).unwrap()), main.get().target());
+    assert!(!repo_path.join("002-Second.md").exists());
+    assert!(file_on_branch(&repo, "002-Second", "002-Second.md").is_some());
+}
+
+#[test]
+fn test_create_in_repo_without_commits_explains() {
+    let repo_path = temp_dir("repo");
+    git2::Repository::init(&repo_path).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "A new RFC"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("has no commits yet"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_with_base_branch() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let release_tip = commit_on_branch(&repo, "release", &[("notes.txt", "release\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--base", "release", "Second"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let created = repo
+        .find_branch("002-Second", git2::BranchType::Local)
+        .unwrap();
+    let rfc_commit = created.get().peel_to_commit().unwrap();
+    assert_eq!(rfc_commit.parent_id(0).unwrap(), release_tip);
+    assert!(repo_path.join("notes.txt").exists());
+
+    let output = rfcs(&home, &["create", "--base", "nope", "Third"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("'nope'"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_create_porcelain() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--porcelain", "Caches, revisited"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let record: Vec<(String, String)> = stdout(&output)
+        .lines()
+        .map(|line| {
+            let (key, value) = line.split_once(' ').unwrap();
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    let path = repo_path.join("002-Caches-revisited.md");
+    assert_eq!(
+        record,
+        [
+            ("number".to_string(), "002".to_string()),
+            ("branch".to_string(), "002-Caches-revisited".to_string()),
+            ("path".to_string(), path.display().to_string()),
+        ]
+    );
+    assert_eq!(
+        repo.head().unwrap().shorthand(),
+        Some("002-Caches-revisited")
+    );
+    let content = std::fs::read_to_string(path).unwrap();
+    assert!(content.contains("title: Caches, revisited\nstatus: draft\n"));
+    assert!(repo.statuses(None).unwrap().is_empty());
+}
+
+#[test]
+fn test_concurrent_creates_get_distinct_numbers() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let children: Vec<_> = ["Alpha", "Beta", "Gamma"]
+        .iter()
+        .map(|title| {
+            rfcs_cmd(
+                &home,
+                &home,
+                &["create", "--no-checkout", "--porcelain", title],
+            )
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap()
+        })
+        .collect();
+    let mut numbers: Vec<String> = children
+        .into_iter()
+        .map(|child| {
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success());
+            stdout(&output).lines().next().unwrap().to_string()
+        })
+        .collect();
+    numbers.sort();
+
+    assert_eq!(numbers, ["number 002", "number 003", "number 004"]);
+    assert!(!repo.path().join("rfcs-create.lock").exists());
+}
+
+#[test]
+fn test_create_on_detached_head_warns() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let first = repo.head().unwrap().target().unwrap();
+    std::fs::write(repo_path.join("notes.txt"), "later\n").unwrap();
+    let main_tip = commit_all(&repo, "Notes");
+    repo.set_head_detached(first).unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+    let home = home_for_repo(&repo_path);
+
+    // With --strict, the warning is an error, and nothing is created.
+    let output = rfcs(&home, &["--strict", "create", "Second"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).starts_with("Error: HEAD is detached at"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("002-Second", git2::BranchType::Local)
+        .is_err());
+
+    let output = rfcs(&home, &["create", "Second"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains(&format!(
+            "Warning: HEAD is detached at {}. The RFC is based on main at {}",
+            &first.to_string()[..7],
+            &main_tip.to_string()[..7]
+        )),
+        "{}",
+        stderr(&output)
+    );
+    let created = repo
+        .find_branch("002-Second", git2::BranchType::Local)
+        .unwrap();
+    let rfc_commit = created.get().peel_to_commit().unwrap();
+    assert_eq!(rfc_commit.parent_id(0).unwrap(), main_tip);
+    assert_eq!(repo.head().unwrap().shorthand(), Some("002-Second"));
+}
+
+#[test]
+fn test_create_from_template() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("templates/default.md", "# {{title}}\n"),
+            (
+                "templates/design.md",
+                "# RFC {{number}}: {{title}}\n## Design\n",
+            ),
+            ("templates/process.org", "* {{title}}\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["templates"]);
+    assert_eq!(stdout(&output), "default\ndesign\nprocess\n");
+
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--no-checkout",
+            "--from-template",
+            "design",
+            "Caches",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "002-Caches", "002-Caches.md").unwrap(),
+        "# RFC 002: Caches\n## Design\n"
+    );
+
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--no-checkout",
+            "--from-template",
+            "process",
+            "Reviews",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "003-Reviews", "003-Reviews.org").unwrap(),
+        "* Reviews\n"
+    );
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Plain"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "004-Plain", "004-Plain.md").unwrap(),
+        "# Plain\n"
+    );
+}
+
+#[test]
+fn test_templates_validate() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("templates/default.md", "# {{title}}\n"),
+            ("templates/design.md", "# RFC {{num}}: {{title}}\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["templates", "--validate"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "default: OK\n\
+         design: has an unknown placeholder {{num}}, known ones are title, number, date\n"
+    );
+    assert!(
+        stderr(&output).contains("1 template(s) in"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_from_unknown_template() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("templates/design.md", "# {{title}}\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--from-template", "nope", "Caches"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("There is no template 'nope', available templates: design"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("002-Caches", git2::BranchType::Local)
+        .is_err());
+}
+
+#[test]
+fn test_create_with_number() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &["create", "--no-checkout", "--number", "100", "Reserved"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(file_on_branch(&repo, "100-Reserved", "100-Reserved.md").is_some());
+
+    let output = rfcs(
+        &home,
+        &["create", "--no-checkout", "--number", "100", "Again"],
+    );
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("RFC number 100 is already in use by branch 100-Reserved"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = rfcs(
+        &home,
+        &["create", "--no-checkout", "--number", "1", "Clash"],
+    );
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains(&format!(
+            "RFC number 001 is already in use by {}",
+            repo_path.join("001-first.md").display()
+        )),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("001-Clash", git2::BranchType::Local)
+        .is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_create_runs_post_create_hook() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let bin = temp_dir("bin");
+    let record = bin.join("record");
+    let hook = bin.join("hook");
+    std::fs::write(
+        &hook,
+        format!("#!/bin/sh\necho \"$@\" > {}\n", record.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let home = home_with_config(&format!(
+        "version = 1\n[repo]\npath = {:?}\n[hooks]\npost_create = \"{} {{number}} {{branch}} {{path}}\"\n",
+        repo_path.display().to_string(),
+        hook.display()
+    ));
+
+    let output = rfcs(&home, &["create", "--porcelain", "Hooked"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(&record).unwrap(),
+        format!(
+            "002 002-Hooked {}\n",
+            repo_path.join("002-Hooked.md").display()
+        )
+    );
+    assert_eq!(stdout(&output).lines().count(), 3);
+
+    // A failing hook warns, but the RFC is created all the same.
+    let home = home_with_config(&format!(
+        "version = 1\n[repo]\npath = {:?}\n[hooks]\npost_create = \"false\"\n",
+        repo_path.display().to_string()
+    ));
+    let output = rfcs(&home, &["create", "--no-checkout", "Unhooked"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("Warning: the post_create hook `false` failed"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("003-Unhooked", git2::BranchType::Local)
+        .is_ok());
+}
+
+#[test]
+fn test_create_long_and_colliding_titles() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-Caches.md", "# Caches\n")]);
+    let home = home_with_config(&format!(
+        "version = 1\nslug_max_length = 20\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--no-checkout",
+            "Why we should stop using caches everywhere",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(repo
+        .find_branch("002-Why-we-should-stop", git2::BranchType::Local)
+        .is_ok());
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Caches?"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("Another RFC is already named Caches, naming this one Caches-2"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(file_on_branch(&repo, "003-Caches-2", "003-Caches-2.md").is_some());
+}
+
+#[test]
+fn test_create_twice_with_the_same_title() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let branches = || {
+        let mut names: Vec<String> = repo
+            .branches(Some(git2::BranchType::Local))
+            .unwrap()
+            .map(|b| b.unwrap().0.name().unwrap().unwrap().to_string())
+            .filter(|name| name.contains("Caches"))
+            .collect();
+        names.sort();
+        names
+    };
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let output = rfcs(&home, &["create", "--no-checkout", "Caches"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("There already is an RFC branch 002-Caches"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(branches(), ["002-Caches"]);
+
+    let output = rfcs(&home, &["create", "--reuse", "--porcelain", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "number 002\nbranch 002-Caches\n");
+    assert_eq!(repo.head().unwrap().shorthand(), Some("002-Caches"));
+    assert_eq!(branches(), ["002-Caches"]);
+
+    let output = rfcs(&home, &["create", "--no-checkout", "--force-new", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(branches(), ["002-Caches", "003-Caches-2"]);
+}
+
+#[test]
+fn test_create_commit_author() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let xdg = home.display().to_string();
+    let author_of = |branch: &str| {
+        let commit = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap();
+        let author = commit.author();
+        format!("{} <{}>", author.name().unwrap(), author.email().unwrap())
+    };
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Configured"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        author_of("002-Configured"),
+        "Test Author <test@example.com>"
+    );
+
+    let mut config = repo.config().unwrap();
+    config.remove("user.name").unwrap();
+    config.remove("user.email").unwrap();
+    let env = [
+        ("XDG_CONFIG_HOME", xdg.as_str()),
+        ("GIT_AUTHOR_NAME", "Env Author"),
+        ("GIT_AUTHOR_EMAIL", "env@example.com"),
+    ];
+    let output = rfcs_env(&home, &env, &["create", "--no-checkout", "From env"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(author_of("003-From-env"), "Env Author <env@example.com>");
+
+    let output = rfcs_env(
+        &home,
+        &[("XDG_CONFIG_HOME", xdg.as_str())],
+        &["create", "--no-checkout", "Nobody"],
+    );
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Can't tell who to commit as"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_bases_on_the_remote_default_branch() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let develop = commit_on_branch(&repo, "develop", &[("002-second.md", "# Second\n")]);
+    repo.reference("refs/remotes/origin/develop", develop, false, "test")
+        .unwrap();
+    repo.reference_symbolic(
+        "refs/remotes/origin/HEAD",
+        "refs/remotes/origin/develop",
+        false,
+        "test",
+    )
+    .unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--no-checkout", "On develop"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let rfc = repo
+        .find_branch("002-On-develop", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(rfc.parent_id(0).unwrap(), develop);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_create_and_edit() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = temp_dir("bin");
+    let log = bin.join("log");
+    let editor = bin.join("editor");
+    std::fs::write(
+        &editor,
+        format!("#!/bin/sh\necho \"$@\" >> {}\n", log.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let editor = editor.display().to_string();
+
+    let output = rfcs_env(
+        &home,
+        &[("VISUAL", &editor)],
+        &["create", "--edit", "Straight to writing"],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        format!(
+            "{}\n",
+            repo_path.join("002-Straight-to-writing.md").display()
+        )
+    );
+}
+
+#[test]
+fn test_branch_name() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-Caches.md", "# Caches\n")]);
+    commit_on_branch(&repo, "004-in-flight", &[("notes.txt", "\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["branch-name", "Queues, revisited!"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "005-Queues-revisited\n");
+
+    let output = rfcs(&home, &["branch-name", "Caches"]);
+    assert_eq!(stdout(&output), "005-Caches-2\n");
+
+    let output = rfcs(&home, &["create", "--porcelain", "Queues, revisited!"]);
+    assert!(stdout(&output).contains("branch 005-Queues-revisited\n"));
+}
+
+#[test]
+fn test_create_with_underscore_separator() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-Queues.md", "# Queues\n"),
+            ("002_Caches.md", "# Caches\n"),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "version = 1\n[numbering]\nseparator = \"_\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list"]);
+    assert_eq!(stdout(&output), "001-Queues.md\n002_Caches.md\n");
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Streams"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(file_on_branch(&repo, "003_Streams", "003_Streams.md").is_some());
+
+    let output = rfcs(&home, &["branch-name", "Caches"]);
+    assert_eq!(stdout(&output), "004_Caches-2\n");
+
+    let output = rfcs(&home, &["configure", "numbering.separator", "/"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("use one of -_+"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_relative_template_and_hook_paths_resolve_against_repo() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("docs/README.md", "Not an RFC\n"),
+            ("meta/templates/default.md", "# {{title}}, from the repo\n"),
+            ("scripts/hook.sh", "#!/bin/sh\necho \"$1\" > hooked\n"),
+        ],
+    );
+    let hook = repo_path.join("scripts").join("hook.sh");
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let home = home_with_config(&format!(
+        "version = 1\ntemplates_dir = \"meta/templates\"\n[repo]\npath = {:?}\n\
+         [hooks]\npost_create = \"scripts/hook.sh {{number}}\"\n",
+        repo_path.display().to_string()
+    ));
+
+    // Run from elsewhere in the repository, neither path is relative to it.
+    let output = rfcs_in(
+        &home,
+        &repo_path.join("docs"),
+        &["create", "--no-checkout", "Caches"],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "002-Caches", "002-Caches.md").unwrap(),
+        "# Caches, from the repo\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("hooked")).unwrap(),
+        "002\n"
+    );
+}
+
+#[test]
+fn test_template_paths_relative_to_config() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_with_config(&format!(
+        "version = 1\ntemplates_dir = \"templates\"\npaths_relative_to = \"config\"\n\
+         [repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+    write_files(
+        &home.join(".config").join("rfcs"),
+        &[("templates/default.md", "# {{title}}, from the config\n")],
+    );
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Caches"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "002-Caches", "002-Caches.md").unwrap(),
+        "# Caches, from the config\n"
+    );
+}
+
+#[test]
+fn test_create_with_labels() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--no-checkout",
+            "--label",
+            "api",
+            "--tag",
+            "infra",
+            "Versioned endpoints",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(
+            &repo,
+            "002-Versioned-endpoints",
+            "002-Versioned-endpoints.md"
+        )
+        .unwrap(),
+        "---\ntitle: Versioned endpoints\nstatus: draft\nlabels: [api, infra]\n---\n\
+         # Versioned endpoints\n"
+    );
+
+    let output = rfcs(&home, &["create", "--label", "a,b", "Bad label"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("'a,b' can't be a label"));
+}
+
+#[test]
+fn test_create_with_status_and_author() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--no-checkout",
+            "--status",
+            "in-review",
+            "--author",
+            "Ada Lovelace",
+            "Caches",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "002-Caches", "002-Caches.md").unwrap(),
+        "---\ntitle: Caches\nstatus: in-review\nauthor: Ada Lovelace\n---\n# Caches\n"
+    );
+
+    let output = rfcs(&home, &["create", "--status", "finished", "Unknown status"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("'finished' is not a known status"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_interactive_needs_a_terminal() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    // The test's stdin isn't a terminal, so there's no asking for the title.
+    let output = rfcs(&home, &["create", "--interactive"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("needs a terminal"),
+        "{}",
+        stderr(&output)
+    );
+    let output = rfcs(&home, &["list"]);
+    assert_eq!(stdout(&output), "001-first.md\n");
+
+    // With everything given, there's nothing to ask.
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--interactive",
+            "--no-checkout",
+            "--status",
+            "draft",
+            "--author",
+            "Ada",
+            "--label",
+            "api",
+            "Caches",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "002-Caches", "002-Caches.md").unwrap(),
+        "---\ntitle: Caches\nstatus: draft\nauthor: Ada\nlabels: [api]\n---\n# Caches\n"
+    );
+}
+
+#[test]
+fn test_create_superseding_an_rfc() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\nstatus: accepted\n---\n# First\n"),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &[
+            "create",
+            "--no-checkout",
+            "--supersedes",
+            "1",
+            "--amends",
+            "2",
+            "--mark-superseded",
+            "Third",
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "003-Third", "003-Third.md").unwrap(),
+        "---\ntitle: Third\nstatus: draft\nsupersedes: 001\namends: 002\n---\n# Third\n"
+    );
+    assert_eq!(
+        file_on_branch(&repo, "003-Third", "001-first.md").unwrap(),
+        "---\nstatus: accepted\nsuperseded_by: 003\n---\n# First\n"
+    );
+    assert_eq!(
+        file_on_branch(&repo, "main", "001-first.md").unwrap(),
+        "---\nstatus: accepted\n---\n# First\n"
+    );
+    let tip = repo
+        .find_branch("003-Third", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(tip.summary(), Some("Mark RFC 001 as superseded by RFC 003"));
+}
+
+#[test]
+fn test_create_refuses_to_supersede_missing_rfc() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--supersedes", "7", "Second"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("There is no RFC numbered 007."),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("002-Second", git2::BranchType::Local)
+        .is_err());
+}
+
+/// A repository with RFCs in two directories, `a` with 001 and 002 and `b`
+/// with 001, configured with `extra` on top.
+fn two_rfc_dirs(extra: &str) -> (std::path::PathBuf, git2::Repository, std::path::PathBuf) {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("a/001-first.md", "# First\n"),
+            ("a/002-second.md", "# Second\n"),
+            ("b/001-other.md", "# Other\n"),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "version = 1\nrfc_dirs = [\"a\", \"b\"]\n{}[repo]\npath = {:?}\n",
+        extra,
+        repo_path.display().to_string()
+    ));
+    (repo_path, repo, home)
+}
+
+#[test]
+fn test_create_numbers_rfc_dirs_together() {
+    let (_, repo, home) = two_rfc_dirs("");
+
+    let output = rfcs(&home, &["next", "--dir", "b"]);
+    assert_eq!(stdout(&output), "003\n");
+
+    let output = rfcs(&home, &["create", "--no-checkout", "--dir", "b", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(file_on_branch(&repo, "003-Caches", "b/003-Caches.md").is_some());
+
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "004\n");
+
+    let output = rfcs(&home, &["next", "--dir", "c"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("c isn't one of the RFC directories in rfc_dirs: a, b"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_numbers_rfc_dirs_on_their_own() {
+    let (_, repo, home) = two_rfc_dirs("[numbering]\nper_dir = true\n");
+
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "003\n");
+    let output = rfcs(&home, &["next", "--dir", "b"]);
+    assert_eq!(stdout(&output), "002\n");
+
+    let output = rfcs(&home, &["create", "--no-checkout", "--dir", "b", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(file_on_branch(&repo, "002-Caches", "b/002-Caches.md").is_some());
+
+    // The new branch only counts for the directory its RFC is in.
+    let output = rfcs(&home, &["next", "--dir", "b"]);
+    assert_eq!(stdout(&output), "003\n");
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "003\n");
+
+    // The same number in both directories is no collision, and the listing
+    // says which directory each RFC is in.
+    let output = rfcs(
+        &home,
+        &[
+            "list",
+            "--strict",
+            "--format",
+            "csv",
+            "--fields",
+            "number,root,path",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "number,root,path\r\n\
+         001,a,a/001-first.md\r\n\
+         001,b,b/001-other.md\r\n\
+         002,a,a/002-second.md\r\n"
+    );
+}
+
+#[test]
+fn test_create_in_a_subdir() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("infra/002-second.md", "# Second\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--subdir", "api/v2", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(file_on_branch(&repo, "003-Caches", "api/v2/003-Caches.md").is_some());
+    assert!(repo_path.join("api/v2/003-Caches.md").is_file());
+
+    let output = rfcs(&home, &["list"]);
+    assert_eq!(
+        stdout(&output),
+        "001-first.md\ninfra/002-second.md\napi/v2/003-Caches.md\n"
+    );
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "004\n");
+
+    let output = rfcs(&home, &["create", "--subdir", "../elsewhere", "Queues"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("'../elsewhere' isn't a directory inside the RFC directory"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_create_file_only() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    // Branches don't count towards the number without them.
+    commit_on_branch(&repo, "005-elsewhere", &[]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--file-only", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!("Wrote {}\n", repo_path.join("002-Caches.md").display())
+    );
+    assert!(repo_path.join("002-Caches.md").is_file());
+    assert!(repo
+        .find_branch("002-Caches", git2::BranchType::Local)
+        .is_err());
+    assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+    let statuses = repo.statuses(None).unwrap();
+    assert!(statuses
+        .iter()
+        .any(|s| s.path() == Some("002-Caches.md") && s.status().is_wt_new()));
+
+    let output = rfcs(&home, &["create", "--file-only", "--no-commit", "Queues"]);
+    assert!(!output.status.success());
+}
+
+/// Whether `date` is written like 29.02.2024.
+fn is_day_month_year(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('.').collect();
+    parts.iter().map(|p| p.len()).eq([2, 2, 4])
+        && parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[test]
+fn test_template_date_uses_date_format() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            (
+                "templates/default.md",
+                "# {{title}}\n\nProposed on {{date}}.\n",
+            ),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["configure", "date_format", "%Y-%Q"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("'%Y-%Q' is not a date format"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(!global_config(&home).contains("date_format"));
+    let output = rfcs(&home, &["configure", "date_format", "%d.%m.%Y"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = rfcs(&home, &["create", "--no-checkout", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let content = file_on_branch(&repo, "002-Caches", "002-Caches.md").unwrap();
+    let date = content
+        .strip_prefix("# Caches\n\nProposed on ")
+        .and_then(|rest| rest.strip_suffix(".\n"))
+        .unwrap_or_else(|| panic!("{}", content));
+    assert!(is_day_month_year(date), "{}", date);
+}
+
+#[test]
+fn test_create_no_commit_stages_the_rfc() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let before = repo.head().unwrap().peel_to_commit().unwrap().id();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "--no-commit", "Caches"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("002-Caches.md, staged but not committed"));
+
+    assert_eq!(repo.head().unwrap().shorthand(), Some("002-Caches"));
+    assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), before);
+    assert!(file_on_branch(&repo, "002-Caches", "002-Caches.md").is_none());
+    assert!(repo_path.join("002-Caches.md").is_file());
+    let status = repo
+        .status_file(std::path::Path::new("002-Caches.md"))
+        .unwrap();
+    assert_eq!(status, git2::Status::INDEX_NEW);
+}
+
+#[test]
+fn test_create_refuses_in_the_middle_of_a_merge() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let other = commit_on_branch(&repo, "001-first", &[("001-first.md", "# Other\n")]);
+    // What `git merge` leaves behind when it stops at a conflict.
+    std::fs::write(repo_path.join(".git/MERGE_HEAD"), format!("{}\n", other)).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["create", "A new RFC"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains(
+            "The repository is in the middle of a merge. Finish it with `git commit`, \
+             or abort it with `git merge --abort`, first."
+        ),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("002-A-new-RFC", git2::BranchType::Local)
+        .is_err());
+
+    let output = rfcs(&home, &["--yes", "merge", "1"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("in the middle of a merge"));
+}
+
+#[test]
+fn test_create_cases_slugs_as_configured() {
+    for (case, branch) in [
+        ("keep", "002-A-New-RFC-(Draft)"),
+        ("lower", "002-a-new-rfc-(draft)"),
+        ("kebab", "002-a-new-rfc-draft"),
+    ] {
+        let repo_path = temp_dir("repo");
+        let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+        let home = home_with_config(&format!(
+            "version = 1\n[numbering]\ncase = {:?}\n[repo]\npath = {:?}\n",
+            case,
+            repo_path.display().to_string()
+        ));
+
+        let output = rfcs(&home, &["create", "A New RFC (Draft)"]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert!(
+            repo.find_branch(branch, git2::BranchType::Local).is_ok(),
+            "{} should create {}: {}",
+            case,
+            branch,
+            stdout(&output)
+        );
+        assert!(repo_path.join(format!("{}.md", branch)).exists());
+    }
+}
+
+#[test]
+fn test_create_copy_from() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            (
+                "001-caches.md",
+                "---\ntitle: Caches\nstatus: accepted\n---\n# Caches\n\nCache everything.\n",
+            ),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &["create", "--no-checkout", "--copy-from", "1", "Caches v2"],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "003-Caches-v2", "003-Caches-v2.md").unwrap(),
+        "---\ntitle: Caches v2\nstatus: draft\nderived_from: 001\n---\n\
+         # Caches v2\n\nCache everything.\n"
+    );
+
+    let output = rfcs(&home, &["create", "--copy-from", "7", "Caches v3"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("There is no RFC numbered 007."),
+        "{}",
+        stderr(&output)
+    );
+}
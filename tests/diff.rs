@@ -0,0 +1,57 @@
+mod common;
+
+use std::fs;
+
+use common::*;
+
+#[test]
+fn test_diff_colors_additions_deletions_and_hunks() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\nOld line\n")]);
+    fs::write(repo_path.join("001-first.md"), "# First\nNew line\n").unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["--color", "always", "diff", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let colored = stdout(&output);
+    assert!(
+        colored.contains("\x1b[36m@@ -1,2 +1,2 @@\x1b[0m\n"),
+        "{}",
+        colored
+    );
+    assert!(
+        colored.contains("\n # First\n\x1b[31m-Old line\x1b[0m\n\x1b[32m+New line\x1b[0m\n"),
+        "{}",
+        colored
+    );
+
+    let output = rfcs(&home, &["--color", "never", "diff", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let plain = stdout(&output);
+    assert!(
+        plain.contains("@@ -1,2 +1,2 @@\n # First\n-Old line\n+New line\n"),
+        "{}",
+        plain
+    );
+    assert!(plain.starts_with("diff --git a/001-first.md b/001-first.md\n"));
+    assert!(!plain.contains('\x1b'), "{}", plain);
+}
+
+#[test]
+fn test_diff_from_a_revision() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    repo.tag_lightweight("v1", &repo.revparse_single("HEAD").unwrap(), false)
+        .unwrap();
+    write_files(&repo_path, &[("001-first.md", "# First\nMore\n")]);
+    commit_all(&repo, "Expand RFC 001");
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["diff", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md is the same as on main\n");
+
+    let output = rfcs(&home, &["diff", "--from", "v1", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).ends_with("@@ -1 +1,2 @@\n # First\n+More\n"));
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_doctor_healthy_repo() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["doctor"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    let report = stdout(&output);
+    assert!(
+        report.lines().all(|line| line.starts_with("[ok]")),
+        "{}",
+        report
+    );
+    assert!(report.contains("RFC branches are based on 'main'"));
+    assert!(report.contains("2 RFCs, numbered 001 to 002"));
+}
+
+#[test]
+fn test_doctor_reports_failures() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[("001-first.md", "# First\n"), ("003-third.md", "# Third\n")],
+    );
+    let home = home_with_config(&format!(
+        "[repo]\npath = {:?}\n[git]\ndefault_branch = \"trunk\"\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["doctor"]);
+
+    assert!(!output.status.success());
+    let report = stdout(&output);
+    assert!(
+        report.contains("[fail] The branch 'trunk' to base RFCs on doesn't exist"),
+        "{}",
+        report
+    );
+    assert!(report.contains("[warn] No RFCs numbered 002"), "{}", report);
+    assert!(stderr(&output).contains("1 of "), "{}", stderr(&output));
+}
+
+#[test]
+fn test_doctor_reports_missing_repo_and_broken_config() {
+    let home = home_with_config("[repo]\npath = \"/does/not/exist\"\n");
+
+    let output = rfcs(&home, &["doctor"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("[fail] repo.path /does/not/exist doesn't exist"));
+
+    let home = home_with_config("[git\n");
+    let output = rfcs(&home, &["doctor"]);
+    assert!(!output.status.success());
+    assert!(
+        stdout(&output).contains("[fail] Failed to parse config file"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_doctor_color_choice() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    // Piped, so `auto` wouldn't color; `always` does anyway.
+    let output = rfcs(&home, &["--color", "always", "doctor"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).starts_with("\x1b[32m[ok]  \x1b[0m "),
+        "{:?}",
+        stdout(&output)
+    );
+
+    for args in [&["--color", "never", "doctor"][..], &["doctor"]] {
+        let output = rfcs(&home, args);
+        assert!(!stdout(&output).contains('\x1b'), "{:?}", stdout(&output));
+    }
+}
+
+#[test]
+fn test_doctor_fix_repads_numbers_and_fills_in_front_matter() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("0001-first.md", "# First\n"),
+            ("002-second.MD", "# Second\n"),
+            ("003-third.md", "---\nauthor: Jane\n---\n# Third\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["doctor", "--fix", "--commit"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let report = stdout(&output);
+    assert!(
+        report.contains("[warn] 0001-first.md should be named 001-first.md"),
+        "{}",
+        report
+    );
+    assert!(
+        report.contains("Fixed: Rename 0001-first.md to 001-first.md"),
+        "{}",
+        report
+    );
+    assert!(!repo_path.join("0001-first.md").exists());
+    assert!(repo_path.join("002-second.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("003-third.md")).unwrap(),
+        "---\nauthor: Jane\ntitle: Third\nstatus: draft\n---\n# Third\n"
+    );
+    assert_eq!(
+        file_on_branch(&repo, "main", "001-first.md").as_deref(),
+        Some("# First\n")
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(
+        head.summary(),
+        Some("Add title, status to the front matter of 003-third.md")
+    );
+    assert!(repo.statuses(None).unwrap().is_empty());
+
+    let output = rfcs(&home, &["doctor", "--fix"]);
+    assert!(
+        stdout(&output).contains("Nothing to fix."),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_doctor_fix_leaves_unfixable_repos_alone() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("0001-first.md", "# Also first\n"),
+            ("004-fourth.md", "# Fourth\n"),
+        ],
+    );
+    let head = repo.head().unwrap().target();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["doctor", "--fix", "--commit"]);
+
+    let report = stdout(&output);
+    assert!(
+        report.contains("0001-first.md should be named 001-first.md, which is taken"),
+        "{}",
+        report
+    );
+    assert!(report.contains("Nothing to fix."), "{}", report);
+    assert_eq!(repo.head().unwrap().target(), head);
+    assert!(repo.statuses(None).unwrap().is_empty());
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("0001-first.md")).unwrap(),
+        "# Also first\n"
+    );
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::*;
+
+#[cfg(unix)]
+#[test]
+fn test_edit_editor_precedence() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Launchers that record who was run, with which arguments.
+    let bin = temp_dir("bin");
+    let log = bin.join("log");
+    for name in ["fromenv", "fromconfig", "fromflag"] {
+        let launcher = bin.join(name);
+        std::fs::write(
+            &launcher,
+            format!("#!/bin/sh\necho {} \"$@\" >> {}\n", name, log.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&launcher, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_with_config(&format!(
+        "version = 1\neditor = \"{} --wait\"\n[repo]\npath = {:?}\n",
+        bin.join("fromconfig").display(),
+        repo_path.display().to_string()
+    ));
+    let from_env = bin.join("fromenv").display().to_string();
+    let env = [("VISUAL", ""), ("EDITOR", from_env.as_str())];
+    let file = repo_path.join("001-first.md");
+
+    let output = rfcs_env(&home, &env, &["edit", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let flag = format!("{} -n", bin.join("fromflag").display());
+    let output = rfcs_env(&home, &env, &["edit", "--editor", &flag, "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        format!(
+            "fromconfig --wait {}\nfromflag -n {}\n",
+            file.display(),
+            file.display()
+        )
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_create_if_missing() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = temp_dir("bin");
+    let log = bin.join("log");
+    let editor = bin.join("editor");
+    std::fs::write(
+        &editor,
+        format!("#!/bin/sh\necho \"$@\" >> {}\n", log.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let editor = editor.display().to_string();
+
+    let output = rfcs(&home, &["edit", "--editor", &editor, "Caches"]);
+    assert!(!output.status.success());
+    assert!(!log.exists());
+
+    let output = rfcs(
+        &home,
+        &["edit", "--create-if-missing", "--editor", &editor, "Caches"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    let file = repo_path.join("002-Caches.md");
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        format!("{}\n", file.display())
+    );
+    assert!(file_on_branch(&repo, "002-Caches", "002-Caches.md").is_some());
+
+    // Once it exists, it's edited like any other.
+    let output = rfcs(
+        &home,
+        &["edit", "--create-if-missing", "--editor", &editor, "Caches"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        format!("{}\n{}\n", file.display(), file.display())
+    );
+}
+
+#[test]
+fn test_edit_failing_editor() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["edit", "--editor", "false", "1"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Editor 'false' exited"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_edit_missing_editor() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["edit", "--editor", "no-such-editor --wait", "1"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains(
+            "Editor 'no-such-editor' not found. Set $VISUAL or $EDITOR, or run \
+             `rfcs configure editor <command>`."
+        ),
+        "{}",
+        stderr(&output)
+    );
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_export_orders_rfcs_with_table_of_contents() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            (
+                "docs/002-queues.md",
+                "---\ntitle: Queues\nstatus: accepted\n---\n# Queues\nAre good.\n",
+            ),
+            (
+                "001-caches.md",
+                "---\ntitle: Caches\nstatus: accepted\n---\n# Caches\nAre bad.\n",
+            ),
+            ("003-draft.md", "# Unfinished\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+    let output_file = temp_dir("export").join("rfcs.md");
+
+    let output = rfcs(
+        &home,
+        &[
+            "export",
+            "--status",
+            "accepted",
+            "--output",
+            output_file.to_str().unwrap(),
+        ],
+    );
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Exported 2 RFCs to"));
+    let document = std::fs::read_to_string(&output_file).unwrap();
+    assert!(document
+        .starts_with("# RFCs\n\n- [RFC 001: Caches](#rfc-001)\n- [RFC 002: Queues](#rfc-002)\n"));
+    let caches = document.find("# Caches\nAre bad.").unwrap();
+    let queues = document.find("# Queues\nAre good.").unwrap();
+    assert!(caches < queues);
+    assert!(!document.contains("status: accepted"));
+    assert!(!document.contains("Unfinished"));
+
+    let output = rfcs(&home, &["export", "--status", "Accepted"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        !stdout(&output).contains("Unfinished"),
+        "{}",
+        stdout(&output)
+    );
+    assert!(stdout(&output).contains("# Queues"), "{}", stdout(&output));
+
+    let output = rfcs(&home, &["export", "--from", "3"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("- [RFC 003: Unfinished](#rfc-003)\n"));
+}
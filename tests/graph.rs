@@ -0,0 +1,46 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_graph_of_superseded_and_amended_rfcs() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            (
+                "001-caches.md",
+                "---\ntitle: Caches\nsuperseded_by: 003\n---\n",
+            ),
+            ("002-queues.md", "# Queues\n"),
+            (
+                "003-caches-revisited.md",
+                "---\ntitle: Caches, revisited\nsupersedes: 001\namends: [002]\n---\n",
+            ),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["graph"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "digraph rfcs {\n\
+         \x20   rfc001 [label=\"001: Caches\"];\n\
+         \x20   rfc002 [label=\"002: Queues\"];\n\
+         \x20   rfc003 [label=\"003: Caches, revisited\"];\n\
+         \x20   rfc003 -> rfc001 [label=\"supersedes\"];\n\
+         \x20   rfc003 -> rfc002 [label=\"amends\"];\n\
+         }\n"
+    );
+
+    let output = rfcs(&home, &["graph", "--format", "mermaid"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("    rfc003 -->|amends| rfc002\n"),
+        "{}",
+        stdout(&output)
+    );
+}
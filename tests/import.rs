@@ -0,0 +1,57 @@
+mod common;
+
+use std::fs;
+
+use common::*;
+
+#[test]
+fn test_import_adopts_a_document_as_the_next_rfc() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let docs = temp_dir("docs");
+    let design = docs.join("design.md");
+    fs::write(&design, "# Caching design\n\nWe should cache.\n").unwrap();
+
+    let output = rfcs(&home, &["import", design.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!("Imported {} as 002-Caching-design.md\n", design.display())
+    );
+    assert!(!design.exists());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("002-Caching-design.md")).unwrap(),
+        "# Caching design\n\nWe should cache.\n"
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message(), Some("Import RFC 002: Caching design"));
+    assert!(head
+        .tree()
+        .unwrap()
+        .get_path(std::path::Path::new("002-Caching-design.md"))
+        .is_ok());
+}
+
+#[test]
+fn test_import_refuses_what_isnt_a_text_document() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+    let docs = temp_dir("docs");
+    let slides = docs.join("slides.pdf");
+    fs::write(&slides, b"%PDF-1.7\n\0\x01").unwrap();
+    let notes = docs.join("notes.md");
+    fs::write(&notes, b"# Notes\n\xff\xfe").unwrap();
+
+    for document in [&slides, &notes] {
+        let output = rfcs(&home, &["import", document.to_str().unwrap()]);
+        assert!(!output.status.success());
+        assert!(
+            stderr(&output).contains("isn't a text document"),
+            "{}",
+            stderr(&output)
+        );
+        assert!(document.exists());
+    }
+}
@@ -0,0 +1,1402 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::*;
+
+#[test]
+fn test_list_count() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.org", "* Second\n"),
+            ("docs/003-third.txt", "Third\n"),
+            ("README.md", "Not an RFC\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--count"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "3\n");
+}
+
+#[test]
+fn test_list_count_rejects_format() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--count", "--format", "json"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("cannot be used with"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_number_range() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("099-before.md", ""),
+            ("100-start.md", ""),
+            ("150-middle.md", ""),
+            ("199-end.md", ""),
+            ("200-after.md", ""),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--from", "100", "--to", "199"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let mut listed: Vec<String> = stdout(&output)
+        .lines()
+        .map(|l| l.rsplit('/').next().unwrap().to_string())
+        .collect();
+    listed.sort();
+    assert_eq!(listed, ["100-start.md", "150-middle.md", "199-end.md"]);
+}
+
+#[test]
+fn test_list_null_separated() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[("001-first rfc.md", ""), ("002-second rfc.md", "")],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "-0"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!output.stdout.contains(&b'\n'));
+    assert_eq!(output.stdout.last(), Some(&b'\0'));
+    let mut entries: Vec<&[u8]> = output.stdout[..output.stdout.len() - 1]
+        .split(|b| *b == b'\0')
+        .collect();
+    entries.sort();
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].ends_with(b"001-first rfc.md"));
+    assert!(entries[1].ends_with(b"002-second rfc.md"));
+}
+
+#[test]
+fn test_list_and_next_scoped_to_rfc_dir() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("docs/rfcs/001-first.md", "# First\n"),
+            ("docs/rfcs/002-second.md", "# Second\n"),
+            ("notes/900-meeting.md", "Not an RFC\n"),
+            ("104-changelog.md", "Not an RFC either\n"),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "rfc_dir = \"docs/rfcs\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let listed: Vec<String> = stdout(&output).lines().map(String::from).collect();
+    assert_eq!(listed.len(), 2, "{:?}", listed);
+    assert!(listed.iter().all(|f| f.starts_with("docs/rfcs/")));
+
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "003\n");
+
+    let output = rfcs(&home, &["create", "--porcelain", "Third"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains(&format!(
+        "path {}",
+        repo_path.join("docs/rfcs/003-Third.md").display()
+    )));
+}
+
+#[test]
+fn test_list_porcelain_is_stable() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("010-later.md", "---\nstatus: accepted\n---\n# Later\n"),
+            ("docs/002-second.org", "* Second\n"),
+            ("001-first.md", "---\ntitle: First\nstatus: draft\n---\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "porcelain"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let root = repo_path.display();
+    assert_eq!(
+        output.stdout,
+        format!(
+            "001\tdraft\t{root}/001-first.md\n\
+             002\t\t{root}/docs/002-second.org\n\
+             010\taccepted\t{root}/010-later.md\n"
+        )
+        .into_bytes()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_list_symlink_policy() {
+    use std::os::unix::fs::symlink;
+
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let outside = temp_dir("outside");
+    write_files(&outside, &[("003-shared.md", "# Shared\n")]);
+    symlink(
+        repo_path.join("001-first.md"),
+        repo_path.join("002-link.md"),
+    )
+    .unwrap();
+    symlink(&outside, repo_path.join("shared")).unwrap();
+    symlink(&repo_path, repo_path.join("loop")).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+
+    let output = rfcs(&home, &["--follow-symlinks", "list", "--count"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "3\n");
+    assert!(stderr(&output).contains("loop"), "{}", stderr(&output));
+}
+
+#[test]
+fn test_list_tree() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\nstatus: accepted\n---\n"),
+            ("docs/002-second.md", "---\nstatus: draft\n---\n"),
+            ("docs/old/003-third.md", "---\nstatus: accepted\n---\n"),
+            ("docs/old/004-fourth.md", "# No front matter\n"),
+            ("zeta/005-fifth.md", "---\nstatus: draft\n---\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--tree"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "001-first.md\n\
+         docs/\n  002-second.md\n  old/\n    003-third.md\n    004-fourth.md\n\
+         zeta/\n  005-fifth.md\n"
+    );
+
+    let output = rfcs(&home, &["list", "--tree", "--group-by", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "(no status)\n  docs/old/004-fourth.md\n\
+         accepted\n  001-first.md\n  docs/old/003-third.md\n\
+         draft\n  docs/002-second.md\n  zeta/005-fifth.md\n"
+    );
+}
+
+#[test]
+fn test_list_bare_repo_explains() {
+    let repo_path = temp_dir("repo");
+    git2::Repository::init_bare(&repo_path).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("is a bare repository"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_committed_matches_clean_checkout() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\nstatus: accepted\n---\n# First\n"),
+            ("docs/002-second.md", "---\nstatus: draft\n---\n# Second\n"),
+            ("docs/old/003-third.org", "* Third\n"),
+            ("notes.txt", "not an RFC\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    for args in [
+        &["list"][..],
+        &["list", "--format", "porcelain"],
+        &["list", "--tree", "--group-by", "status"],
+    ] {
+        let on_disk = rfcs(&home, args);
+        let committed = rfcs(&home, &[args, &["--committed"]].concat());
+        assert!(committed.status.success(), "{}", stderr(&committed));
+        let mut on_disk: Vec<String> = stdout(&on_disk).lines().map(String::from).collect();
+        let mut committed: Vec<String> = stdout(&committed).lines().map(String::from).collect();
+        if args == ["list"] {
+            on_disk.sort();
+            committed.sort();
+        }
+        assert_eq!(committed, on_disk);
+    }
+}
+
+#[test]
+fn test_list_committed_in_bare_repo() {
+    let source_path = temp_dir("source");
+    init_repo(
+        &source_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    let repo_path = temp_dir("bare");
+    git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(source_path.to_str().unwrap(), &repo_path)
+        .unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--committed", "--tree"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n002-second.md\n");
+}
+
+#[test]
+fn test_list_modified() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "# Second\n"),
+            ("003-third.md", "# Third\n"),
+        ],
+    );
+    std::fs::write(repo_path.join("002-second.md"), "# Second, edited\n").unwrap();
+    std::fs::write(repo_path.join("004-fourth.md"), "# Untracked\n").unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--modified"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "002-second.md\n");
+    let output = rfcs(&home, &["list", "--modified", "--from", "3"]);
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_list_csv_quotes_titles() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            (
+                "001-first.md",
+                "---\ntitle: Caches, \"revisited\"\nstatus: draft\n---\n",
+            ),
+            ("002-second.md", "---\ntitle: Plain\n---\n"),
+            ("003-third.md", "# No front matter\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "csv", "--to", "2"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "number,title,status,path\r\n\
+         001,\"Caches, \"\"revisited\"\"\",draft,001-first.md\r\n\
+         002,Plain,,002-second.md\r\n"
+    );
+}
+
+#[test]
+fn test_list_json_compact_and_pretty() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\ntitle: First\nstatus: draft\n---\n"),
+            ("002-second.md", "# No front matter\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "[{\"number\":1,\"path\":\"001-first.md\",\"status\":\"draft\",\"title\":\"First\"},\
+         {\"number\":2,\"path\":\"002-second.md\"}]\n"
+    );
+
+    let output = rfcs(&home, &["list", "--format", "json", "--pretty"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let pretty = stdout(&output);
+    assert!(
+        pretty.starts_with("[\n  {\n    \"number\": 1,\n"),
+        "{}",
+        pretty
+    );
+    assert_eq!(pretty.lines().count(), 12);
+
+    let output = rfcs(&home, &["list", "--pretty"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--pretty only applies to --format json"));
+}
+
+#[test]
+fn test_list_json_envelope() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "---\ntitle: First\n---\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "json", "--envelope"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "{\"rfcs\":[{\"number\":1,\"path\":\"001-first.md\",\"title\":\"First\"}],\"version\":1}\n"
+    );
+
+    let output = rfcs(&home, &["list", "--format", "json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!stdout(&output).contains("version"));
+    assert!(stdout(&output).starts_with('['));
+
+    let output = rfcs(&home, &["list", "--envelope"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--envelope only applies to --format json"));
+}
+
+#[test]
+fn test_list_format_is_checked_when_parsing() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "yaml"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("invalid value 'yaml' for '--format <FORMAT>'"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(
+        stderr(&output).contains("[possible values: human, porcelain, csv, json, ndjson, table]"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = rfcs(&home, &["list", "--format", "JSON"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "[{\"number\":1,\"path\":\"001-first.md\"}]\n"
+    );
+}
+
+#[test]
+fn test_list_ndjson() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\ntitle: First\nstatus: draft\n---\n"),
+            ("002-second.md", "# No front matter\n"),
+            ("003-third.md", "---\ntitle: Caches, \"revisited\"\n---\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "ndjson"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "{\"number\":1,\"path\":\"001-first.md\",\"status\":\"draft\",\"title\":\"First\"}",
+            "{\"number\":2,\"path\":\"002-second.md\"}",
+            "{\"number\":3,\"path\":\"003-third.md\",\"title\":\"Caches, \\\"revisited\\\"\"}",
+        ]
+    );
+    for line in lines {
+        assert!(line.starts_with('{') && line.ends_with('}'), "{}", line);
+    }
+
+    let output = rfcs(&home, &["list", "--format", "ndjson", "--fields", "number"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "{\"number\":1}\n{\"number\":2}\n{\"number\":3}\n"
+    );
+}
+
+#[test]
+fn test_list_fields() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\ntitle: First\nauthor: Ada\n---\n"),
+            ("002-second.md", "---\ntitle: Second\n---\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &["list", "--format", "csv", "--fields", "path,author,number"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "path,author,number\r\n001-first.md,Ada,001\r\n002-second.md,,002\r\n"
+    );
+
+    let output = rfcs(
+        &home,
+        &["list", "--format", "json", "--fields", "author,title"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "[{\"author\":\"Ada\",\"title\":\"First\"},{\"title\":\"Second\"}]\n"
+    );
+
+    let output = rfcs(
+        &home,
+        &["list", "--format", "csv", "--fields", "number,owner"],
+    );
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("[possible values: number, title, status, author, path, root]"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_warns_about_number_collisions() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("042-foo.md", "# Foo\n"),
+            ("042-bar.md", "# Bar\n"),
+            ("043-baz.md", "# Baz\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--count"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "3\n");
+    assert_eq!(
+        stderr(&output),
+        "Warning: RFC 042 is used by more than one file: 042-bar.md, 042-foo.md\n"
+    );
+
+    let output = rfcs(&home, &["list", "--from", "43"]);
+    assert_eq!(stderr(&output), "");
+
+    let output = rfcs(&home, &["list", "--strict"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("RFC 042 is used by more than one file: 042-bar.md, 042-foo.md"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_list_shows_an_rfc_in_two_formats_once() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("042-foo.md", "# Foo\n"),
+            ("042-foo.org", "* Foo\n"),
+            ("043-bar.org", "* Bar\n"),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "version = 1\npreferred_extensions = [\"org\", \"md\"]\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "042-foo.org\n043-bar.org\n");
+    assert_eq!(stderr(&output), "");
+
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "044\n");
+
+    let output = rfcs(&home_for_repo(&repo_path), &["list", "--count"]);
+    assert_eq!(stdout(&output), "3\n");
+}
+
+#[test]
+fn test_list_pages_with_offset_and_limit() {
+    let repo_path = temp_dir("repo");
+    let files: Vec<(String, String)> = (1..=7)
+        .map(|n| (format!("{:03}-rfc.md", n), format!("# RFC {}\n", n)))
+        .collect();
+    let files: Vec<(&str, &str)> = files
+        .iter()
+        .map(|(name, content)| (name.as_str(), content.as_str()))
+        .collect();
+    init_repo(&repo_path, &files);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--offset", "2", "--limit", "3"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "003-rfc.md\n004-rfc.md\n005-rfc.md\n");
+
+    let output = rfcs(
+        &home,
+        &[
+            "list", "--format", "json", "--fields", "number", "--offset", "2", "--limit", "3",
+        ],
+    );
+    assert_eq!(
+        stdout(&output),
+        "[{\"number\":3},{\"number\":4},{\"number\":5}]\n"
+    );
+
+    let output = rfcs(
+        &home,
+        &[
+            "list", "--format", "ndjson", "--fields", "number", "--offset", "5",
+        ],
+    );
+    assert_eq!(stdout(&output), "{\"number\":6}\n{\"number\":7}\n");
+
+    let output = rfcs(&home, &["list", "--offset", "10"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_list_watch_without_a_terminal_lists_once() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--watch"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+}
+
+#[test]
+fn test_list_count_by() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-a.md", "---\nstatus: draft\n---\n"),
+            ("002-b.md", "---\nstatus: accepted\n---\n"),
+            ("003-c.md", "---\nstatus: draft\n---\n"),
+            ("004-d.md", "# No front matter\n"),
+            ("105-e.md", "---\nstatus: accepted\n---\n"),
+            ("106-f.md", "---\nstatus: draft\n---\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--count-by", "status"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "draft\t3\naccepted\t2\n(none)\t1\n");
+
+    let output = rfcs(&home, &["list", "--count-by", "number-prefix"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "0xx\t4\n1xx\t2\n");
+}
+
+#[test]
+fn test_list_path_forms() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("docs/001-first.md", "# First\n")]);
+    let fixtures = repo_path.parent().unwrap().to_path_buf();
+    let relative_repo = repo_path.file_name().unwrap().to_str().unwrap();
+    let home = home_with_config(&format!(
+        "version = 1\n[repo]\npath = {:?}\n",
+        relative_repo
+    ));
+
+    let output = rfcs_in(&home, &fixtures, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "docs/001-first.md\n");
+
+    let output = rfcs_in(&home, &fixtures, &["list", "--relative"]);
+    assert_eq!(stdout(&output), "docs/001-first.md\n");
+
+    let output = rfcs_in(&home, &fixtures, &["list", "--absolute"]);
+    assert_eq!(
+        stdout(&output),
+        format!("{}\n", repo_path.join("docs/001-first.md").display())
+    );
+
+    let output = rfcs_in(&home, &fixtures, &["list", "--format", "porcelain"]);
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "001\t\t{}\n",
+            Path::new(relative_repo).join("docs/001-first.md").display()
+        )
+    );
+}
+
+#[test]
+fn test_list_added_on_branch() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_on_branch(
+        &repo,
+        "003-third",
+        &[
+            ("003-third.md", "---\nstatus: draft\n---\n# Third\n"),
+            ("notes.txt", "notes\n"),
+        ],
+    );
+    // Added on the default branch after the RFC branch split off.
+    std::fs::write(repo_path.join("002-second.md"), "# Second\n").unwrap();
+    commit_all(&repo, "Second RFC");
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--added-on", "003-third"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "003-third.md\n");
+
+    let output = rfcs(
+        &home,
+        &["list", "--added-on", "003-third", "--format", "porcelain"],
+    );
+    assert_eq!(
+        stdout(&output),
+        format!("003\tdraft\t{}\n", repo_path.join("003-third.md").display())
+    );
+
+    let output = rfcs(&home, &["list", "--added-on", "main"]);
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_list_changed_in_range() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    std::fs::write(repo_path.join("002-second.md"), "# Second\n").unwrap();
+    let start = commit_all(&repo, "Second RFC");
+    write_files(
+        &repo_path,
+        &[
+            ("001-first.md", "# First, amended\n"),
+            ("003-third.md", "# Third\n"),
+            ("notes.txt", "notes\n"),
+        ],
+    );
+    commit_all(&repo, "Amend the first RFC, add the third");
+    std::fs::write(repo_path.join("004-fourth.md"), "# Fourth\n").unwrap();
+    let end = commit_all(&repo, "Fourth RFC");
+    std::fs::write(repo_path.join("005-fifth.md"), "# Fifth\n").unwrap();
+    commit_all(&repo, "Fifth RFC");
+    let home = home_for_repo(&repo_path);
+
+    let range = format!("{}..{}", start, end);
+    let output = rfcs(&home, &["list", "--changed-in", &range]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "001-first.md\n003-third.md\n004-fourth.md\n"
+    );
+
+    let output = rfcs(&home, &["list", "--changed-in", "nope..HEAD"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Can't find the revisions of range 'nope..HEAD'"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = rfcs(&home, &["list", "--changed-in", "HEAD"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("'HEAD' is not a range of commits"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_since_tag() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let tagged = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.tag_lightweight("v1.0", tagged.as_object(), false)
+        .unwrap();
+    write_files(
+        &repo_path,
+        &[
+            ("001-first.md", "# First, amended\n"),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    commit_all(&repo, "Amend the first RFC, add the second");
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--since-tag", "v1.0"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "002-second.md\n");
+
+    let output = rfcs(&home, &["list", "--since-tag", "v2.0"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("There's no tag 'v2.0'"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_json_with_git() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("README.txt", "Proposals\n")]);
+    write_files(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_all_at(&repo, "Add the first", 1_700_000_000);
+    write_files(&repo_path, &[("001-first.md", "# First\n\nMore.\n")]);
+    let ada =
+        git2::Signature::new("Ada", "ada@example.com", &git2::Time::new(1_700_200_000, 0)).unwrap();
+    commit_all_as(&repo, "Revise the first", &ada);
+    write_files(&repo_path, &[("002-second.md", "# Second\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &[
+            "list",
+            "--format",
+            "ndjson",
+            "--fields",
+            "number",
+            "--with-git",
+        ],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "{\"commits\":2,\"first_commit\":\"2023-11-14\",\"last_author\":\"Ada\",\
+         \"last_commit\":\"2023-11-17\",\"number\":1}\n\
+         {\"number\":2}\n"
+    );
+
+    let output = rfcs(&home, &["list", "--format", "json", "--with-git"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("\"last_author\":\"Ada\""));
+
+    let output = rfcs(&home, &["list", "--format", "json"]);
+    assert!(!stdout(&output).contains("commit"), "{}", stdout(&output));
+
+    let output = rfcs(&home, &["list", "--with-git"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--with-git only applies to --format json and ndjson."));
+}
+
+#[test]
+fn test_list_sort() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("README.txt", "Proposals\n")]);
+    write_files(
+        &repo_path,
+        &[
+            ("001-zebra.md", "---\ntitle: Zebra\n---\n"),
+            ("002-apple.md", "---\ntitle: apple\n---\n"),
+        ],
+    );
+    commit_all_at(&repo, "First RFCs", 1_700_000_100);
+    write_files(&repo_path, &[("003-mango.md", "---\ntitle: Mango\n---\n")]);
+    commit_all_at(&repo, "Third RFC", 1_700_000_200);
+    write_files(
+        &repo_path,
+        &[("001-zebra.md", "---\ntitle: Zebra\n---\nMore.\n")],
+    );
+    commit_all_at(&repo, "Revise the first", 1_700_000_300);
+    let home = home_for_repo(&repo_path);
+
+    for (sort, expected) in [
+        ("number", "001-zebra.md\n002-apple.md\n003-mango.md\n"),
+        ("modified", "001-zebra.md\n003-mango.md\n002-apple.md\n"),
+        ("created", "003-mango.md\n001-zebra.md\n002-apple.md\n"),
+        ("title", "002-apple.md\n003-mango.md\n001-zebra.md\n"),
+    ] {
+        let output = rfcs(&home, &["list", "--sort", sort]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), expected, "--sort {}", sort);
+    }
+
+    // Uncommitted changes are newer than any commit.
+    write_files(
+        &repo_path,
+        &[("002-apple.md", "---\ntitle: apple\n---\nEdited.\n")],
+    );
+    let output = rfcs(&home, &["list", "--sort", "modified"]);
+    assert_eq!(
+        stdout(&output),
+        "002-apple.md\n001-zebra.md\n003-mango.md\n"
+    );
+}
+
+#[test]
+fn test_list_with_branch() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", "# First\n"), ("003-third.md", "# Third\n")],
+    );
+    commit_on_branch(&repo, "003-third", &[("003-third.md", "# Third\nMore.\n")]);
+    commit_on_branch(&repo, "002-second", &[("002-second.md", "# Second\n")]);
+    commit_on_branch(&repo, "scratch", &[("notes.txt", "\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--with-branch"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "001-first.md\tfile only\n\
+         003-third.md\tfile and branch\n\
+         002-second\tbranch only\n"
+    );
+}
+
+#[test]
+fn test_list_exists_only() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", "# First\n"), ("003-third.md", "# Third\n")],
+    );
+    commit_on_branch(&repo, "002-second", &[("notes.txt", "\n")]);
+    // Committed, but deleted from the working directory since.
+    fs::remove_file(repo_path.join("003-third.md")).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--with-branch", "--exists-only"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\tfile only\n");
+
+    let output = rfcs(&home, &["list", "--committed"]);
+    assert_eq!(stdout(&output), "001-first.md\n003-third.md\n");
+    let output = rfcs(&home, &["list", "--committed", "--exists-only"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n");
+}
+
+#[test]
+fn test_list_max_depth() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-shallow.md", "# Shallow\n"),
+            ("a/b/003-deep.md", "# Deep\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-shallow.md\na/b/003-deep.md\n");
+
+    let output = rfcs(&home, &["list", "--max-depth", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-shallow.md\n");
+
+    let home = home_with_config(&format!(
+        "version = 1\nmax_depth = 1\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-shallow.md\n");
+    let output = rfcs(&home, &["list", "--max-depth", "3"]);
+    assert_eq!(stdout(&output), "001-shallow.md\na/b/003-deep.md\n");
+}
+
+#[test]
+fn test_list_fail_on_empty() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-api.md", "---\nlabels: api\n---\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--label", "nonexistent"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+
+    let output = rfcs(
+        &home,
+        &["list", "--label", "nonexistent", "--fail-on-empty"],
+    );
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert!(
+        stderr(&output).contains("No RFCs match"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = rfcs(&home, &["list", "--label", "api", "--fail-on-empty"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-api.md\n");
+}
+
+#[test]
+fn test_list_numbers_only() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("012-twelve.md", "---\nlabels: api\n---\n"),
+            ("docs/003-three.md", "# Three\n"),
+            ("101-hundred-one.md", "---\nlabels: api\n---\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--numbers-only"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "003\n012\n101\n");
+
+    let output = rfcs(&home, &["list", "--numbers-only=raw", "--sort", "title"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "3\n12\n101\n");
+
+    let output = rfcs(&home, &["list", "--numbers-only", "--label", "api"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "012\n101\n");
+}
+
+#[test]
+fn test_list_pattern() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("RFC-7-caching.md", "# Caching\n"),
+            ("RFC-12-queues.md", "# Queues\n"),
+            ("notes-2024.md", "# Notes\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "notes-2024.md\n");
+
+    let pattern = r"^RFC-(?<rfc_number>\d+)-";
+    let output = rfcs(&home, &["list", "--pattern", pattern]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "RFC-7-caching.md\nRFC-12-queues.md\n");
+    let output = rfcs(&home, &["--pattern", pattern, "next"]);
+    assert_eq!(stdout(&output), "013\n");
+
+    let output = rfcs(&home, &["list", "--pattern", r"^RFC-(\d+)"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("has no group named rfc_number"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_by_label() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-api.md", "---\nlabels: [API]\n---\n"),
+            ("002-both.md", "---\nlabels: [api, infra]\n---\n"),
+            ("003-infra.md", "---\ntags: infra\n---\n"),
+            ("004-none.md", "# Unlabelled\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    for (args, expected) in [
+        (&["--label", "api"][..], "001-api.md\n002-both.md\n"),
+        (&["--label", "infra"][..], "002-both.md\n003-infra.md\n"),
+        (&["--label", "api", "--label", "Infra"][..], "002-both.md\n"),
+        (
+            &["--label", "api", "--label", "infra", "--any-label"][..],
+            "001-api.md\n002-both.md\n003-infra.md\n",
+        ),
+        (&["--label", "process"][..], ""),
+    ] {
+        let output = rfcs(&home, &[&["list"][..], args].concat());
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), expected, "{:?}", args);
+    }
+}
+
+#[test]
+fn test_list_include_unnumbered() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", ""),
+            ("002-second.md", ""),
+            ("drafts/caching.md", "# Caching\n"),
+            ("drafts/dr.md", "---\nnumber: 9\n---\n# Dr\n"),
+            ("drafts/notes.png", ""),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "version = 1\ndrafts_dir = \"drafts\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["list"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "001-first.md\n002-second.md\n");
+
+    let output = rfcs(&home, &["list", "--include-unnumbered"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "001-first.md\n002-second.md\ndrafts/caching.md (number pending)\n\
+         drafts/dr.md (number pending)\n"
+    );
+
+    let output = rfcs(&home, &["list", "--include-unnumbered", "--format", "csv"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("\r\npending,,,drafts/caching.md\r\n"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn test_list_include_unnumbered_needs_drafts_dir() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--include-unnumbered"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("drafts_dir"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_list_duplicates() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", ""),
+            ("002-second.md", ""),
+            ("docs/002-other.md", ""),
+            ("003-third.md", ""),
+        ],
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    // The third RFC's own branch, and another RFC that took its number.
+    repo.branch("003-third", &head, false).unwrap();
+    repo.branch("003-fourth", &head, false).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--duplicates"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "002: 002-second.md, docs/002-other.md\n\
+         003: 003-third.md, branch 003-fourth\n"
+    );
+}
+
+#[test]
+fn test_list_duplicates_when_there_are_none() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", ""), ("002-second.md", "")]);
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("002-second", &head, false).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--duplicates"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn test_list_reads_yaml_and_toml_front_matter() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-yaml.md", "---\ntitle: First\nstatus: draft\n---\n"),
+            (
+                "002-toml.md",
+                "+++\ntitle = \"Second\"\nstatus = \"accepted\"\n+++\n",
+            ),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--format", "csv"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "number,title,status,path\r\n\
+         001,First,draft,001-yaml.md\r\n\
+         002,Second,accepted,002-toml.md\r\n"
+    );
+}
+
+#[test]
+fn test_list_summary() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-a.md", "---\nstatus: accepted\n---\n"),
+            ("002-b.md", "---\nstatus: draft\n---\n"),
+            ("003-c.md", "---\nstatus: accepted\n---\n"),
+            ("004-d.md", "# No front matter\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["list", "--summary"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "001-a.md\n002-b.md\n003-c.md\n004-d.md\n\
+         4 RFCs: 2 accepted, 1 draft, 1 without a status\n"
+    );
+
+    let output = rfcs(&home, &["list", "--status-counts-footer", "--to", "1"]);
+    assert_eq!(stdout(&output), "001-a.md\n1 RFC: 1 accepted\n");
+
+    for format in ["porcelain", "csv", "json"] {
+        let with = rfcs(&home, &["list", "--summary", "--format", format]);
+        let without = rfcs(&home, &["list", "--format", format]);
+        assert!(with.status.success(), "{}", stderr(&with));
+        assert_eq!(stdout(&with), stdout(&without));
+    }
+}
+
+#[test]
+fn test_list_authored_by_me() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("README.txt", "Proposals\n")]);
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Ada Lovelace").unwrap();
+    config.set_str("user.email", "ada@example.com").unwrap();
+    let ada = git2::Signature::now("Ada Lovelace", "ada@example.com").unwrap();
+    let bob = git2::Signature::now("Bob", "bob@example.com").unwrap();
+    write_files(&repo_path, &[("001-mine.md", "# Committed by Ada\n")]);
+    commit_all_as(&repo, "Add RFC 001", &ada);
+    write_files(&repo_path, &[("002-bobs.md", "# Committed by Bob\n")]);
+    commit_all_as(&repo, "Add RFC 002", &bob);
+    write_files(
+        &repo_path,
+        &[("003-written-for-me.md", "---\nauthor: Ada Lovelace\n---\n")],
+    );
+    commit_all_as(&repo, "Add RFC 003", &bob);
+    write_files(
+        &repo_path,
+        &[("004-merged-by-me.md", "---\nauthor: Bob\n---\n")],
+    );
+    commit_all_as(&repo, "Add RFC 004", &ada);
+    let home = home_for_repo(&repo_path);
+
+    for flag in ["--authored-by-me", "--mine"] {
+        let output = rfcs(&home, &["list", flag]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), "001-mine.md\n003-written-for-me.md\n");
+    }
+}
+
+#[test]
+fn test_list_table() {
+    let repo_path = temp_dir("repo");
+    let long = format!(
+        "---\ntitle: {}\nstatus: draft\n---\n",
+        "Long ".repeat(20).trim()
+    );
+    init_repo(
+        &repo_path,
+        &[
+            (
+                "001-caches.md",
+                "---\ntitle: Caches, and how to invalidate them\nstatus: accepted\n---\n",
+            ),
+            ("002-queues.md", "---\ntitle: Queues\nstatus: draft\n---\n"),
+            ("003-long.md", &long),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(
+        &home,
+        &["list", "--format", "table", "--max-title-width", "12"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "NUMBER  TITLE         STATUS    PATH\n\
+         001     Caches, and…  accepted  001-caches.md\n\
+         002     Queues        draft     002-queues.md\n\
+         003     Long Long L…  draft     003-long.md\n"
+    );
+
+    let output = rfcs(&home, &["list", "--format", "table", "--no-truncate"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout(&output).contains("001     Caches, and how to invalidate them "),
+        "{}",
+        stdout(&output)
+    );
+    assert!(
+        stdout(&output).contains(&format!("003     {}  draft", "Long ".repeat(20).trim())),
+        "{}",
+        stdout(&output)
+    );
+
+    // Not on a terminal, so fitted into 80 columns.
+    let output = rfcs(
+        &home,
+        &["list", "--format", "table", "--fields", "number,title"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    let table = stdout(&output);
+    assert!(
+        table.contains("001     Caches, and how to invalidate them\n"),
+        "{}",
+        table
+    );
+    let long_row = table.lines().last().unwrap();
+    assert_eq!(long_row.chars().count(), 80, "{}", table);
+    assert!(long_row.ends_with('…'), "{}", table);
+
+    let output = rfcs(&home, &["list", "--no-truncate"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_list_status_shorthands() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "---\nstatus: accepted\n---\n"),
+            ("002-second.md", "---\nstatus: draft\n---\n"),
+            ("003-third.md", "---\nstatus: Accepted\n---\n"),
+            ("004-fourth.md", "# No front matter\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let by_status = rfcs(&home, &["list", "--status", "accepted"]);
+    assert!(by_status.status.success(), "{}", stderr(&by_status));
+    assert_eq!(stdout(&by_status), "001-first.md\n003-third.md\n");
+    let accepted_only = rfcs(&home, &["list", "--accepted-only"]);
+    assert!(accepted_only.status.success(), "{}", stderr(&accepted_only));
+    assert_eq!(stdout(&accepted_only), stdout(&by_status));
+
+    let output = rfcs(&home, &["list", "--drafts-only"]);
+    assert_eq!(stdout(&output), "002-second.md\n");
+    let output = rfcs(
+        &home,
+        &["list", "--status", "draft", "--status", "accepted"],
+    );
+    assert_eq!(
+        stdout(&output),
+        "001-first.md\n002-second.md\n003-third.md\n"
+    );
+
+    let output = rfcs(&home, &["list", "--accepted-only", "--status", "draft"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_list_base_path() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("rfcs/001-first.md", "# First\n"),
+            ("docs/README.md", "# Docs\n"),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "version = 1\nrfc_dir = \"rfcs\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let docs = repo_path.join("docs");
+    let output = rfcs(&home, &["list", "--base-path", docs.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{}\n",
+            std::path::Path::new("..")
+                .join("rfcs")
+                .join("001-first.md")
+                .display()
+        )
+    );
+
+    // Relative to where rfcs runs.
+    let output = rfcs_in(&home, &docs, &["list", "--base-path", "."]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{}\n",
+            std::path::Path::new("..")
+                .join("rfcs")
+                .join("001-first.md")
+                .display()
+        )
+    );
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::*;
+
+#[test]
+fn test_log_follows_renames() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", "# First\n\nSome text that stays.\n")],
+    );
+    fs::write(
+        repo_path.join("001-first.md"),
+        "# First\n\nSome text that stays.\nMore.\n",
+    )
+    .unwrap();
+    commit_all(&repo, "Expand RFC 001");
+    fs::rename(
+        repo_path.join("001-first.md"),
+        repo_path.join("001-caches.md"),
+    )
+    .unwrap();
+    let mut index = repo.index().unwrap();
+    index.remove_path(Path::new("001-first.md")).unwrap();
+    index.write().unwrap();
+    commit_all(&repo, "Retitle RFC 001");
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["log", "1"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let summaries: Vec<String> = stdout(&output)
+        .lines()
+        .map(|line| line.splitn(3, ' ').nth(2).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        summaries,
+        [
+            "Retitle RFC 001",
+            "Expand RFC 001 (001-first.md)",
+            "Initial commit (001-first.md)",
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_merge_lands_rfc_and_marks_it_accepted() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_on_branch(
+        &repo,
+        "002-second",
+        &[("002-second.md", "---\nstatus: draft\n---\n# Second\n")],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["merge", "--yes", "2"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "main", "002-second.md").as_deref(),
+        Some("---\nstatus: accepted\n---\n# Second\n")
+    );
+    assert!(repo
+        .find_branch("002-second", git2::BranchType::Local)
+        .is_err());
+    assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+}
+
+#[test]
+fn test_merge_refuses_conflicts() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_on_branch(&repo, "002-second", &[("001-first.md", "# Theirs\n")]);
+    commit_on_branch(&repo, "main", &[("001-first.md", "# Ours\n")]);
+    repo.set_head("refs/heads/002-second").unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["merge", "--yes", "2"]);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("conflict in: 001-first.md"));
+    assert!(repo
+        .find_branch("002-second", git2::BranchType::Local)
+        .is_ok());
+    assert_eq!(repo.head().unwrap().shorthand(), Some("002-second"));
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("001-first.md")).unwrap(),
+        "# Theirs\n"
+    );
+}
+
+#[test]
+fn test_merge_without_yes_is_declined_when_not_interactive() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_on_branch(&repo, "002-second", &[("002-second.md", "# Second\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs_cmd(&home, &home, &["merge", "2"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Pass --yes"),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("002-second", git2::BranchType::Local)
+        .is_ok());
+    assert_eq!(file_on_branch(&repo, "main", "002-second.md"), None);
+}
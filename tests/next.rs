@@ -0,0 +1,116 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_next_with_and_without_untracked_files() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    write_files(&repo_path, &[("drafts/050-draft.md", "# Draft\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["next"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "051\n");
+
+    let output = rfcs(&home, &["next", "--include-untracked=false"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "002\n");
+}
+
+#[test]
+fn test_next_counts_branches() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    commit_on_branch(&repo, "007-in-flight", &[("notes.txt", "\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "008\n");
+}
+
+#[test]
+fn test_next_with_and_without_merged_branches() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    // Merged after being renumbered to 002.
+    let merged = commit_on_branch(&repo, "007-renumbered", &[("002-renumbered.md", "# Two\n")]);
+    repo.reference("refs/heads/main", merged, true, "fast-forward")
+        .unwrap();
+    switch_to(&repo, "refs/heads/main");
+    commit_on_branch(&repo, "005-open", &[("005-open.md", "# Open\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["next"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "008\n");
+
+    let output = rfcs(&home, &["next", "--exclude-merged"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "006\n");
+}
+
+#[test]
+fn test_next_counts_tags_with_prefix() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    for tag in ["rfc-042", "rfc-draft-2024", "release-100"] {
+        repo.tag_lightweight(tag, head.as_object(), false).unwrap();
+    }
+
+    let home = home_for_repo(&repo_path);
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "002\n");
+
+    let home = home_with_config(&format!(
+        "version = 1\n[repo]\npath = {:?}\n[git]\ntag_prefix = \"rfc-\"\n",
+        repo_path.display().to_string()
+    ));
+    let output = rfcs(&home, &["next"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "043\n");
+
+    let output = rfcs(&home, &["create", "--number", "42", "Reused"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("RFC number 042 is already in use by tag rfc-042."),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_next_counts_front_matter_numbers() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("notes/caching.md", "---\nnumber: 42\n---\n# Caching\n"),
+        ],
+    );
+
+    let home = home_for_repo(&repo_path);
+    let output = rfcs(&home, &["next"]);
+    assert_eq!(stdout(&output), "002\n");
+
+    let home = home_with_config(&format!(
+        "version = 1\nnumber_in_front_matter = true\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+    let output = rfcs(&home, &["next"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "043\n");
+
+    let output = rfcs(&home, &["list"]);
+    assert!(
+        stdout(&output).contains("caching.md"),
+        "{}",
+        stdout(&output)
+    );
+
+    let output = rfcs(&home, &["show", "42"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("# Caching"), "{}", stdout(&output));
+}
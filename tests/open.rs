@@ -0,0 +1,63 @@
+mod common;
+
+use common::*;
+
+/// A repository with RFC 001 merged, and RFC 002 on its own branch, whose
+/// origin is on GitHub.
+fn fixture() -> std::path::PathBuf {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("docs/001-first.md", "# First\n")]);
+    repo.remote("origin", "git@github.com:org/rfcs.git")
+        .unwrap();
+    commit_on_branch(&repo, "002-second", &[("docs/002-second.md", "# Second\n")]);
+    home_for_repo(&repo_path)
+}
+
+#[test]
+fn test_open_file_and_pr_urls() {
+    let home = fixture();
+
+    for (args, url) in [
+        (
+            &["open", "--print", "1"][..],
+            "https://github.com/org/rfcs/blob/main/docs/001-first.md",
+        ),
+        (
+            &["open", "--print", "2"][..],
+            "https://github.com/org/rfcs/pull/new/002-second",
+        ),
+        (
+            &["open", "--print", "--pr", "2"][..],
+            "https://github.com/org/rfcs/pull/new/002-second",
+        ),
+        (
+            &["open", "--print", "--file", "1"][..],
+            "https://github.com/org/rfcs/blob/main/docs/001-first.md",
+        ),
+    ] {
+        let output = rfcs(&home, args);
+        assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+        assert_eq!(stdout(&output), format!("{}\n", url), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_open_fails_without_what_to_open() {
+    let home = fixture();
+
+    let output = rfcs(&home, &["open", "--print", "--pr", "1"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("No branch found for RFC 001."),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = rfcs(&home, &["open", "--print", "--file", "2"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("RFC 002 isn't on the default branch yet"),
+        "{}",
+        stderr(&output)
+    );
+}
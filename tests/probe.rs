@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_probe_lists_refs_without_cloning() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("002-second", &head, false).unwrap();
+    let remote_path = temp_dir("remote");
+    git2::Repository::init_bare(&remote_path).unwrap();
+    repo.remote("origin", remote_path.to_str().unwrap())
+        .unwrap()
+        .push(
+            &[
+                "refs/heads/main:refs/heads/main",
+                "refs/heads/002-second:refs/heads/002-second",
+            ],
+            None,
+        )
+        .unwrap();
+    let home = home_with_config("version = 1\n");
+
+    let output = rfcs(&home, &["probe", remote_path.to_str().unwrap()]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let report = stdout(&output);
+    assert!(report.starts_with("Reached "), "{}", report);
+    assert!(
+        report.contains(&format!("{}\trefs/heads/main\n", head.id())),
+        "{}",
+        report
+    );
+    assert!(
+        report.contains(&format!("{}\trefs/heads/002-second\n", head.id())),
+        "{}",
+        report
+    );
+    assert!(!home.join(".config").join("rfcs").join("rfcs").exists());
+}
+
+#[test]
+fn test_probe_reports_unreachable_remotes() {
+    let home = home_with_config("version = 1\n");
+    let missing = temp_dir("remote").join("missing.git");
+
+    let output = rfcs(&home, &["probe", missing.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Can't reach"),
+        "{}",
+        stderr(&output)
+    );
+}
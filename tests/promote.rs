@@ -0,0 +1,182 @@
+mod common;
+
+use std::fs;
+
+use common::*;
+
+#[test]
+fn test_promote_valid_transition_commits() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", "---\nstatus: draft\n---\n# First\n")],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["promote", "--yes", "1", "in-review"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "main", "001-first.md").as_deref(),
+        Some("---\nstatus: in-review\n---\n# First\n")
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("Mark RFC 001 as in-review"));
+}
+
+#[test]
+fn test_promote_without_yes_is_declined_when_not_interactive() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", "---\nstatus: draft\n---\n# First\n")],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs_cmd(&home, &home, &["promote", "1", "in-review"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Not promoting 001-first.md. Pass --yes"),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("001-first.md")).unwrap(),
+        "---\nstatus: draft\n---\n# First\n"
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("Initial commit"));
+}
+
+#[test]
+fn test_promote_invalid_transition_is_rejected() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[("001-first.md", "---\nstatus: accepted\n---\n# First\n")],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["promote", "--yes", "1", "draft"]);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("An RFC that is accepted can't become draft, only superseded."),
+        "{}",
+        stderr(&output)
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("001-first.md")).unwrap(),
+        "---\nstatus: accepted\n---\n# First\n"
+    );
+}
+
+#[test]
+fn test_promote_uses_configured_transitions() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_with_config(&format!(
+        "[repo]\npath = {:?}\n\n[transitions]\ndraft = [\"final\"]\nfinal = []\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["promote", "--yes", "1", "in-review"]);
+    assert!(!output.status.success());
+
+    let output = rfcs(&home, &["promote", "--yes", "1", "final"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(fs::read_to_string(repo_path.join("001-first.md"))
+        .unwrap()
+        .starts_with("---\nstatus: final\n---\n"));
+}
+
+#[test]
+fn test_promote_several_rfcs_in_one_commit() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "# Second\n"),
+            ("003-third.md", "# Third\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["promote", "--yes", "1-2", "3", "in-review"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("Mark RFCs 001, 002, 003 as in-review"));
+}
+
+#[test]
+fn test_promote_several_rfcs_changes_none_if_one_is_rejected() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "---\nstatus: accepted\n---\n# Second\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["promote", "--yes", "all", "in-review"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("001-first.md")).unwrap(),
+        "# First\n"
+    );
+}
+
+#[test]
+fn test_promote_files_rfcs_into_status_dirs() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            (
+                "docs/001-first.md",
+                "---\nstatus: in-review\n---\n# First\n",
+            ),
+            (
+                "docs/002-second.md",
+                "---\nstatus: in-review\n---\n# Second\n",
+            ),
+        ],
+    );
+    let home = home_with_config(&format!(
+        "version = 1\nrfc_dir = \"docs\"\n[repo]\npath = {:?}\n\
+         [status_dirs]\naccepted = \"accepted\"\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["promote", "--yes", "1", "accepted"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).contains("Filed it as docs/accepted/001-first.md"));
+    assert_eq!(
+        file_on_branch(&repo, "main", "docs/accepted/001-first.md").as_deref(),
+        Some("---\nstatus: accepted\n---\n# First\n")
+    );
+    assert_eq!(file_on_branch(&repo, "main", "docs/001-first.md"), None);
+    assert!(!repo_path.join("docs/001-first.md").exists());
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("Mark RFC 001 as accepted"));
+
+    // Without a directory for rejected RFCs, they stay where they are.
+    let output = rfcs(&home, &["promote", "--yes", "2", "rejected"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(repo_path.join("docs/002-second.md").is_file());
+
+    let output = rfcs(&home, &["list"]);
+    assert_eq!(
+        stdout(&output),
+        "docs/accepted/001-first.md\ndocs/002-second.md\n"
+    );
+}
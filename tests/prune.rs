@@ -0,0 +1,101 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::*;
+
+#[test]
+fn test_prune_deletes_only_merged_rfc_branches() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let merged = commit_on_branch(&repo, "002-merged", &[("002-merged.md", "# Merged\n")]);
+    repo.reference("refs/heads/main", merged, true, "fast-forward")
+        .unwrap();
+    switch_to(&repo, "refs/heads/main");
+    commit_on_branch(&repo, "003-open", &[("003-open.md", "# Open\n")]);
+    commit_on_branch(&repo, "notes", &[]);
+    // Merged, but checked out.
+    let tip = repo.find_commit(merged).unwrap();
+    repo.branch("004-current", &tip, false).unwrap();
+    switch_to(&repo, "refs/heads/004-current");
+    let home = home_for_repo(&repo_path);
+    let exists = |branch: &str| repo.find_branch(branch, git2::BranchType::Local).is_ok();
+
+    let output = rfcs(&home, &["prune"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Not deleting 002-merged."),
+        "{}",
+        stderr(&output)
+    );
+    assert!(exists("002-merged"));
+
+    let output = rfcs(&home, &["prune", "--yes"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "Deleted branch 002-merged\n");
+    assert!(!exists("002-merged"));
+    assert!(exists("003-open"));
+    assert!(exists("notes"));
+    assert!(exists("main"));
+    assert!(exists("004-current"));
+
+    let output = rfcs(&home, &["prune"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "No merged RFC branches to prune.\n");
+}
+
+/// A repo with the merged RFC branch 002-merged, and a config with `extra`
+/// at the top level.
+fn repo_with_merged_branch(extra: &str) -> (git2::Repository, PathBuf) {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let merged = commit_on_branch(&repo, "002-merged", &[("002-merged.md", "# Merged\n")]);
+    repo.reference("refs/heads/main", merged, true, "fast-forward")
+        .unwrap();
+    switch_to(&repo, "refs/heads/main");
+    let home = home_with_config(&format!(
+        "version = 1\n{}[repo]\npath = {:?}\n",
+        extra,
+        repo_path.display().to_string()
+    ));
+    (repo, home)
+}
+
+#[test]
+fn test_non_tty_allow_confirms_without_asking() {
+    let (repo, home) = repo_with_merged_branch("non_tty = \"allow\"\n");
+
+    let output = rfcs(&home, &["prune"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Delete the merged RFC branches 002-merged? Yes, stdin isn't a terminal.\n\
+         Deleted branch 002-merged\n"
+    );
+    assert!(repo
+        .find_branch("002-merged", git2::BranchType::Local)
+        .is_err());
+}
+
+#[test]
+fn test_assume_yes_on_nontty_overrides_non_tty_deny() {
+    let (repo, home) = repo_with_merged_branch("non_tty = \"deny\"\n");
+
+    let output = rfcs(&home, &["prune"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("Not deleting 002-merged."),
+        "{}",
+        stderr(&output)
+    );
+    assert!(repo
+        .find_branch("002-merged", git2::BranchType::Local)
+        .is_ok());
+
+    let output = rfcs(&home, &["prune", "--assume-yes-on-nontty"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).ends_with("Deleted branch 002-merged\n"));
+    assert!(repo
+        .find_branch("002-merged", git2::BranchType::Local)
+        .is_err());
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_reconcile_pairs_branches_with_files() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-paired.md", "# Paired\n"),
+        ],
+    );
+    commit_on_branch(&repo, "002-paired", &[]);
+    commit_on_branch(&repo, "003-open", &[("003-open.md", "# Open\n")]);
+    commit_on_branch(&repo, "004-stalled", &[]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["reconcile"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Branches without an RFC file:\n  004-stalled\n\
+         RFC files without a branch:\n  001-first.md\n"
+    );
+}
+
+#[test]
+fn test_reconcile_without_mismatches() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("README.md", "Proposals\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["reconcile"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Branches without an RFC file:\n  none\nRFC files without a branch:\n  none\n"
+    );
+}
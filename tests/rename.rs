@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_rename_title_from_file() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[(
+            "002-Old-title.md",
+            "---\ntitle: Caches, revisited\n---\n# Old title\n",
+        )],
+    );
+    commit_on_branch(&repo, "002-Old-title", &[("notes.txt", "\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["rename", "--title-from-file", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Renamed 002-Old-title.md to 002-Caches-revisited.md\n\
+         Renamed branch 002-Old-title to 002-Caches-revisited\n"
+    );
+    assert!(!repo_path.join("002-Old-title.md").exists());
+    assert_eq!(
+        file_on_branch(&repo, "main", "002-Caches-revisited.md").as_deref(),
+        Some("---\ntitle: Caches, revisited\n---\n# Old title\n")
+    );
+    assert_eq!(file_on_branch(&repo, "main", "002-Old-title.md"), None);
+    assert!(repo
+        .find_branch("002-Caches-revisited", git2::BranchType::Local)
+        .is_ok());
+    assert!(repo
+        .find_branch("002-Old-title", git2::BranchType::Local)
+        .is_err());
+
+    let output = rfcs(&home, &["rename", "--title-from-file", "2"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "002-Caches-revisited.md is already named 002-Caches-revisited\n"
+    );
+}
+
+#[test]
+fn test_rename_to_a_new_title() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-First.md", "---\ntitle: First\n---\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["rename", "1", "The first one"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        file_on_branch(&repo, "main", "001-The-first-one.md").as_deref(),
+        Some("---\ntitle: The first one\n---\n")
+    );
+
+    let output = rfcs(&home, &["rename", "1"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_rename_cases_slugs_as_configured() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-First.md", "# Caches, Revisited\n")]);
+    let home = home_with_config(&format!(
+        "version = 1\n[numbering]\ncase = \"kebab\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["rename", "--title-from-file", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(file_on_branch(&repo, "main", "001-caches-revisited.md").is_some());
+}
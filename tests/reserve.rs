@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_reserve_and_release() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["reserve"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "003\n");
+    let output = rfcs(&home, &["reserve", "4"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "004\n");
+    assert_eq!(stdout(&rfcs(&home, &["next"])), "005\n");
+    assert!(!rfcs(&home, &["reserve", "4"]).status.success());
+
+    let output = rfcs(&home, &["reserve", "--release", "4"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "Released RFC number 004\n");
+    assert_eq!(stdout(&rfcs(&home, &["next"])), "004\n");
+    let output = rfcs(&home, &["reserve", "--release", "4"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("RFC number 004 isn't reserved."),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_reserve_refuses_to_release_a_used_number() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    assert_eq!(stdout(&rfcs(&home, &["reserve", "10"])), "010\n");
+    let output = rfcs(
+        &home,
+        &["create", "--number", "10", "--no-checkout", "Announced"],
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(repo
+        .find_branch("010-Announced", git2::BranchType::Local)
+        .is_ok());
+
+    let output = rfcs(&home, &["reserve", "--release", "10"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("RFC number 010 is used by branch 010-Announced by now"),
+        "{}",
+        stderr(&output)
+    );
+}
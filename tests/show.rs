@@ -0,0 +1,227 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_show_by_number_and_path() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("docs/001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    for target in ["1", "docs/001-first.md"] {
+        let output = rfcs(&home, &["show", target]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), "# First\n");
+    }
+}
+
+#[test]
+fn test_show_metadata() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[(
+            "007-caches.md",
+            "---\ntitle: \"Caches: bad\"\nstatus: in-review\nauthor: Ada\ncreated: 2024-01-02\n---\n# Caches\n",
+        )],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["show", "7", "--metadata"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "number: 007\ntitle: Caches: bad\nstatus: in-review\nauthor: Ada\ncreated: 2024-01-02\n"
+    );
+
+    let output = rfcs(&home, &["show", "7", "--metadata", "--json"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "{\n  \"author\": \"Ada\",\n  \"created\": \"2024-01-02\",\n  \"number\": 7,\n  \
+         \"status\": \"in-review\",\n  \"title\": \"Caches: bad\"\n}\n"
+    );
+}
+
+#[test]
+fn test_show_by_title_fragment() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            (
+                "001-caches.md",
+                "---\ntitle: Caches are bad, actually\n---\nCaches\n",
+            ),
+            (
+                "002-cache-eviction.md",
+                "---\ntitle: Evicting from caches\n---\nEviction\n",
+            ),
+            ("003-queues.md", "---\ntitle: Queues\n---\nQueues\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["show", "queue"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "---\ntitle: Queues\n---\nQueues\n");
+
+    let output = rfcs(&home, &["show", "evict"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output).ends_with("Eviction\n"));
+
+    let output = rfcs(&home, &["show", "caches"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains(&format!(
+            "'caches' matches more than one RFC: {}, {}",
+            repo_path.join("001-caches.md").display(),
+            repo_path.join("002-cache-eviction.md").display()
+        )),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = rfcs(&home, &["show", "kafka"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("No RFC's title matches 'kafka' either."),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_show_large_rfc() {
+    let content = format!(
+        "---\ntitle: Large\n---\n{}The end.\n",
+        "All work and no play.\n".repeat(200_000)
+    );
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-large.md", &content)]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["show", "large"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout(&output) == content);
+}
+
+#[test]
+fn test_show_at_revision() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", "---\nstatus: draft\n---\n# First\n")],
+    );
+    let first = repo
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .id()
+        .to_string();
+    repo.tag_lightweight("accepted", &repo.revparse_single("HEAD").unwrap(), false)
+        .unwrap();
+    write_files(
+        &repo_path,
+        &[
+            (
+                "001-first.md",
+                "---\nstatus: withdrawn\n---\n# First, revised\n",
+            ),
+            ("002-second.md", "# Second\n"),
+        ],
+    );
+    commit_all(&repo, "Revise");
+    let home = home_for_repo(&repo_path);
+
+    for target in ["1", "001-first.md"] {
+        let output = rfcs(&home, &["show", "--at", "accepted", target]);
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), "---\nstatus: draft\n---\n# First\n");
+    }
+    let output = rfcs(&home, &["show", "--at", &first, "--metadata", "1"]);
+    assert_eq!(stdout(&output), "number: 001\nstatus: draft\n");
+    let output = rfcs(&home, &["show", "1"]);
+    assert_eq!(
+        stdout(&output),
+        "---\nstatus: withdrawn\n---\n# First, revised\n"
+    );
+
+    let output = rfcs(&home, &["show", "--at", "accepted", "2"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("There was no RFC numbered 002 at accepted."),
+        "{}",
+        stderr(&output)
+    );
+    let output = rfcs(&home, &["show", "--at", "accepted", "002-second.md"]);
+    assert!(stderr(&output).contains("There was no file at 002-second.md at accepted."));
+    let output = rfcs(&home, &["show", "--at", "nonsense", "1"]);
+    assert!(stderr(&output).contains("Can't find revision 'nonsense'"));
+}
+
+#[test]
+fn test_show_raw_bytes_keeps_invalid_utf8() {
+    let repo_path = temp_dir("repo");
+    let content = b"# Diagram\n\xff\xd8\xff\xe0 caf\xc3\xa9\n";
+    init_repo(&repo_path, &[]);
+    std::fs::write(repo_path.join("001-diagram.md"), content).unwrap();
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["show", "--raw-bytes", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(output.stdout, content);
+    assert_eq!(stderr(&output), "");
+
+    let output = rfcs(&home, &["show", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "# Diagram\n\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD} café\n"
+    );
+    assert!(
+        stderr(&output).contains(
+            "001-diagram.md isn't valid UTF-8, the invalid bytes were replaced. \
+             Pass --raw-bytes to print it as it is."
+        ),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_show_range_notes_gaps() {
+    let repo_path = temp_dir("repo");
+    init_repo(
+        &repo_path,
+        &[
+            ("001-first.md", "# First\n"),
+            ("002-second.md", "---\ntitle: Second\n---\nText\n"),
+            ("005-fifth.md", "# Fifth\n"),
+            ("007-seventh.md", "# Seventh\n"),
+        ],
+    );
+    let home = home_for_repo(&repo_path);
+    let rule = "-".repeat(72);
+
+    let output = rfcs(&home, &["show", "--range", "2..6"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "RFC 002: Second\n\n---\ntitle: Second\n---\nText\n\
+             \n{rule}\n\n\
+             (There are no RFCs 003 to 004.)\n\
+             \n{rule}\n\n\
+             RFC 005: Fifth\n\n# Fifth\n\
+             \n{rule}\n\n\
+             (There is no RFC 006.)\n"
+        )
+    );
+
+    let output = rfcs(&home, &["show", "--range", "8..9"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("There is no RFC numbered 008 to 009."));
+}
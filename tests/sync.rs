@@ -0,0 +1,83 @@
+mod common;
+
+use std::path::Path;
+
+use common::*;
+
+/// A bare clone of `source` at a new temp path.
+fn bare_clone(source: &Path) -> (std::path::PathBuf, git2::Repository) {
+    let path = temp_dir("remote");
+    let repo = git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(source.to_str().unwrap(), &path)
+        .unwrap();
+    (path, repo)
+}
+
+fn add_branch(repo: &git2::Repository, name: &str) {
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch(name, &head, false).unwrap();
+}
+
+fn has_remote_branch(repo: &git2::Repository, name: &str) -> bool {
+    repo.find_branch(name, git2::BranchType::Remote).is_ok()
+}
+
+#[test]
+fn test_sync_fetches_and_prunes() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let (origin_path, origin) = bare_clone(&repo_path);
+    let (upstream_path, upstream) = bare_clone(&repo_path);
+    repo.remote("origin", origin_path.to_str().unwrap())
+        .unwrap();
+    repo.remote("upstream", upstream_path.to_str().unwrap())
+        .unwrap();
+    add_branch(&origin, "002-second");
+    add_branch(&upstream, "003-third");
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["sync"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Fetched origin: 2 new, 0 updated, 0 pruned\n  \
+         new      origin/002-second\n  \
+         new      origin/main\n"
+    );
+    assert!(has_remote_branch(&repo, "origin/002-second"));
+    assert!(!has_remote_branch(&repo, "upstream/003-third"));
+
+    origin
+        .find_branch("002-second", git2::BranchType::Local)
+        .unwrap()
+        .delete()
+        .unwrap();
+    let output = rfcs(&home, &["sync", "--all"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "Fetched origin: 0 new, 0 updated, 1 pruned\n  \
+         pruned   origin/002-second\n\
+         Fetched upstream: 2 new, 0 updated, 0 pruned\n  \
+         new      upstream/003-third\n  \
+         new      upstream/main\n"
+    );
+    assert!(!has_remote_branch(&repo, "origin/002-second"));
+    assert!(has_remote_branch(&repo, "upstream/003-third"));
+}
+
+#[test]
+fn test_sync_without_remotes_fails() {
+    let repo_path = temp_dir("repo");
+    init_repo(&repo_path, &[("001-first.md", "# First\n")]);
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["sync", "--all"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("has no remotes to fetch from"));
+
+    let output = rfcs(&home, &["sync"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("Can't find remote 'origin'"));
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+
+const RFC: &str =
+    "---\ntitle: First\nupdated: 2001-01-01\n---\n# First\n\nupdated: not front matter\n";
+
+#[test]
+fn test_touch_only_changes_the_date() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(
+        &repo_path,
+        &[("001-first.md", RFC), ("002-second.md", "Body\n")],
+    );
+    let home = home_for_repo(&repo_path);
+
+    let output = rfcs(&home, &["touch", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let touched = file_on_branch(&repo, "main", "001-first.md").unwrap();
+    let lines: Vec<&str> = touched.lines().collect();
+    let expected: Vec<&str> = RFC.lines().collect();
+    assert_eq!(lines.len(), expected.len());
+    for (i, (line, original)) in lines.iter().zip(&expected).enumerate() {
+        if i == 2 {
+            assert!(line.starts_with("updated: 20"), "{}", line);
+            assert_ne!(line, original);
+        } else {
+            assert_eq!(line, original);
+        }
+    }
+
+    let output = rfcs(&home, &["touch", "002-second.md"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let touched = file_on_branch(&repo, "main", "002-second.md").unwrap();
+    assert!(touched.starts_with("---\nupdated: 20"));
+    assert!(touched.ends_with("\n---\nBody\n"));
+}
+
+#[test]
+fn test_touch_uses_date_format() {
+    let repo_path = temp_dir("repo");
+    let repo = init_repo(&repo_path, &[("001-first.md", RFC)]);
+    let home = home_with_config(&format!(
+        "version = 1\ndate_format = \"%Y%m%d\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+
+    let output = rfcs(&home, &["touch", "1"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let touched = file_on_branch(&repo, "main", "001-first.md").unwrap();
+    let date = touched
+        .lines()
+        .nth(2)
+        .unwrap()
+        .strip_prefix("updated: ")
+        .unwrap();
+    assert_eq!(date.len(), 8, "{}", date);
+    assert!(date.starts_with("20") && date.chars().all(|c| c.is_ascii_digit()));
+
+    let broken = home_with_config(&format!(
+        "version = 1\ndate_format = \"%Y-%Q\"\n[repo]\npath = {:?}\n",
+        repo_path.display().to_string()
+    ));
+    let output = rfcs(&broken, &["touch", "1"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("'%Y-%Q' is not a date format"),
+        "{}",
+        stderr(&output)
+    );
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn test_version_reports_libgit2() {
+    // A broken config doesn't get in the way of a bug report.
+    let home = home_with_config("not = [valid toml");
+
+    let output = rfcs(&home, &["version"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        format!("rfcs {}\n", env!("CARGO_PKG_VERSION"))
+    );
+
+    let output = rfcs(&home, &["version", "--verbose"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let (major, minor, patch) = git2::Version::get().libgit2_version();
+    let out = stdout(&output);
+    assert!(
+        out.contains(&format!("\nlibgit2 {}.{}.{}", major, minor, patch)),
+        "{}",
+        out
+    );
+    assert!(out.contains("\nHTTPS: "), "{}", out);
+    assert!(out.contains("\nSSH: "), "{}", out);
+}